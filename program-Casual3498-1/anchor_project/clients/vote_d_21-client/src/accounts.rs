@@ -0,0 +1,74 @@
+use anchor_lang::{AccountDeserialize, Result};
+use vote_d_21::state::{CandidateAccount, Election, Results, VoterAccount};
+
+/// Decode a program account from its raw data, discriminator included.
+/// Fails if the discriminator does not match `T`.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_election(data: &[u8]) -> Result<Election> {
+    decode(data)
+}
+
+pub fn decode_candidate(data: &[u8]) -> Result<CandidateAccount> {
+    decode(data)
+}
+
+pub fn decode_voter(data: &[u8]) -> Result<VoterAccount> {
+    decode(data)
+}
+
+pub fn decode_results(data: &[u8]) -> Result<Results> {
+    decode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AccountSerialize;
+    use vote_d_21::state::CandidateStatus;
+
+    fn candidate() -> CandidateAccount {
+        CandidateAccount {
+            version: CandidateAccount::VERSION,
+            election: Pubkey::new_unique(),
+            name: String::from("Alice"),
+            description: String::from("About Alice"),
+            uri: String::new(),
+            owner: Pubkey::new_unique(),
+            status: CandidateStatus::Approved,
+            votes: 12,
+            negative_votes: 3,
+            score_sum: 0,
+            payer: Pubkey::new_unique(),
+            bump: 254,
+        }
+    }
+
+    #[test]
+    fn test_decode_round_trips_program_accounts() {
+        let original = candidate();
+        let mut data = Vec::new();
+        original.try_serialize(&mut data).unwrap();
+        // Accounts are allocated at full size; trailing zeroes are ignored
+        data.resize(CandidateAccount::SPACE, 0);
+
+        let decoded = decode_candidate(&data).unwrap();
+        assert_eq!(decoded.election, original.election);
+        assert_eq!(decoded.name, original.name);
+        assert_eq!((decoded.votes, decoded.negative_votes), (12, 3));
+        assert_eq!(decoded.status, CandidateStatus::Approved);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_accounts() {
+        let mut data = Vec::new();
+        candidate().try_serialize(&mut data).unwrap();
+
+        assert!(decode_voter(&data).is_err());
+        assert!(decode_election(&data).is_err());
+        assert!(decode_candidate(&data[..4]).is_err());
+    }
+}
@@ -0,0 +1,495 @@
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use vote_d_21::state::{Ballot, SignedBallot};
+use vote_d_21::ID;
+
+use crate::pda::{
+    find_candidate_pda, find_config_pda, find_legacy_candidate_pda, find_legacy_voter_pda, find_program_data_pda,
+    find_sponsor_budget_pda, find_voter_pda, find_whitelist_pda, INSTRUCTIONS_SYSVAR_ID,
+};
+
+/// Create the program config naming `emergency_authority`. `authority` must
+/// be the program's upgrade authority and pays for the account.
+pub fn initialize_config(authority: &Pubkey, emergency_authority: &Pubkey) -> Instruction {
+    let accounts = vote_d_21::accounts::InitializeConfig {
+        config: find_config_pda().0,
+        authority: *authority,
+        program: ID,
+        program_data: find_program_data_pda().0,
+        system_program: system_program::ID,
+    };
+    let data = vote_d_21::instruction::InitializeConfig { emergency_authority: *emergency_authority };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Halt registration and voting; signed by the emergency authority.
+pub fn pause(emergency_authority: &Pubkey) -> Instruction {
+    set_paused(emergency_authority, vote_d_21::instruction::Pause {}.data())
+}
+
+/// Resume registration and voting; signed by the emergency authority.
+pub fn unpause(emergency_authority: &Pubkey) -> Instruction {
+    set_paused(emergency_authority, vote_d_21::instruction::Unpause {}.data())
+}
+
+fn set_paused(emergency_authority: &Pubkey, data: Vec<u8>) -> Instruction {
+    let accounts = vote_d_21::accounts::SetPaused {
+        config: find_config_pda().0,
+        emergency_authority: *emergency_authority,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data,
+    }
+}
+
+/// Register candidate `name` in `election`. `authority` must be the
+/// election's admin or a registrar; `payer` funds the candidate account.
+pub fn initialize_candidate(
+    election: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    name: &str,
+    description: &str,
+    uri: &str,
+) -> Instruction {
+    let accounts = vote_d_21::accounts::InitializeCandidate {
+        election: *election,
+        candidate: find_candidate_pda(election, name).0,
+        config: find_config_pda().0,
+        authority: *authority,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    let data = vote_d_21::instruction::InitializeCandidate {
+        name: name.to_string(),
+        description: description.to_string(),
+        uri: uri.to_string(),
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// How a voter proves they may register in an election
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Eligibility {
+    /// Pass the voter's whitelist entry, for `EligibilityMode::Whitelist`
+    pub whitelisted: bool,
+    /// Merkle proof, for `EligibilityMode::MerkleRoot`
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Register `authority` as a voter in `election`; `payer` funds the voter account.
+pub fn initialize_voter(
+    election: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    eligibility: Eligibility,
+) -> Instruction {
+    register_voter(election, authority, payer, eligibility, None)
+}
+
+/// Register `authority` as a voter in `election` with `relayer` paying the
+/// rent and fee, reimbursed from the election's sponsor budget.
+pub fn sponsored_initialize_voter(
+    election: &Pubkey,
+    authority: &Pubkey,
+    relayer: &Pubkey,
+    eligibility: Eligibility,
+) -> Instruction {
+    let budget = find_sponsor_budget_pda(election).0;
+    register_voter(election, authority, relayer, eligibility, Some(budget))
+}
+
+fn register_voter(
+    election: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    eligibility: Eligibility,
+    sponsor_budget: Option<Pubkey>,
+) -> Instruction {
+    let accounts = vote_d_21::accounts::InitializeVoter {
+        election: *election,
+        voter: find_voter_pda(election, authority).0,
+        config: find_config_pda().0,
+        whitelist_entry: eligibility
+            .whitelisted
+            .then(|| find_whitelist_pda(election, authority).0),
+        sponsor_budget,
+        authority: *authority,
+        payer: *payer,
+        system_program: system_program::ID,
+    };
+    let data = vote_d_21::instruction::InitializeVoter { proof: eligibility.proof };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Cast `ballot` in `election` as `authority`. The ballot's candidate
+/// accounts are appended, writable, in ballot order.
+pub fn vote(election: &Pubkey, authority: &Pubkey, ballot: Ballot) -> Instruction {
+    cast_vote(election, authority, ballot, None)
+}
+
+/// Cast `ballot` in `election` as `authority`, with `relayer` paying the fee
+/// and being reimbursed from the election's sponsor budget.
+pub fn sponsored_vote(election: &Pubkey, authority: &Pubkey, relayer: &Pubkey, ballot: Ballot) -> Instruction {
+    cast_vote(election, authority, ballot, Some(relayer))
+}
+
+fn cast_vote(election: &Pubkey, authority: &Pubkey, ballot: Ballot, relayer: Option<&Pubkey>) -> Instruction {
+    let accounts = vote_d_21::accounts::Vote {
+        election: *election,
+        voter: find_voter_pda(election, authority).0,
+        config: find_config_pda().0,
+        authority: *authority,
+        sponsor_budget: relayer.map(|_| find_sponsor_budget_pda(election).0),
+        relayer: relayer.copied(),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(ballot.candidates().map(|key| AccountMeta::new(*key, false)));
+    Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: vote_d_21::instruction::Vote { ballot }.data(),
+    }
+}
+
+/// Ed25519 program instruction verifying each ballot's `signature` by its
+/// authority over `SignedBallot::message`. Keys, signatures and messages are
+/// all stored in the instruction itself, as `vote_signed_batch` requires.
+pub fn verify_signed_ballots(election: &Pubkey, ballots: &[(SignedBallot, [u8; 64])]) -> Instruction {
+    // Signature count and padding, then one 14 byte offsets entry per signature
+    let mut data = vec![ballots.len() as u8, 0];
+    let mut payload = Vec::new();
+    let payload_start = 2 + 14 * ballots.len();
+    for (signed, signature) in ballots {
+        let message = signed.message(election);
+        let key_offset = payload_start + payload.len();
+        let signature_offset = key_offset + 32;
+        let message_offset = signature_offset + 64;
+        for field in [
+            signature_offset as u16,
+            u16::MAX,
+            key_offset as u16,
+            u16::MAX,
+            message_offset as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        payload.extend_from_slice(signed.authority.as_ref());
+        payload.extend_from_slice(signature);
+        payload.extend_from_slice(&message);
+    }
+    data.extend_from_slice(&payload);
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+/// Cast ballots signed off-chain in `election`: the Ed25519 verification of
+/// their signatures followed by `vote_signed_batch`, to be sent in this order
+/// in one transaction by any fee payer. Each ballot's voter account and
+/// candidate accounts are appended, writable, in ballot order.
+pub fn vote_signed_batch(election: &Pubkey, ballots: &[(SignedBallot, [u8; 64])]) -> Vec<Instruction> {
+    let accounts = vote_d_21::accounts::VoteSignedBatch {
+        election: *election,
+        config: find_config_pda().0,
+        instructions: INSTRUCTIONS_SYSVAR_ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for (signed, _) in ballots {
+        metas.push(AccountMeta::new(find_voter_pda(election, &signed.authority).0, false));
+        metas.extend(signed.ballot.candidates().map(|key| AccountMeta::new(*key, false)));
+    }
+    let data = vote_d_21::instruction::VoteSignedBatch {
+        ballots: ballots.iter().map(|(signed, _)| signed.clone()).collect(),
+    };
+    let batch = Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: data.data(),
+    };
+    vec![verify_signed_ballots(election, ballots), batch]
+}
+
+/// Create the sponsor budget of `election`, reimbursing `relayer` for rent
+/// and `fee_per_transaction` lamports per sponsored transaction. Admin only.
+pub fn initialize_sponsor_budget(
+    election: &Pubkey,
+    admin: &Pubkey,
+    relayer: &Pubkey,
+    fee_per_transaction: u64,
+) -> Instruction {
+    let accounts = vote_d_21::accounts::InitializeSponsorBudget {
+        election: *election,
+        sponsor_budget: find_sponsor_budget_pda(election).0,
+        admin: *admin,
+        system_program: system_program::ID,
+    };
+    let data = vote_d_21::instruction::InitializeSponsorBudget { relayer: *relayer, fee_per_transaction };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Deposit `amount` lamports from `funder` into the sponsor budget of `election`.
+pub fn fund_sponsor_budget(election: &Pubkey, funder: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vote_d_21::accounts::FundSponsorBudget {
+        sponsor_budget: find_sponsor_budget_pda(election).0,
+        funder: *funder,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: vote_d_21::instruction::FundSponsorBudget { amount }.data(),
+    }
+}
+
+/// Return `amount` unspent lamports of the sponsor budget of `election` to `sponsor`.
+pub fn withdraw_sponsor_budget(election: &Pubkey, sponsor: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vote_d_21::accounts::WithdrawSponsorBudget {
+        sponsor_budget: find_sponsor_budget_pda(election).0,
+        sponsor: *sponsor,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: vote_d_21::instruction::WithdrawSponsorBudget { amount }.data(),
+    }
+}
+
+/// Move `authority`'s voter account from the first deployment into
+/// `election`. `candidates` are the migrated accounts of the candidates its
+/// ballot voted for, in ballot order, and empty if it never voted. The admin
+/// pays for the new account.
+pub fn migrate_voter(election: &Pubkey, admin: &Pubkey, authority: &Pubkey, candidates: &[Pubkey]) -> Instruction {
+    let accounts = vote_d_21::accounts::MigrateVoter {
+        election: *election,
+        legacy_voter: find_legacy_voter_pda(authority).0,
+        authority: *authority,
+        voter: find_voter_pda(election, authority).0,
+        config: find_config_pda().0,
+        admin: *admin,
+        system_program: system_program::ID,
+    };
+    let mut accounts = accounts.to_account_metas(None);
+    accounts.extend(candidates.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: vote_d_21::instruction::MigrateVoter {}.data(),
+    }
+}
+
+/// Move the candidate `name` from the first deployment into `election`.
+/// The admin pays for the new account.
+pub fn migrate_candidate(election: &Pubkey, admin: &Pubkey, name: &str) -> Instruction {
+    let accounts = vote_d_21::accounts::MigrateCandidate {
+        election: *election,
+        legacy_candidate: find_legacy_candidate_pda(name).0,
+        candidate: find_candidate_pda(election, name).0,
+        config: find_config_pda().0,
+        admin: *admin,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: vote_d_21::instruction::MigrateCandidate { name: name.to_string() }.data(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+
+    fn meta(ix: &Instruction, i: usize) -> (Pubkey, bool, bool) {
+        let meta = &ix.accounts[i];
+        (meta.pubkey, meta.is_signer, meta.is_writable)
+    }
+
+    #[test]
+    fn test_initialize_candidate_builder() {
+        let (election, authority, payer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = initialize_candidate(&election, &authority, &payer, "Alice", "About Alice", "https://a.example");
+
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(meta(&ix, 0), (election, false, true));
+        assert_eq!(meta(&ix, 1), (find_candidate_pda(&election, "Alice").0, false, true));
+        assert_eq!(meta(&ix, 2), (find_config_pda().0, false, false));
+        assert_eq!(meta(&ix, 3), (authority, true, false));
+        assert_eq!(meta(&ix, 4), (payer, true, true));
+        assert_eq!(meta(&ix, 5), (system_program::ID, false, false));
+
+        assert_eq!(ix.data[..8], *vote_d_21::instruction::InitializeCandidate::DISCRIMINATOR);
+        let args = vote_d_21::instruction::InitializeCandidate::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!((args.name.as_str(), args.uri.as_str()), ("Alice", "https://a.example"));
+    }
+
+    #[test]
+    fn test_initialize_voter_optional_accounts() {
+        let (election, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Unused optional accounts are passed as the program id
+        let open = initialize_voter(&election, &authority, &authority, Eligibility::default());
+        assert_eq!(meta(&open, 1), (find_voter_pda(&election, &authority).0, false, true));
+        assert_eq!(open.accounts[2].pubkey, find_config_pda().0);
+        assert_eq!(open.accounts[3].pubkey, ID);
+        assert_eq!(open.accounts[4].pubkey, ID);
+
+        let listed = initialize_voter(
+            &election,
+            &authority,
+            &authority,
+            Eligibility { whitelisted: true, proof: vec![[1; 32]] },
+        );
+        assert_eq!(listed.accounts[3].pubkey, find_whitelist_pda(&election, &authority).0);
+
+        let args = vote_d_21::instruction::InitializeVoter::try_from_slice(&listed.data[8..]).unwrap();
+        assert_eq!(args.proof, vec![[1; 32]]);
+    }
+
+    #[test]
+    fn test_vote_appends_candidates_in_ballot_order() {
+        let (election, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ballot = Ballot { positive: vec![a, b], negative: vec![c], scores: Vec::new() };
+
+        let ix = vote(&election, &authority, ballot.clone());
+
+        assert_eq!(ix.accounts.len(), 9);
+        assert_eq!(meta(&ix, 0), (election, false, true));
+        assert_eq!(meta(&ix, 2), (find_config_pda().0, false, false));
+        assert_eq!(meta(&ix, 3), (authority, true, false));
+        let candidates: Vec<(Pubkey, bool, bool)> = (6..9).map(|i| meta(&ix, i)).collect();
+        assert_eq!(candidates, vec![(a, false, true), (b, false, true), (c, false, true)]);
+
+        assert_eq!(ix.data[..8], *vote_d_21::instruction::Vote::DISCRIMINATOR);
+        let args = vote_d_21::instruction::Vote::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.ballot, ballot);
+    }
+
+    #[test]
+    fn test_pause_builders() {
+        let authority = Pubkey::new_unique();
+        let ix = initialize_config(&authority, &authority);
+        assert_eq!(meta(&ix, 0), (find_config_pda().0, false, true));
+        assert_eq!(meta(&ix, 1), (authority, true, true));
+        assert_eq!(meta(&ix, 2), (ID, false, false));
+        assert_eq!(meta(&ix, 3).0, find_program_data_pda().0);
+
+        let (pause, unpause) = (pause(&authority), unpause(&authority));
+        assert_eq!(pause.accounts, unpause.accounts);
+        assert_eq!(meta(&pause, 0), (find_config_pda().0, false, true));
+        assert_eq!(meta(&pause, 1), (authority, true, false));
+        assert_eq!(pause.data, vote_d_21::instruction::Pause::DISCRIMINATOR);
+        assert_eq!(unpause.data, vote_d_21::instruction::Unpause::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_migrate_builders() {
+        let (election, admin, authority) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let candidates = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let ix = migrate_voter(&election, &admin, &authority, &candidates);
+        assert_eq!(meta(&ix, 1), (find_legacy_voter_pda(&authority).0, false, true));
+        assert_eq!(meta(&ix, 3), (find_voter_pda(&election, &authority).0, false, true));
+        assert_eq!(meta(&ix, 5), (admin, true, true));
+        assert_eq!(meta(&ix, 7), (candidates[0], false, false));
+        assert_eq!(ix.accounts.len(), 9);
+        assert_eq!(ix.data, vote_d_21::instruction::MigrateVoter::DISCRIMINATOR);
+
+        let ix = migrate_candidate(&election, &admin, "Alice");
+        assert_eq!(meta(&ix, 1), (find_legacy_candidate_pda("Alice").0, false, true));
+        assert_eq!(meta(&ix, 2), (find_candidate_pda(&election, "Alice").0, false, true));
+        let data = vote_d_21::instruction::MigrateCandidate::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(data.name, "Alice");
+    }
+
+    #[test]
+    fn test_sponsored_builders() {
+        let (election, authority, relayer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let budget = find_sponsor_budget_pda(&election).0;
+
+        let ix = sponsored_initialize_voter(&election, &authority, &relayer, Eligibility::default());
+        assert_eq!(meta(&ix, 4), (budget, false, true));
+        assert_eq!(meta(&ix, 5), (authority, true, false));
+        assert_eq!(meta(&ix, 6), (relayer, true, true));
+
+        let unsponsored = vote(&election, &authority, Ballot::default());
+        assert_eq!(unsponsored.accounts[4].pubkey, ID);
+        assert_eq!(unsponsored.accounts[5].pubkey, ID);
+        let ix = sponsored_vote(&election, &authority, &relayer, Ballot::default());
+        assert_eq!(meta(&ix, 4), (budget, false, true));
+        assert_eq!(meta(&ix, 5), (relayer, true, true));
+        assert_eq!(ix.data, unsponsored.data);
+
+        let ix = initialize_sponsor_budget(&election, &authority, &relayer, 5_000);
+        assert_eq!(meta(&ix, 1), (budget, false, true));
+        assert_eq!(meta(&ix, 2), (authority, true, true));
+        let args = vote_d_21::instruction::InitializeSponsorBudget::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!((args.relayer, args.fee_per_transaction), (relayer, 5_000));
+
+        assert_eq!(meta(&fund_sponsor_budget(&election, &relayer, 1), 0), (budget, false, true));
+        let ix = withdraw_sponsor_budget(&election, &authority, 1);
+        assert_eq!(meta(&ix, 1), (authority, true, true));
+    }
+
+    #[test]
+    fn test_signed_batch_builders() {
+        let election = Pubkey::new_unique();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let signed = |nonce, candidate| SignedBallot {
+            authority: Pubkey::new_unique(),
+            nonce,
+            ballot: Ballot { positive: vec![candidate], negative: Vec::new(), scores: Vec::new() },
+        };
+        let ballots = vec![(signed(0, alice), [1; 64]), (signed(4, bob), [2; 64])];
+
+        let [verify, batch] = vote_signed_batch(&election, &ballots).try_into().unwrap();
+        assert_eq!(verify, verify_signed_ballots(&election, &ballots));
+        assert_eq!(verify.program_id, ed25519_program::ID);
+        assert_eq!(verify.data[0], 2);
+
+        // Every offset points at this instruction's own data
+        for (i, (ballot, signature)) in ballots.iter().enumerate() {
+            let offsets = &verify.data[2 + 14 * i..2 + 14 * (i + 1)];
+            let field = |n: usize| usize::from(u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]));
+            assert_eq!([field(1), field(3), field(6)], [usize::from(u16::MAX); 3]);
+            assert_eq!(&verify.data[field(0)..field(0) + 64], signature);
+            assert_eq!(&verify.data[field(2)..field(2) + 32], ballot.authority.as_ref());
+            assert_eq!(verify.data[field(4)..field(4) + field(5)], ballot.message(&election));
+        }
+
+        assert_eq!(batch.accounts.len(), 7);
+        assert_eq!(meta(&batch, 2), (INSTRUCTIONS_SYSVAR_ID, false, false));
+        assert_eq!(meta(&batch, 3), (find_voter_pda(&election, &ballots[0].0.authority).0, false, true));
+        assert_eq!(meta(&batch, 4), (alice, false, true));
+        assert_eq!(meta(&batch, 5), (find_voter_pda(&election, &ballots[1].0.authority).0, false, true));
+        assert_eq!(meta(&batch, 6), (bob, false, true));
+        let args = vote_d_21::instruction::VoteSignedBatch::try_from_slice(&batch.data[8..]).unwrap();
+        assert_eq!(args.ballots[1], ballots[1].0);
+    }
+}
@@ -0,0 +1,10 @@
+//! Rust client for the `vote_d_21` program: PDA helpers, instruction
+//! builders and account decoders, built on the program's own types so they
+//! cannot drift from what it expects on-chain.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+
+pub use vote_d_21::state::*;
+pub use vote_d_21::ID;
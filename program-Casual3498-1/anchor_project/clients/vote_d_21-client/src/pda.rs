@@ -0,0 +1,151 @@
+use anchor_lang::prelude::{pubkey, Pubkey};
+use vote_d_21::state::CandidateAccount;
+use vote_d_21::ID;
+
+/// The upgradeable BPF loader, owner of the deployed program
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// The instructions sysvar, read by `vote_signed_batch` to find its signatures
+pub const INSTRUCTIONS_SYSVAR_ID: Pubkey = pubkey!("Sysvar1nstructions1111111111111111111111111");
+
+/// The program's data account, which records its upgrade authority.
+/// Seeds: [program id], derived under the upgradeable loader
+pub fn find_program_data_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID)
+}
+
+/// Program-wide config holding the emergency pause flag.
+/// Seeds: ["config"]
+pub fn find_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &ID)
+}
+
+/// Election account of `creator`'s `election_id`.
+/// Seeds: ["election", creator, election_id (u64 LE)]
+pub fn find_election_pda(creator: &Pubkey, election_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"election", creator.as_ref(), &election_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Candidate account named `name` in `election`. Names are compared after
+/// trimming, Unicode normalization and case folding, as on-chain.
+/// Seeds: ["candidate", election, sha256(name key)]
+pub fn find_candidate_pda(election: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"candidate", election.as_ref(), &CandidateAccount::name_seed(name)],
+        &ID,
+    )
+}
+
+/// Voter account of `authority` in `election`.
+/// Seeds: ["voter", election, authority]
+pub fn find_voter_pda(election: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"voter", election.as_ref(), authority.as_ref()], &ID)
+}
+
+/// Whitelist entry allowing `voter` to register in `election`.
+/// Seeds: ["whitelist", election, voter]
+pub fn find_whitelist_pda(election: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"whitelist", election.as_ref(), voter.as_ref()], &ID)
+}
+
+/// Results account written when `election` is finalized.
+/// Seeds: ["results", election]
+pub fn find_results_pda(election: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"results", election.as_ref()], &ID)
+}
+
+/// Instant-runoff tally of `election`.
+/// Seeds: ["runoff", election]
+pub fn find_runoff_pda(election: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"runoff", election.as_ref()], &ID)
+}
+
+/// Ranked ballot cast by the voter account `voter`.
+/// Seeds: ["ballot", voter]
+pub fn find_ballot_pda(voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"ballot", voter.as_ref()], &ID)
+}
+
+/// Token vault escrowing the locked tokens of the voter account `voter`.
+/// Seeds: ["vault", voter]
+pub fn find_vault_pda(voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", voter.as_ref()], &ID)
+}
+
+/// Sponsor budget reimbursing the relayer of `election`.
+/// Seeds: ["sponsor_budget", election]
+pub fn find_sponsor_budget_pda(election: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sponsor_budget", election.as_ref()], &ID)
+}
+
+/// Candidate named `name` in the first deployment, before elections existed.
+/// Names were used verbatim, without normalization.
+/// Seeds: ["candidate", name]
+pub fn find_legacy_candidate_pda(name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"candidate", name.as_bytes()], &ID)
+}
+
+/// Voter account of `authority` in the first deployment, before elections existed.
+/// Seeds: ["voter", authority]
+pub fn find_legacy_voter_pda(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"voter", authority.as_ref()], &ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vote_d_21::state::CandidateAccountV0;
+
+    #[test]
+    fn test_voter_pda_is_scoped_to_election() {
+        let authority = Pubkey::new_unique();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let (voter, bump) = find_voter_pda(&a, &authority);
+        assert_eq!(
+            Pubkey::create_program_address(&[b"voter", a.as_ref(), authority.as_ref(), &[bump]], &ID).unwrap(),
+            voter
+        );
+        assert_ne!(voter, find_voter_pda(&b, &authority).0);
+    }
+
+    #[test]
+    fn test_candidate_pda_matches_name_key() {
+        let election = Pubkey::new_unique();
+        let (alice, _) = find_candidate_pda(&election, "Alice");
+
+        // The same name key maps to the same account
+        assert_eq!(find_candidate_pda(&election, "  ALICE ").0, alice);
+        assert_ne!(find_candidate_pda(&election, "Bob").0, alice);
+        assert_ne!(find_candidate_pda(&Pubkey::new_unique(), "Alice").0, alice);
+    }
+
+    #[test]
+    fn test_election_pda_uses_little_endian_id() {
+        let creator = Pubkey::new_unique();
+        let (election, bump) = find_election_pda(&creator, 7);
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[b"election", creator.as_ref(), &[7, 0, 0, 0, 0, 0, 0, 0], &[bump]],
+                &ID
+            )
+            .unwrap(),
+            election
+        );
+        assert_ne!(find_election_pda(&creator, 8).0, election);
+    }
+
+    #[test]
+    fn test_legacy_pdas_match_program() {
+        assert_eq!(find_legacy_candidate_pda("Alice").0, CandidateAccountV0::address("Alice"));
+        assert_ne!(find_legacy_candidate_pda("alice").0, find_legacy_candidate_pda("Alice").0);
+
+        let (authority, election) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (voter, bump) = find_legacy_voter_pda(&authority);
+        assert_eq!(Pubkey::create_program_address(&[b"voter", authority.as_ref(), &[bump]], &ID).unwrap(), voter);
+        assert_ne!(voter, find_voter_pda(&election, &authority).0);
+    }
+}
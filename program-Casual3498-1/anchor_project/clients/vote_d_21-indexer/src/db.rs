@@ -0,0 +1,607 @@
+//! SQLite store of elections, candidates, voters and their current ballots,
+//! rebuilt by replaying events in order.
+//!
+//! Counters are taken from the absolute values events carry (candidate
+//! tallies, `ballots_cast`, `registered_voters`) rather than incremented, so
+//! replaying overlapping log dumps leaves the database unchanged.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use vote_d_21::events::CandidateTally;
+use vote_d_21::state::{CandidateStatus, ElectionStatus};
+
+use crate::events::ProgramEvent;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS elections (
+    address           TEXT PRIMARY KEY,
+    creator           TEXT,
+    title             TEXT,
+    status            TEXT NOT NULL,
+    registered_voters INTEGER NOT NULL DEFAULT 0,
+    ballots_cast      INTEGER NOT NULL DEFAULT 0,
+    winners           TEXT,
+    updated_slot      INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS candidates (
+    address        TEXT PRIMARY KEY,
+    election       TEXT NOT NULL,
+    name           TEXT,
+    status         TEXT NOT NULL,
+    votes          INTEGER NOT NULL DEFAULT 0,
+    negative_votes INTEGER NOT NULL DEFAULT 0,
+    score_sum      INTEGER NOT NULL DEFAULT 0,
+    updated_slot   INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS voters (
+    election     TEXT NOT NULL,
+    authority    TEXT NOT NULL,
+    address      TEXT,
+    weight       INTEGER NOT NULL DEFAULT 0,
+    voted        INTEGER NOT NULL DEFAULT 0,
+    updated_slot INTEGER NOT NULL,
+    PRIMARY KEY (election, authority)
+);
+CREATE TABLE IF NOT EXISTS ballots (
+    election  TEXT NOT NULL,
+    voter     TEXT NOT NULL,
+    candidate TEXT NOT NULL,
+    choice    TEXT NOT NULL,
+    position  INTEGER NOT NULL,
+    score     INTEGER,
+    weight    INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    PRIMARY KEY (election, voter, candidate)
+);
+CREATE INDEX IF NOT EXISTS candidates_by_election ON candidates (election);
+CREATE TABLE IF NOT EXISTS sync_state (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// How a ballot row counts for its candidate
+pub const CHOICE_POSITIVE: &str = "positive";
+pub const CHOICE_NEGATIVE: &str = "negative";
+pub const CHOICE_RANKED: &str = "ranked";
+
+const LAST_SIGNATURE: &str = "last_signature";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElectionRow {
+    pub address: String,
+    pub title: Option<String>,
+    pub status: String,
+    pub registered_voters: u32,
+    pub ballots_cast: u32,
+    pub winners: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CandidateRow {
+    pub address: String,
+    pub name: Option<String>,
+    pub status: String,
+    pub votes: u64,
+    pub negative_votes: u64,
+    pub score_sum: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoterRow {
+    pub authority: String,
+    pub address: Option<String>,
+    pub weight: u64,
+    pub voted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BallotRow {
+    pub voter: String,
+    pub candidate: String,
+    pub choice: String,
+    pub position: u32,
+    pub score: Option<u8>,
+    pub weight: u64,
+}
+
+/// Outcome of an `ingest` call
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IngestStats {
+    /// Events written to the database
+    pub applied: usize,
+    /// Program events the indexer does not track
+    pub skipped: usize,
+}
+
+/// SQLite stores signed 64-bit integers; amounts keep their bit pattern.
+fn int(value: u64) -> i64 {
+    value as i64
+}
+
+fn status<T: std::fmt::Debug>(status: T) -> String {
+    format!("{status:?}")
+}
+
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Decode and apply event payloads in order, atomically.
+    pub fn ingest(&mut self, payloads: &[Vec<u8>]) -> anyhow::Result<IngestStats> {
+        let tx = self.conn.transaction()?;
+        let mut stats = IngestStats::default();
+        for payload in payloads {
+            match ProgramEvent::decode(payload)? {
+                Some(event) => {
+                    apply(&tx, &event)?;
+                    stats.applied += 1;
+                }
+                None => stats.skipped += 1,
+            }
+        }
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    /// Newest transaction signature already ingested from RPC.
+    pub fn last_signature(&self) -> anyhow::Result<Option<String>> {
+        let value = self
+            .conn
+            .query_row("SELECT value FROM sync_state WHERE key = ?1", [LAST_SIGNATURE], |row| row.get(0))
+            .optional()?;
+        Ok(value)
+    }
+
+    pub fn set_last_signature(&self, signature: &str) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            [LAST_SIGNATURE, signature],
+        )?;
+        Ok(())
+    }
+
+    pub fn elections(&self) -> anyhow::Result<Vec<ElectionRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT address, title, status, registered_voters, ballots_cast, winners
+             FROM elections ORDER BY address",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let winners: Option<String> = row.get(5)?;
+            Ok(ElectionRow {
+                address: row.get(0)?,
+                title: row.get(1)?,
+                status: row.get(2)?,
+                registered_voters: row.get(3)?,
+                ballots_cast: row.get(4)?,
+                winners: winners
+                    .map(|winners| winners.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Candidates of `election`, most net votes first.
+    pub fn candidates(&self, election: &str) -> anyhow::Result<Vec<CandidateRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT address, name, status, votes, negative_votes, score_sum FROM candidates
+             WHERE election = ?1 ORDER BY votes - negative_votes DESC, address",
+        )?;
+        let rows = stmt.query_map([election], |row| {
+            Ok(CandidateRow {
+                address: row.get(0)?,
+                name: row.get(1)?,
+                status: row.get(2)?,
+                votes: row.get::<_, i64>(3)? as u64,
+                negative_votes: row.get::<_, i64>(4)? as u64,
+                score_sum: row.get::<_, i64>(5)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn voters(&self, election: &str) -> anyhow::Result<Vec<VoterRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT authority, address, weight, voted FROM voters
+             WHERE election = ?1 ORDER BY authority",
+        )?;
+        let rows = stmt.query_map([election], |row| {
+            Ok(VoterRow {
+                authority: row.get(0)?,
+                address: row.get(1)?,
+                weight: row.get::<_, i64>(2)? as u64,
+                voted: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Current ballots in `election`, optionally only `voter`'s.
+    pub fn ballots(&self, election: &str, voter: Option<&str>) -> anyhow::Result<Vec<BallotRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT voter, candidate, choice, position, score, weight FROM ballots
+             WHERE election = ?1 AND (?2 IS NULL OR voter = ?2)
+             ORDER BY voter, choice DESC, position",
+        )?;
+        let rows = stmt.query_map(params![election, voter], |row| {
+            Ok(BallotRow {
+                voter: row.get(0)?,
+                candidate: row.get(1)?,
+                choice: row.get(2)?,
+                position: row.get(3)?,
+                score: row.get(4)?,
+                weight: row.get::<_, i64>(5)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
+
+fn apply(tx: &Transaction, event: &ProgramEvent) -> rusqlite::Result<()> {
+    match event {
+        ProgramEvent::ElectionCreated(e) => {
+            tx.execute(
+                "INSERT INTO elections (address, creator, title, status, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (address) DO UPDATE SET creator = excluded.creator, title = excluded.title",
+                params![
+                    e.election.to_string(),
+                    e.creator.to_string(),
+                    e.title,
+                    status(ElectionStatus::Pending),
+                    int(e.header.slot)
+                ],
+            )?;
+        }
+        ProgramEvent::ElectionOpened(e) => {
+            set_election_status(tx, &e.election, ElectionStatus::Active, e.header.slot)?;
+        }
+        ProgramEvent::ElectionClosed(e) => {
+            set_election_status(tx, &e.election, ElectionStatus::Closed, e.header.slot)?;
+        }
+        ProgramEvent::ElectionFinalized(e) => {
+            let outcome = match e.quorum_reached {
+                true => ElectionStatus::Finalized,
+                false => ElectionStatus::Invalid,
+            };
+            set_election_status(tx, &e.election, outcome, e.header.slot)?;
+            let winners: Vec<String> = e.winners.iter().map(Pubkey::to_string).collect();
+            tx.execute(
+                "UPDATE elections SET registered_voters = ?2, ballots_cast = ?3, winners = ?4
+                 WHERE address = ?1",
+                params![e.election.to_string(), e.registered_voters, e.ballots_cast, winners.join(",")],
+            )?;
+        }
+        ProgramEvent::CandidateInitialized(e) => {
+            insert_candidate(tx, &e.election, &e.pubkey, &e.name, CandidateStatus::Approved, e.header.slot)?;
+        }
+        ProgramEvent::CandidateNominated(e) => {
+            insert_candidate(tx, &e.election, &e.pubkey, &e.name, CandidateStatus::Nominated, e.header.slot)?;
+        }
+        ProgramEvent::CandidateApproved(e) => {
+            set_candidate_status(tx, &e.pubkey, CandidateStatus::Approved, e.header.slot)?;
+        }
+        ProgramEvent::CandidateRejected(e) => {
+            set_candidate_status(tx, &e.pubkey, CandidateStatus::Rejected, e.header.slot)?;
+        }
+        ProgramEvent::CandidateWithdrawn(e) => {
+            set_candidate_status(tx, &e.pubkey, CandidateStatus::Withdrawn, e.header.slot)?;
+        }
+        ProgramEvent::CandidateDisqualified(e) => {
+            set_candidate_status(tx, &e.pubkey, CandidateStatus::Disqualified, e.header.slot)?;
+        }
+        ProgramEvent::CandidateClosed(e) => {
+            tx.execute("DELETE FROM candidates WHERE address = ?1", [e.pubkey.to_string()])?;
+        }
+        ProgramEvent::VoterRegistered(e) => {
+            tx.execute(
+                "INSERT INTO voters (election, authority, address, weight, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (election, authority) DO UPDATE SET
+                     address = excluded.address, weight = excluded.weight,
+                     updated_slot = excluded.updated_slot",
+                params![
+                    e.election.to_string(),
+                    e.authority.to_string(),
+                    e.voter.to_string(),
+                    int(e.weight),
+                    int(e.header.slot)
+                ],
+            )?;
+            tx.execute(
+                "UPDATE elections SET registered_voters = ?2 WHERE address = ?1",
+                params![e.election.to_string(), e.registered_voters],
+            )?;
+        }
+        ProgramEvent::VoterClosed(e) => {
+            let (election, authority) = (e.election.to_string(), e.authority.to_string());
+            tx.execute(
+                "DELETE FROM voters WHERE election = ?1 AND authority = ?2",
+                [&election, &authority],
+            )?;
+            clear_ballot(tx, &election, &authority)?;
+        }
+        ProgramEvent::VoteCast(e) => {
+            let choices = tallied_choices(&e.positive, &e.negative, &e.scores);
+            record_ballot(tx, &e.election, &e.voter, &choices, e.weight, e.header.slot)?;
+            update_tallies(tx, &e.election, &e.tallies, e.header.slot)?;
+            set_ballots_cast(tx, &e.election, e.ballots_cast)?;
+        }
+        ProgramEvent::VoteChanged(e) => {
+            let choices = tallied_choices(&e.positive, &e.negative, &e.scores);
+            record_ballot(tx, &e.election, &e.voter, &choices, e.weight, e.header.slot)?;
+            update_tallies(tx, &e.election, &e.tallies, e.header.slot)?;
+        }
+        ProgramEvent::VoteRevoked(e) => {
+            let (election, voter) = (e.election.to_string(), e.voter.to_string());
+            clear_ballot(tx, &election, &voter)?;
+            set_voted(tx, &election, &voter, false, e.header.slot)?;
+            update_tallies(tx, &e.election, &e.tallies, e.header.slot)?;
+            set_ballots_cast(tx, &e.election, e.ballots_cast)?;
+        }
+        ProgramEvent::RankedBallotCast(e) => {
+            let choices: Vec<(Pubkey, &str, Option<u8>)> =
+                e.rankings.iter().map(|key| (*key, CHOICE_RANKED, None)).collect();
+            record_ballot(tx, &e.election, &e.voter, &choices, e.weight, e.header.slot)?;
+            set_ballots_cast(tx, &e.election, e.ballots_cast)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ballot rows for a tallied ballot; scores pair with positive choices.
+fn tallied_choices<'a>(
+    positive: &[Pubkey],
+    negative: &[Pubkey],
+    scores: &[u8],
+) -> Vec<(Pubkey, &'a str, Option<u8>)> {
+    positive
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (*key, CHOICE_POSITIVE, scores.get(i).copied()))
+        .chain(negative.iter().map(|key| (*key, CHOICE_NEGATIVE, None)))
+        .collect()
+}
+
+fn set_election_status(tx: &Transaction, election: &Pubkey, value: ElectionStatus, slot: u64) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO elections (address, status, updated_slot) VALUES (?1, ?2, ?3)
+         ON CONFLICT (address) DO UPDATE SET status = excluded.status, updated_slot = excluded.updated_slot",
+        params![election.to_string(), status(value), int(slot)],
+    )?;
+    Ok(())
+}
+
+fn set_ballots_cast(tx: &Transaction, election: &Pubkey, ballots_cast: u32) -> rusqlite::Result<()> {
+    tx.execute(
+        "UPDATE elections SET ballots_cast = ?2 WHERE address = ?1",
+        params![election.to_string(), ballots_cast],
+    )?;
+    Ok(())
+}
+
+fn insert_candidate(
+    tx: &Transaction,
+    election: &Pubkey,
+    candidate: &Pubkey,
+    name: &str,
+    value: CandidateStatus,
+    slot: u64,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO candidates (address, election, name, status, updated_slot) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (address) DO UPDATE SET name = excluded.name",
+        params![candidate.to_string(), election.to_string(), name, status(value), int(slot)],
+    )?;
+    Ok(())
+}
+
+fn set_candidate_status(tx: &Transaction, candidate: &Pubkey, value: CandidateStatus, slot: u64) -> rusqlite::Result<()> {
+    tx.execute(
+        "UPDATE candidates SET status = ?2, updated_slot = ?3 WHERE address = ?1",
+        params![candidate.to_string(), status(value), int(slot)],
+    )?;
+    Ok(())
+}
+
+/// Overwrite candidate counters with the post-instruction tallies. Candidates
+/// registered before the indexer's first event are created on the fly.
+fn update_tallies(tx: &Transaction, election: &Pubkey, tallies: &[CandidateTally], slot: u64) -> rusqlite::Result<()> {
+    for tally in tallies {
+        tx.execute(
+            "INSERT INTO candidates (address, election, status, votes, negative_votes, score_sum, updated_slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (address) DO UPDATE SET
+                 votes = excluded.votes, negative_votes = excluded.negative_votes,
+                 score_sum = excluded.score_sum, updated_slot = excluded.updated_slot",
+            params![
+                tally.candidate.to_string(),
+                election.to_string(),
+                status(CandidateStatus::Approved),
+                int(tally.votes),
+                int(tally.negative_votes),
+                int(tally.score_sum),
+                int(slot)
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn clear_ballot(tx: &Transaction, election: &str, voter: &str) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM ballots WHERE election = ?1 AND voter = ?2", [election, voter])?;
+    Ok(())
+}
+
+fn set_voted(tx: &Transaction, election: &str, voter: &str, voted: bool, slot: u64) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO voters (election, authority, voted, updated_slot) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (election, authority) DO UPDATE SET
+             voted = excluded.voted, updated_slot = excluded.updated_slot",
+        params![election, voter, voted, int(slot)],
+    )?;
+    Ok(())
+}
+
+/// Replace `voter`'s ballot with `choices`, in ballot order.
+fn record_ballot(
+    tx: &Transaction,
+    election: &Pubkey,
+    voter: &Pubkey,
+    choices: &[(Pubkey, &str, Option<u8>)],
+    weight: u64,
+    slot: u64,
+) -> rusqlite::Result<()> {
+    let (election, voter) = (election.to_string(), voter.to_string());
+    clear_ballot(tx, &election, &voter)?;
+    for (position, (candidate, choice, score)) in choices.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO ballots (election, voter, candidate, choice, position, score, weight, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                election,
+                voter,
+                candidate.to_string(),
+                choice,
+                position as u32,
+                score,
+                int(weight),
+                int(slot)
+            ],
+        )?;
+    }
+    set_voted(tx, &election, &voter, true, slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+    use vote_d_21::events::*;
+
+    fn header(slot: u64) -> EventHeader {
+        EventHeader { version: EVENT_VERSION, slot, timestamp: 1_700_000_000 }
+    }
+
+    fn tally(candidate: Pubkey, votes: u64, negative_votes: u64) -> CandidateTally {
+        CandidateTally { candidate, votes, negative_votes, score_sum: 0 }
+    }
+
+    #[test]
+    fn test_vote_lifecycle() {
+        let (election, voter, alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let payloads = vec![
+            CandidateInitialized { header: header(1), election, name: "alice".into(), pubkey: alice }.data(),
+            CandidateInitialized { header: header(1), election, name: "bob".into(), pubkey: bob }.data(),
+            VoteCast {
+                header: header(2),
+                election,
+                voter,
+                positive: vec![alice, bob],
+                negative: vec![],
+                scores: vec![],
+                weight: 1,
+                tallies: vec![tally(alice, 1, 0), tally(bob, 1, 0)],
+                ballots_cast: 1,
+            }
+            .data(),
+            VoteChanged {
+                header: header(3),
+                election,
+                voter,
+                previous_positive: vec![alice, bob],
+                previous_negative: vec![],
+                previous_scores: vec![],
+                positive: vec![bob],
+                negative: vec![],
+                scores: vec![],
+                weight: 1,
+                tallies: vec![tally(alice, 0, 0), tally(bob, 1, 0)],
+            }
+            .data(),
+        ];
+        let mut db = Database::open_in_memory().unwrap();
+        let stats = db.ingest(&payloads).unwrap();
+        assert_eq!(stats, IngestStats { applied: 4, skipped: 0 });
+
+        let candidates = db.candidates(&election.to_string()).unwrap();
+        assert_eq!(candidates[0].name.as_deref(), Some("bob"));
+        assert_eq!((candidates[0].votes, candidates[1].votes), (1, 0));
+        let ballots = db.ballots(&election.to_string(), None).unwrap();
+        assert_eq!(ballots.len(), 1);
+        assert_eq!(ballots[0].candidate, bob.to_string());
+        assert!(db.voters(&election.to_string()).unwrap()[0].voted);
+
+        // Replaying the same events changes nothing
+        db.ingest(&payloads).unwrap();
+        assert_eq!(db.candidates(&election.to_string()).unwrap(), candidates);
+        assert_eq!(db.ballots(&election.to_string(), None).unwrap(), ballots);
+    }
+
+    #[test]
+    fn test_revoke_clears_ballot() {
+        let (election, voter, alice) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let payloads = vec![
+            VoteCast {
+                header: header(2),
+                election,
+                voter,
+                positive: vec![alice],
+                negative: vec![],
+                scores: vec![4],
+                weight: 3,
+                tallies: vec![CandidateTally { candidate: alice, votes: 3, negative_votes: 0, score_sum: 12 }],
+                ballots_cast: 1,
+            }
+            .data(),
+        ];
+        let mut db = Database::open_in_memory().unwrap();
+        db.ingest(&payloads).unwrap();
+        let ballots = db.ballots(&election.to_string(), Some(&voter.to_string())).unwrap();
+        assert_eq!((ballots[0].score, ballots[0].weight), (Some(4), 3));
+        assert_eq!(db.candidates(&election.to_string()).unwrap()[0].score_sum, 12);
+
+        let revoked = VoteRevoked {
+            header: header(3),
+            election,
+            voter,
+            positive: vec![alice],
+            negative: vec![],
+            scores: vec![4],
+            weight: 3,
+            tallies: vec![tally(alice, 0, 0)],
+            ballots_cast: 0,
+        };
+        db.ingest(&[revoked.data()]).unwrap();
+        assert!(db.ballots(&election.to_string(), None).unwrap().is_empty());
+        assert!(!db.voters(&election.to_string()).unwrap()[0].voted);
+        assert_eq!(db.candidates(&election.to_string()).unwrap()[0].votes, 0);
+    }
+
+    #[test]
+    fn test_sync_cursor() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.last_signature().unwrap(), None);
+        db.set_last_signature("abc").unwrap();
+        db.set_last_signature("def").unwrap();
+        assert_eq!(db.last_signature().unwrap().as_deref(), Some("def"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Decoding `vote_d_21` event payloads into the program's own event types.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use vote_d_21::events::*;
+
+/// The events the indexer applies to its database
+pub enum ProgramEvent {
+    ElectionCreated(ElectionCreated),
+    ElectionOpened(ElectionOpened),
+    ElectionClosed(ElectionClosed),
+    ElectionFinalized(ElectionFinalized),
+    CandidateInitialized(CandidateInitialized),
+    CandidateNominated(CandidateNominated),
+    CandidateApproved(CandidateApproved),
+    CandidateRejected(CandidateRejected),
+    CandidateWithdrawn(CandidateWithdrawn),
+    CandidateDisqualified(CandidateDisqualified),
+    CandidateClosed(CandidateClosed),
+    VoterRegistered(VoterRegistered),
+    VoterClosed(VoterClosed),
+    VoteCast(VoteCast),
+    VoteChanged(VoteChanged),
+    VoteRevoked(VoteRevoked),
+    RankedBallotCast(RankedBallotCast),
+}
+
+/// Decode `payload` as `T` if it carries `T`'s discriminator.
+fn decode_as<T: Discriminator + AnchorDeserialize>(payload: &[u8]) -> Option<anyhow::Result<T>> {
+    let data = payload.strip_prefix(T::DISCRIMINATOR)?;
+    Some(T::try_from_slice(data).map_err(Into::into))
+}
+
+macro_rules! decode_any {
+    ($payload:expr, $($event:ident),+ $(,)?) => {
+        $(
+            if let Some(event) = decode_as::<$event>($payload) {
+                return event.map(|event| Some(ProgramEvent::$event(event)));
+            }
+        )+
+    };
+}
+
+impl ProgramEvent {
+    /// Decode an event payload. Events the indexer does not track decode to
+    /// `None`; a known discriminator with a malformed body is an error.
+    pub fn decode(payload: &[u8]) -> anyhow::Result<Option<Self>> {
+        decode_any!(
+            payload,
+            ElectionCreated,
+            ElectionOpened,
+            ElectionClosed,
+            ElectionFinalized,
+            CandidateInitialized,
+            CandidateNominated,
+            CandidateApproved,
+            CandidateRejected,
+            CandidateWithdrawn,
+            CandidateDisqualified,
+            CandidateClosed,
+            VoterRegistered,
+            VoterClosed,
+            VoteCast,
+            VoteChanged,
+            VoteRevoked,
+            RankedBallotCast,
+        );
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::Event;
+
+    fn header() -> EventHeader {
+        EventHeader { version: EVENT_VERSION, slot: 9, timestamp: 1_700_000_000 }
+    }
+
+    #[test]
+    fn test_decode_known_event() {
+        let event = CandidateInitialized {
+            header: header(),
+            election: Pubkey::new_unique(),
+            name: String::from("alice"),
+            pubkey: Pubkey::new_unique(),
+        };
+        match ProgramEvent::decode(&event.data()).unwrap() {
+            Some(ProgramEvent::CandidateInitialized(decoded)) => {
+                assert_eq!(decoded.name, "alice");
+                assert_eq!(decoded.pubkey, event.pubkey);
+                assert_eq!(decoded.header.slot, 9);
+            }
+            _ => panic!("expected CandidateInitialized"),
+        }
+    }
+
+    #[test]
+    fn test_untracked_event_ignored() {
+        let event = QuorumUpdated { header: header(), election: Pubkey::new_unique(), quorum_bps: None };
+        assert!(ProgramEvent::decode(&event.data()).unwrap().is_none());
+        assert!(ProgramEvent::decode(&[1, 2, 3]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_event_rejected() {
+        let event = ElectionOpened { header: header(), election: Pubkey::new_unique(), starts_at: 1, ends_at: 2 };
+        let data = event.data();
+        assert!(ProgramEvent::decode(&data[..data.len() - 1]).is_err());
+    }
+}
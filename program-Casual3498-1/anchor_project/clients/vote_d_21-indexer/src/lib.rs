@@ -0,0 +1,12 @@
+//! Off-chain indexer for the `vote_d_21` program. It extracts the program's
+//! events from transaction logs, read from a dump file or a JSON-RPC node,
+//! and replays them into a SQLite database of elections, candidates, voters
+//! and ballots.
+
+pub mod db;
+pub mod events;
+pub mod logs;
+pub mod rpc;
+
+pub use db::Database;
+pub use events::ProgramEvent;
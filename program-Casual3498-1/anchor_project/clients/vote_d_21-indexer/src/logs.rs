@@ -0,0 +1,227 @@
+//! Extracting a program's event payloads from transaction log messages.
+//!
+//! Anchor's `emit!` logs each event as `Program data: <base64>`. The runtime
+//! does not say which program wrote a log line, so the invoke/success/failed
+//! lines are followed to attribute each payload to the program executing at
+//! that point. Events of failed transactions are discarded, since their
+//! state changes were rolled back.
+
+use anchor_lang::prelude::Pubkey;
+use base64::prelude::{Engine, BASE64_STANDARD};
+
+const PROGRAM_LOG_PREFIX: &str = "Program ";
+const DATA_PREFIX: &str = "Program data: ";
+
+/// Walks the log lines of one or more transactions and collects the
+/// payloads emitted by `program_id`.
+pub struct LogParser {
+    program_id: Pubkey,
+    /// Programs currently executing, innermost last
+    stack: Vec<Pubkey>,
+    /// Payloads of the transaction in progress
+    pending: Vec<Vec<u8>>,
+    failed: bool,
+    events: Vec<Vec<u8>>,
+}
+
+impl LogParser {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            stack: Vec::new(),
+            pending: Vec::new(),
+            failed: false,
+            events: Vec::new(),
+        }
+    }
+
+    /// Feed one log line. Leading whitespace, as in `solana logs` output, is ignored.
+    pub fn line(&mut self, line: &str) {
+        let line = line.trim();
+        // `solana logs` prints a header line for every transaction
+        if line.starts_with("Transaction executed in slot") {
+            self.end_transaction();
+            return;
+        }
+        if let Some(data) = line.strip_prefix(DATA_PREFIX) {
+            if self.stack.last() == Some(&self.program_id) {
+                // Several fields may be logged on one line; Anchor events use one
+                if let Some(payload) = data.split_whitespace().next() {
+                    if let Ok(bytes) = BASE64_STANDARD.decode(payload) {
+                        self.pending.push(bytes);
+                    }
+                }
+            }
+            return;
+        }
+        let Some(rest) = line.strip_prefix(PROGRAM_LOG_PREFIX) else {
+            return;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(program), Some(action)) = (words.next(), words.next()) else {
+            return;
+        };
+        let Ok(program) = program.parse::<Pubkey>() else {
+            return;
+        };
+        match action {
+            "invoke" => self.stack.push(program),
+            "success" => {
+                self.stack.pop();
+            }
+            "failed:" => {
+                self.stack.pop();
+                self.failed = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Close the current transaction, keeping its payloads if it succeeded.
+    pub fn end_transaction(&mut self) {
+        if !self.failed {
+            self.events.append(&mut self.pending);
+        }
+        self.pending.clear();
+        self.stack.clear();
+        self.failed = false;
+    }
+
+    /// Payloads of every successful transaction seen, in log order.
+    pub fn finish(mut self) -> Vec<Vec<u8>> {
+        self.end_transaction();
+        self.events
+    }
+}
+
+/// Event payloads emitted by `program_id` in a single transaction's log messages.
+pub fn transaction_events<'a>(
+    program_id: Pubkey,
+    logs: impl IntoIterator<Item = &'a str>,
+) -> Vec<Vec<u8>> {
+    let mut parser = LogParser::new(program_id);
+    for line in logs {
+        parser.line(line);
+    }
+    parser.finish()
+}
+
+/// Event payloads from a log dump: either plain log lines, possibly as
+/// printed by `solana logs`, or JSON holding `getTransaction` results,
+/// alone or in an array. Failed transactions are skipped.
+pub fn dump_events(program_id: Pubkey, dump: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+    let trimmed = dump.trim_start();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        let mut parser = LogParser::new(program_id);
+        for line in dump.lines() {
+            parser.line(line);
+        }
+        return Ok(parser.finish());
+    }
+
+    let json: serde_json::Value = serde_json::from_str(dump)?;
+    let transactions = match json {
+        serde_json::Value::Array(transactions) => transactions,
+        transaction => vec![transaction],
+    };
+    let mut events = Vec::new();
+    for transaction in &transactions {
+        // Accept bare results as well as full JSON-RPC responses
+        let transaction = transaction.get("result").unwrap_or(transaction);
+        events.extend(logged_events(program_id, transaction));
+    }
+    Ok(events)
+}
+
+/// Event payloads from a `getTransaction` result, or none if it failed.
+pub fn logged_events(program_id: Pubkey, transaction: &serde_json::Value) -> Vec<Vec<u8>> {
+    let meta = &transaction["meta"];
+    if !meta["err"].is_null() {
+        return Vec::new();
+    }
+    let logs = meta["logMessages"].as_array().into_iter().flatten();
+    transaction_events(program_id, logs.filter_map(|line| line.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoke(program: &Pubkey, depth: u8) -> String {
+        format!("Program {program} invoke [{depth}]")
+    }
+
+    fn success(program: &Pubkey) -> String {
+        format!("Program {program} success")
+    }
+
+    fn data(bytes: &[u8]) -> String {
+        format!("Program data: {}", BASE64_STANDARD.encode(bytes))
+    }
+
+    #[test]
+    fn test_attributes_data_to_executing_program() {
+        let program = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let logs = [
+            invoke(&program, 1),
+            data(&[1]),
+            invoke(&other, 2),
+            data(&[2]),
+            success(&other),
+            data(&[3]),
+            success(&program),
+            invoke(&other, 1),
+            data(&[4]),
+            success(&other),
+        ];
+        let events = transaction_events(program, logs.iter().map(String::as_str));
+        assert_eq!(events, vec![vec![1], vec![3]]);
+    }
+
+    #[test]
+    fn test_failed_transaction_discarded() {
+        let program = Pubkey::new_unique();
+        let logs = [
+            invoke(&program, 1),
+            data(&[1]),
+            success(&program),
+            invoke(&program, 1),
+            data(&[2]),
+            format!("Program {program} failed: custom program error: 0x1770"),
+        ];
+        let events = transaction_events(program, logs.iter().map(String::as_str));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_solana_logs_dump_splits_transactions() {
+        let program = Pubkey::new_unique();
+        let dump = [
+            "Transaction executed in slot 5:".to_string(),
+            "  Status: Ok".to_string(),
+            "  Log Messages:".to_string(),
+            format!("    {}", invoke(&program, 1)),
+            format!("    {}", data(&[1])),
+            format!("    {}", success(&program)),
+            "Transaction executed in slot 6:".to_string(),
+            format!("    {}", invoke(&program, 1)),
+            format!("    {}", data(&[2])),
+            format!("    Program {program} failed: custom program error: 0x1770"),
+        ]
+        .join("\n");
+        assert_eq!(dump_events(program, &dump).unwrap(), vec![vec![1]]);
+    }
+
+    #[test]
+    fn test_json_dump_skips_failed_transactions() {
+        let program = Pubkey::new_unique();
+        let logs = [invoke(&program, 1), data(&[7]), success(&program)];
+        let dump = serde_json::json!([
+            { "jsonrpc": "2.0", "result": { "meta": { "err": null, "logMessages": logs } } },
+            { "meta": { "err": { "InstructionError": [0, "Custom"] }, "logMessages": logs } },
+        ]);
+        let events = dump_events(program, &dump.to_string()).unwrap();
+        assert_eq!(events, vec![vec![7]]);
+    }
+}
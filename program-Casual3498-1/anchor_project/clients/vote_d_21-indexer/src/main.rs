@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use vote_d_21_indexer::logs::dump_events;
+use vote_d_21_indexer::rpc::RpcClient;
+use vote_d_21_indexer::Database;
+
+#[derive(Parser)]
+#[command(about = "Index vote_d_21 events into SQLite and query them")]
+struct Cli {
+    /// SQLite database file, created if missing
+    #[arg(long, default_value = "vote_d_21.sqlite")]
+    db: PathBuf,
+
+    /// Program whose events are indexed
+    #[arg(long, default_value_t = vote_d_21::ID)]
+    program_id: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay log dumps: plain log lines or getTransaction JSON
+    Ingest { files: Vec<PathBuf> },
+    /// Fetch and replay transactions newer than the last sync
+    Sync {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Maximum transactions replayed per run, oldest first
+        #[arg(long, default_value_t = 1_000)]
+        limit: usize,
+    },
+    /// List indexed elections
+    Elections,
+    /// List an election's candidates, most net votes first
+    Candidates { election: Pubkey },
+    /// List an election's registered voters
+    Voters { election: Pubkey },
+    /// Show current ballots in an election
+    Ballots {
+        election: Pubkey,
+        #[arg(long)]
+        voter: Option<Pubkey>,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let mut db = Database::open(&cli.db)?;
+
+    match cli.command {
+        Command::Ingest { files } => {
+            for file in files {
+                let events = dump_events(cli.program_id, &std::fs::read_to_string(&file)?)?;
+                let stats = db.ingest(&events)?;
+                println!("{}: {} applied, {} skipped", file.display(), stats.applied, stats.skipped);
+            }
+        }
+        Command::Sync { url, limit } => {
+            let rpc = RpcClient::new(url);
+            let signatures = rpc.signatures(&cli.program_id, db.last_signature()?.as_deref(), limit)?;
+            let mut applied = 0;
+            for signature in &signatures {
+                let transaction = rpc.transaction(&cli.program_id, signature)?;
+                applied += db.ingest(&transaction.events)?.applied;
+                db.set_last_signature(&transaction.signature)?;
+            }
+            println!("{} transactions, {} events applied", signatures.len(), applied);
+        }
+        Command::Elections => {
+            for e in db.elections()? {
+                println!(
+                    "{}\t{}\t{}\t{}/{} voted\t{}",
+                    e.address,
+                    e.title.unwrap_or_default(),
+                    e.status,
+                    e.ballots_cast,
+                    e.registered_voters,
+                    e.winners.join(",")
+                );
+            }
+        }
+        Command::Candidates { election } => {
+            for c in db.candidates(&election.to_string())? {
+                println!(
+                    "{}\t{}\t{}\t+{}\t-{}\tscore {}",
+                    c.address,
+                    c.name.unwrap_or_default(),
+                    c.status,
+                    c.votes,
+                    c.negative_votes,
+                    c.score_sum
+                );
+            }
+        }
+        Command::Voters { election } => {
+            for v in db.voters(&election.to_string())? {
+                let voted = if v.voted { "voted" } else { "-" };
+                println!("{}\t{}\tweight {}\t{}", v.authority, v.address.unwrap_or_default(), v.weight, voted);
+            }
+        }
+        Command::Ballots { election, voter } => {
+            let voter = voter.map(|voter| voter.to_string());
+            for b in db.ballots(&election.to_string(), voter.as_deref())? {
+                let score = b.score.map(|score| score.to_string()).unwrap_or_default();
+                println!("{}\t{}\t{}\t{}\t{}\tweight {}", b.voter, b.candidate, b.choice, b.position, score, b.weight);
+            }
+        }
+    }
+    Ok(())
+}
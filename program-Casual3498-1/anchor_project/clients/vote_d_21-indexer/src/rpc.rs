@@ -0,0 +1,167 @@
+//! Fetching the program's transaction logs from a JSON-RPC endpoint.
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::{json, Value};
+
+use crate::logs::logged_events;
+
+/// Signatures requested per `getSignaturesForAddress` page, the RPC maximum
+const PAGE_SIZE: usize = 1_000;
+
+/// A confirmed transaction of the program and the event payloads it emitted
+pub struct LoggedTransaction {
+    pub signature: String,
+    pub events: Vec<Vec<u8>>,
+}
+
+/// Sends a JSON-RPC request and returns its `result`
+pub trait Transport {
+    fn call(&self, method: &str, params: Value) -> anyhow::Result<Value>;
+}
+
+/// JSON-RPC over HTTP
+pub struct HttpTransport {
+    url: String,
+}
+
+impl Transport for HttpTransport {
+    fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = ureq::post(&self.url).send_json(request)?.into_json()?;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("{method} failed: {error}");
+        }
+        Ok(response["result"].take())
+    }
+}
+
+pub struct RpcClient<T = HttpTransport> {
+    transport: T,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_transport(HttpTransport { url: url.into() })
+    }
+}
+
+impl<T: Transport> RpcClient<T> {
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        self.transport.call(method, params)
+    }
+
+    /// Signatures of `program_id`'s successful transactions newer than
+    /// `until`, oldest first, at most `limit` of them.
+    pub fn signatures(&self, program_id: &Pubkey, until: Option<&str>, limit: usize) -> anyhow::Result<Vec<String>> {
+        // Pages run newest first, so every page back to `until` is fetched
+        // before the oldest `limit` are known
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let page = self.call(
+                "getSignaturesForAddress",
+                json!([
+                    program_id.to_string(),
+                    { "limit": PAGE_SIZE, "before": before, "until": until, "commitment": "confirmed" }
+                ]),
+            )?;
+            let page = page.as_array().cloned().unwrap_or_default();
+            for entry in &page {
+                let signature = entry["signature"].as_str().unwrap_or_default().to_string();
+                if entry["err"].is_null() {
+                    signatures.push(signature.clone());
+                }
+                before = Some(signature);
+            }
+            if page.len() < PAGE_SIZE {
+                break;
+            }
+        }
+        // A sync records the last one it replays and resumes after it, so
+        // the newer ones are fetched next time
+        signatures.reverse();
+        signatures.truncate(limit);
+        Ok(signatures)
+    }
+
+    pub fn transaction(&self, program_id: &Pubkey, signature: &str) -> anyhow::Result<LoggedTransaction> {
+        let transaction = self.call(
+            "getTransaction",
+            json!([
+                signature,
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+            ]),
+        )?;
+        Ok(LoggedTransaction {
+            signature: signature.to_string(),
+            events: logged_events(*program_id, &transaction),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// `getSignaturesForAddress` over a fixed history, newest first, with
+    /// every tenth transaction failed.
+    struct PagedRpc {
+        history: Vec<String>,
+        pages: Cell<usize>,
+    }
+
+    impl PagedRpc {
+        fn new(len: usize) -> Self {
+            let history = (0..len).rev().map(|i| format!("sig{i}")).collect();
+            Self { history, pages: Cell::new(0) }
+        }
+    }
+
+    impl Transport for PagedRpc {
+        fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+            assert_eq!(method, "getSignaturesForAddress");
+            self.pages.set(self.pages.get() + 1);
+            let config = &params[1];
+            let position = |key: &str| {
+                config[key].as_str().map(|signature| self.history.iter().position(|s| s == signature).unwrap())
+            };
+            let start = position("before").map_or(0, |i| i + 1);
+            let end = position("until").unwrap_or(self.history.len()).max(start);
+            let limit = config["limit"].as_u64().unwrap() as usize;
+            let page: Vec<Value> = self.history[start..end]
+                .iter()
+                .take(limit)
+                .map(|signature| {
+                    let failed = signature.trim_start_matches("sig").parse::<usize>().unwrap() % 10 == 9;
+                    let err = if failed { json!({ "InstructionError": [0, "Custom"] }) } else { Value::Null };
+                    json!({ "signature": signature, "err": err })
+                })
+                .collect();
+            Ok(Value::Array(page))
+        }
+    }
+
+    #[test]
+    fn test_signatures_resume_from_oldest_pending() {
+        let rpc = RpcClient::with_transport(PagedRpc::new(2_500));
+        let program_id = Pubkey::new_unique();
+        let successful: Vec<String> = (0..2_500).filter(|i| i % 10 != 9).map(|i| format!("sig{i}")).collect();
+
+        // More pending than the limit: the oldest come first, newer ones wait
+        let first = rpc.signatures(&program_id, None, 100).unwrap();
+        assert_eq!(first, successful[..100]);
+        assert_eq!(rpc.transport.pages.get(), 3);
+
+        let second = rpc.signatures(&program_id, first.last().map(String::as_str), 2_000).unwrap();
+        assert_eq!(second, successful[100..2_100]);
+        let third = rpc.signatures(&program_id, second.last().map(String::as_str), 2_000).unwrap();
+        assert_eq!(third, successful[2_100..]);
+        let done = rpc.signatures(&program_id, third.last().map(String::as_str), 2_000).unwrap();
+        assert!(done.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+//! Replays recorded logs of one election: three candidates, two voters, a
+//! rejected double vote, a changed ballot and finalization.
+
+use anchor_lang::prelude::Pubkey;
+use vote_d_21_indexer::db::{CHOICE_NEGATIVE, CHOICE_POSITIVE};
+use vote_d_21_indexer::logs::dump_events;
+use vote_d_21_indexer::Database;
+
+const SOLANA_LOGS: &str = include_str!("fixtures/solana_logs.txt");
+const TRANSACTIONS: &str = include_str!("fixtures/transactions.json");
+
+/// Fixture accounts are filled with a single repeated byte
+fn key(byte: u8) -> String {
+    Pubkey::new_from_array([byte; 32]).to_string()
+}
+
+fn indexed(dump: &str) -> Database {
+    let mut db = Database::open_in_memory().unwrap();
+    let events = dump_events(vote_d_21::ID, dump).unwrap();
+    let stats = db.ingest(&events).unwrap();
+    assert_eq!(stats.applied, 11);
+    assert_eq!(stats.skipped, 0);
+    db
+}
+
+fn assert_final_state(db: &Database) {
+    let (election, alice, bob, first, second) = (key(2), key(3), key(4), key(6), key(8));
+
+    let elections = db.elections().unwrap();
+    assert_eq!(elections.len(), 1);
+    assert_eq!(elections[0].address, election);
+    assert_eq!(elections[0].title.as_deref(), Some("Student council"));
+    assert_eq!(elections[0].status, "Finalized");
+    assert_eq!((elections[0].ballots_cast, elections[0].registered_voters), (2, 2));
+    assert_eq!(elections[0].winners, vec![alice.clone(), bob.clone()]);
+
+    let candidates = db.candidates(&election).unwrap();
+    let standings: Vec<(&str, u64, u64)> = candidates
+        .iter()
+        .map(|c| (c.name.as_deref().unwrap(), c.votes, c.negative_votes))
+        .collect();
+    assert_eq!(standings.len(), 3);
+    assert!(standings.contains(&("alice", 1, 0)));
+    assert!(standings.contains(&("bob", 1, 0)));
+    assert_eq!(standings[2], ("carol", 0, 0));
+
+    let voters = db.voters(&election).unwrap();
+    assert_eq!(voters.len(), 2);
+    assert!(voters.iter().all(|voter| voter.voted));
+
+    // The second voter's rejected double vote left their ballot alone
+    let ballots = db.ballots(&election, Some(&second)).unwrap();
+    assert_eq!(ballots.len(), 1);
+    assert_eq!((ballots[0].candidate.as_str(), ballots[0].choice.as_str()), (alice.as_str(), CHOICE_POSITIVE));
+
+    // The first voter's changed ballot replaced the original, negative vote included
+    let ballots = db.ballots(&election, Some(&first)).unwrap();
+    assert_eq!(ballots.len(), 1);
+    assert_eq!(ballots[0].candidate, bob);
+    assert!(db.ballots(&election, None).unwrap().iter().all(|b| b.choice != CHOICE_NEGATIVE));
+}
+
+#[test]
+fn test_replay_solana_logs_dump() {
+    assert_final_state(&indexed(SOLANA_LOGS));
+}
+
+#[test]
+fn test_replay_transactions_json() {
+    assert_final_state(&indexed(TRANSACTIONS));
+}
+
+#[test]
+fn test_replay_is_idempotent() {
+    let mut db = indexed(SOLANA_LOGS);
+    db.ingest(&dump_events(vote_d_21::ID, TRANSACTIONS).unwrap()).unwrap();
+    assert_final_state(&db);
+}
+
+#[test]
+fn test_other_program_ignored() {
+    let events = dump_events(Pubkey::new_unique(), SOLANA_LOGS).unwrap();
+    assert!(events.is_empty());
+}
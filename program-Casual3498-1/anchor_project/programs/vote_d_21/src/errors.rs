@@ -15,11 +15,207 @@ pub enum VoteError {
     #[msg("Unauthorized access: signer does not match voter authority.")]
     UnauthorizedAccess,
 
+    // Deprecated: no longer returned, kept so later error codes stay stable
     #[msg("Account has already been initialized.")]
     AccountAlreadyInitialized,
 
     #[msg("Vote count overflow detected.")]
     VoteOverflow,
+
+    #[msg("Election title exceeds the maximum length.")]
+    TitleTooLong,
+
+    #[msg("Election has reached the maximum number of candidates.")]
+    TooManyCandidates,
+
+    #[msg("Election is not active.")]
+    ElectionNotActive,
+
+    #[msg("Voting window must end after it starts.")]
+    InvalidVotingWindow,
+
+    #[msg("Voting has not started yet.")]
+    VotingNotStarted,
+
+    #[msg("Voting has already ended.")]
+    VotingEnded,
+
+    #[msg("Voting has not ended yet.")]
+    VotingNotEnded,
+
+    #[msg("Invalid vote configuration: need at least one positive vote and at most one negative vote per two positive votes.")]
+    InvalidVoteConfig,
+
+    #[msg("Ballot must contain at least one positive vote.")]
+    EmptyBallot,
+
+    #[msg("Ballot exceeds the allowed number of positive votes.")]
+    TooManyVotes,
+
+    #[msg("Ballot exceeds the allowed number of negative votes.")]
+    TooManyNegativeVotes,
+
+    #[msg("A negative vote requires at least two positive votes.")]
+    NegativeVoteNotAllowed,
+
+    #[msg("Registrar is already allow-listed.")]
+    RegistrarAlreadyExists,
+
+    #[msg("Registrar is not allow-listed.")]
+    RegistrarNotFound,
+
+    #[msg("Election has reached the maximum number of registrars.")]
+    TooManyRegistrars,
+
+    #[msg("No admin transfer is pending.")]
+    NoPendingAdmin,
+
+    #[msg("Election configuration can only change before voting opens.")]
+    ConfigurationLocked,
+
+    #[msg("Voter is not eligible for this election.")]
+    IneligibleVoter,
+
+    #[msg("Token account is missing or does not hold the election's governance mint.")]
+    InvalidTokenAccount,
+
+    #[msg("Token balance is too low to vote in this election.")]
+    InsufficientTokenBalance,
+
+    #[msg("Voter has no voting weight.")]
+    NoVotingWeight,
+
+    #[msg("This election does not lock governance tokens.")]
+    TokensNotLockable,
+
+    #[msg("Voter has not cast a ballot.")]
+    NotVoted,
+
+    #[msg("Vote count underflow detected.")]
+    VoteUnderflow,
+
+    #[msg("Election has already been finalized.")]
+    ElectionAlreadyFinalized,
+
+    #[msg("This election uses commit-reveal; commit and reveal the ballot instead.")]
+    CommitRevealRequired,
+
+    #[msg("This election does not use commit-reveal.")]
+    NotCommitReveal,
+
+    #[msg("Voter has no ballot commitment to reveal.")]
+    NoCommitment,
+
+    #[msg("Revealed ballot and salt do not match the commitment.")]
+    InvalidReveal,
+
+    #[msg("The reveal window has ended.")]
+    RevealEnded,
+
+    #[msg("Locked tokens must be unlocked before closing the voter account.")]
+    TokensStillLocked,
+
+    #[msg("Rent must be refunded to the account's original payer.")]
+    InvalidPayer,
+
+    #[msg("Voter has already delegated their ballot.")]
+    AlreadyDelegated,
+
+    #[msg("Voter has not delegated their ballot.")]
+    NotDelegated,
+
+    #[msg("Voter has delegated their ballot and cannot vote directly.")]
+    VoterDelegated,
+
+    #[msg("Delegation would create a cycle.")]
+    DelegationCycle,
+
+    #[msg("Delegation chain exceeds the maximum depth.")]
+    DelegationTooDeep,
+
+    #[msg("Delegation chain accounts are missing or out of order.")]
+    InvalidDelegationChain,
+
+    #[msg("The delegation chain ends at a voter who has already voted.")]
+    DelegateAlreadyVoted,
+
+    #[msg("Candidate description exceeds maximum length.")]
+    DescriptionTooLong,
+
+    #[msg("Candidate metadata URI exceeds maximum length.")]
+    UriTooLong,
+
+    #[msg("Candidate has not been approved for the ballot.")]
+    CandidateNotApproved,
+
+    #[msg("Candidate is not in the required status for this action.")]
+    InvalidCandidateStatus,
+
+    #[msg("Candidate has withdrawn or been disqualified.")]
+    CandidateInactive,
+
+    #[msg("Reason exceeds maximum length.")]
+    ReasonTooLong,
+
+    #[msg("Candidate name is empty.")]
+    NameEmpty,
+
+    #[msg("Candidate name exceeds maximum length.")]
+    NameTooLong,
+
+    #[msg("Candidate name contains invalid characters.")]
+    InvalidNameCharacters,
+
+    #[msg("This instruction does not apply to the election's voting method.")]
+    WrongVotingMethod,
+
+    #[msg("Rankings must list distinct candidates of this election.")]
+    InvalidRanking,
+
+    #[msg("Ballot account does not belong to this election.")]
+    InvalidBallot,
+
+    #[msg("Ballot has already been counted in this round.")]
+    BallotAlreadyCounted,
+
+    #[msg("The runoff count is already complete.")]
+    RunoffComplete,
+
+    #[msg("The runoff count has not completed yet.")]
+    RunoffNotComplete,
+
+    #[msg("Scores must be given for exactly the rated candidates of a score election.")]
+    InvalidScores,
+
+    #[msg("Score exceeds the election's maximum score.")]
+    ScoreTooHigh,
+
+    #[msg("Quorum must be between 1 and 10000 basis points.")]
+    InvalidQuorum,
+
+    #[msg("The program is paused by its emergency authority.")]
+    ProgramPaused,
+
+    #[msg("The sponsor budget cannot cover this transaction.")]
+    SponsorBudgetExhausted,
+
+    #[msg("Sponsored transactions must be paid for by the budget's relayer.")]
+    InvalidRelayer,
+
+    #[msg("Sponsored fee per transaction exceeds the maximum.")]
+    InvalidSponsorFee,
+
+    #[msg("Signed ballot does not match an ed25519 signature verified in this transaction.")]
+    InvalidBallotSignature,
+
+    #[msg("Signed ballot nonce does not match the voter's next nonce.")]
+    InvalidNonce,
+
+    #[msg("Signed ballot batch is empty or too large.")]
+    InvalidBatchSize,
+
+    #[msg("Voters have already registered under the current rules.")]
+    VotersAlreadyRegistered,
 }
 
 #[cfg(test)]
@@ -35,6 +231,71 @@ mod tests {
         let _unauthorized = VoteError::UnauthorizedAccess;
         let _reinit = VoteError::AccountAlreadyInitialized;
         let _overflow = VoteError::VoteOverflow;
+        let _title = VoteError::TitleTooLong;
+        let _too_many = VoteError::TooManyCandidates;
+        let _not_active = VoteError::ElectionNotActive;
+        let _window = VoteError::InvalidVotingWindow;
+        let _not_started = VoteError::VotingNotStarted;
+        let _ended = VoteError::VotingEnded;
+        let _not_ended = VoteError::VotingNotEnded;
+        let _config = VoteError::InvalidVoteConfig;
+        let _empty = VoteError::EmptyBallot;
+        let _too_many_votes = VoteError::TooManyVotes;
+        let _too_many_negative = VoteError::TooManyNegativeVotes;
+        let _negative_not_allowed = VoteError::NegativeVoteNotAllowed;
+        let _registrar_exists = VoteError::RegistrarAlreadyExists;
+        let _registrar_missing = VoteError::RegistrarNotFound;
+        let _too_many_registrars = VoteError::TooManyRegistrars;
+        let _no_pending_admin = VoteError::NoPendingAdmin;
+        let _locked = VoteError::ConfigurationLocked;
+        let _ineligible = VoteError::IneligibleVoter;
+        let _token_account = VoteError::InvalidTokenAccount;
+        let _balance = VoteError::InsufficientTokenBalance;
+        let _no_weight = VoteError::NoVotingWeight;
+        let _not_lockable = VoteError::TokensNotLockable;
+        let _not_voted = VoteError::NotVoted;
+        let _underflow = VoteError::VoteUnderflow;
+        let _finalized = VoteError::ElectionAlreadyFinalized;
+        let _commit_reveal = VoteError::CommitRevealRequired;
+        let _not_commit_reveal = VoteError::NotCommitReveal;
+        let _no_commitment = VoteError::NoCommitment;
+        let _invalid_reveal = VoteError::InvalidReveal;
+        let _reveal_ended = VoteError::RevealEnded;
+        let _still_locked = VoteError::TokensStillLocked;
+        let _invalid_payer = VoteError::InvalidPayer;
+        let _already_delegated = VoteError::AlreadyDelegated;
+        let _not_delegated = VoteError::NotDelegated;
+        let _voter_delegated = VoteError::VoterDelegated;
+        let _cycle = VoteError::DelegationCycle;
+        let _too_deep = VoteError::DelegationTooDeep;
+        let _invalid_chain = VoteError::InvalidDelegationChain;
+        let _delegate_voted = VoteError::DelegateAlreadyVoted;
+        let _description = VoteError::DescriptionTooLong;
+        let _uri = VoteError::UriTooLong;
+        let _not_approved = VoteError::CandidateNotApproved;
+        let _candidate_status = VoteError::InvalidCandidateStatus;
+        let _inactive = VoteError::CandidateInactive;
+        let _reason = VoteError::ReasonTooLong;
+        let _name_empty = VoteError::NameEmpty;
+        let _name_too_long = VoteError::NameTooLong;
+        let _name_chars = VoteError::InvalidNameCharacters;
+        let _wrong_method = VoteError::WrongVotingMethod;
+        let _invalid_ranking = VoteError::InvalidRanking;
+        let _invalid_ballot = VoteError::InvalidBallot;
+        let _already_counted = VoteError::BallotAlreadyCounted;
+        let _runoff_complete = VoteError::RunoffComplete;
+        let _runoff_incomplete = VoteError::RunoffNotComplete;
+        let _invalid_scores = VoteError::InvalidScores;
+        let _score_too_high = VoteError::ScoreTooHigh;
+        let _invalid_quorum = VoteError::InvalidQuorum;
+        let _paused = VoteError::ProgramPaused;
+        let _exhausted = VoteError::SponsorBudgetExhausted;
+        let _invalid_relayer = VoteError::InvalidRelayer;
+        let _invalid_fee = VoteError::InvalidSponsorFee;
+        let _invalid_signature = VoteError::InvalidBallotSignature;
+        let _invalid_nonce = VoteError::InvalidNonce;
+        let _invalid_batch = VoteError::InvalidBatchSize;
+        let _registered = VoteError::VotersAlreadyRegistered;
     }
 
     #[test]
@@ -59,19 +320,100 @@ mod tests {
         assert_ne!(reinit, overflow);
     }
 
+    #[test]
+    fn test_baseline_error_codes_stable() {
+        // Deployed clients match on these codes, so variants are only appended
+        let baseline = [
+            VoteError::AlreadyVoted,
+            VoteError::DuplicateCandidates,
+            VoteError::InvalidCandidate,
+            VoteError::UnauthorizedAccess,
+            VoteError::AccountAlreadyInitialized,
+            VoteError::VoteOverflow,
+        ];
+        for (offset, error) in baseline.into_iter().enumerate() {
+            assert_eq!(u32::from(error), 6000 + offset as u32);
+        }
+    }
+
     #[test]
     fn test_error_count() {
-        // Ensure we have exactly 6 error types
+        // Ensure we have exactly 71 error types
         // This helps catch if we accidentally add/remove errors
-        let errors = vec![
+        let errors = [
             VoteError::AlreadyVoted,
             VoteError::DuplicateCandidates,
             VoteError::InvalidCandidate,
             VoteError::UnauthorizedAccess,
             VoteError::AccountAlreadyInitialized,
             VoteError::VoteOverflow,
+            VoteError::TitleTooLong,
+            VoteError::TooManyCandidates,
+            VoteError::ElectionNotActive,
+            VoteError::InvalidVotingWindow,
+            VoteError::VotingNotStarted,
+            VoteError::VotingEnded,
+            VoteError::VotingNotEnded,
+            VoteError::InvalidVoteConfig,
+            VoteError::EmptyBallot,
+            VoteError::TooManyVotes,
+            VoteError::TooManyNegativeVotes,
+            VoteError::NegativeVoteNotAllowed,
+            VoteError::RegistrarAlreadyExists,
+            VoteError::RegistrarNotFound,
+            VoteError::TooManyRegistrars,
+            VoteError::NoPendingAdmin,
+            VoteError::ConfigurationLocked,
+            VoteError::IneligibleVoter,
+            VoteError::InvalidTokenAccount,
+            VoteError::InsufficientTokenBalance,
+            VoteError::NoVotingWeight,
+            VoteError::TokensNotLockable,
+            VoteError::NotVoted,
+            VoteError::VoteUnderflow,
+            VoteError::ElectionAlreadyFinalized,
+            VoteError::CommitRevealRequired,
+            VoteError::NotCommitReveal,
+            VoteError::NoCommitment,
+            VoteError::InvalidReveal,
+            VoteError::RevealEnded,
+            VoteError::TokensStillLocked,
+            VoteError::InvalidPayer,
+            VoteError::AlreadyDelegated,
+            VoteError::NotDelegated,
+            VoteError::VoterDelegated,
+            VoteError::DelegationCycle,
+            VoteError::DelegationTooDeep,
+            VoteError::InvalidDelegationChain,
+            VoteError::DelegateAlreadyVoted,
+            VoteError::DescriptionTooLong,
+            VoteError::UriTooLong,
+            VoteError::CandidateNotApproved,
+            VoteError::InvalidCandidateStatus,
+            VoteError::CandidateInactive,
+            VoteError::ReasonTooLong,
+            VoteError::NameEmpty,
+            VoteError::NameTooLong,
+            VoteError::InvalidNameCharacters,
+            VoteError::WrongVotingMethod,
+            VoteError::InvalidRanking,
+            VoteError::InvalidBallot,
+            VoteError::BallotAlreadyCounted,
+            VoteError::RunoffComplete,
+            VoteError::RunoffNotComplete,
+            VoteError::InvalidScores,
+            VoteError::ScoreTooHigh,
+            VoteError::InvalidQuorum,
+            VoteError::ProgramPaused,
+            VoteError::SponsorBudgetExhausted,
+            VoteError::InvalidRelayer,
+            VoteError::InvalidSponsorFee,
+            VoteError::InvalidBallotSignature,
+            VoteError::InvalidNonce,
+            VoteError::InvalidBatchSize,
+            VoteError::VotersAlreadyRegistered,
         ];
-        assert_eq!(errors.len(), 6);
+        assert_eq!(errors.len(), 71);
     }
 }
 
@@ -1,66 +1,564 @@
 use anchor_lang::prelude::*;
+use crate::state::{CandidateAccount, EligibilityMode, VoteWeighting, VotingMethod};
+
+/// Schema version of every event. Bumped whenever an event's fields change,
+/// so indexers can decode events logged by older program versions.
+pub const EVENT_VERSION: u8 = 1;
+
+/// Common prefix of every event: schema version and when it was emitted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EventHeader {
+    pub version: u8,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+impl EventHeader {
+    /// Header stamped with the current slot and cluster time.
+    pub fn now() -> Result<Self> {
+        let clock = Clock::get()?;
+        Ok(Self {
+            version: EVENT_VERSION,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+/// A candidate's tallies after a ballot was applied
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CandidateTally {
+    pub candidate: Pubkey,
+    pub votes: u64,
+    pub negative_votes: u64,
+    pub score_sum: u64,
+}
+
+impl CandidateTally {
+    pub fn new(key: Pubkey, candidate: &CandidateAccount) -> Self {
+        Self {
+            candidate: key,
+            votes: candidate.votes,
+            negative_votes: candidate.negative_votes,
+            score_sum: candidate.score_sum,
+        }
+    }
+}
+
+/// Event emitted when an election is created
+#[event]
+pub struct ElectionCreated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub creator: Pubkey,
+    pub title: String,
+}
+
+/// Event emitted when an election's voting window opens
+#[event]
+pub struct ElectionOpened {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+/// Event emitted when an election's voting window has ended and tallies are final
+#[event]
+pub struct ElectionClosed {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub closed_at: i64,
+}
+
+/// Event emitted when a registrar is allow-listed
+#[event]
+pub struct RegistrarAdded {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub registrar: Pubkey,
+}
+
+/// Event emitted when a registrar is removed from the allow-list
+#[event]
+pub struct RegistrarRemoved {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub registrar: Pubkey,
+}
+
+/// Event emitted when the admin nominates a successor
+#[event]
+pub struct AdminTransferStarted {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// Event emitted when the nominated successor accepts the admin role
+#[event]
+pub struct AdminTransferred {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/// Event emitted when the admin changes who may register as a voter
+#[event]
+pub struct EligibilityUpdated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub mode: EligibilityMode,
+}
+
+/// Event emitted when a wallet is whitelisted
+#[event]
+pub struct VoterWhitelisted {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+}
+
+/// Event emitted when a wallet is removed from the whitelist
+#[event]
+pub struct VoterRemovedFromWhitelist {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+}
+
+/// Event emitted when the admin changes how ballots are weighted
+#[event]
+pub struct VoteWeightingUpdated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub weighting: VoteWeighting,
+}
+
+/// Event emitted when a voter escrows governance tokens
+#[event]
+pub struct TokensLocked {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when escrowed governance tokens are returned
+#[event]
+pub struct TokensUnlocked {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+}
+
+/// Event emitted when a wallet registers as a voter
+#[event]
+pub struct VoterRegistered {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    /// The voter account
+    pub voter: Pubkey,
+    pub authority: Pubkey,
+    pub weight: u64,
+    pub registered_voters: u32,
+}
+
+/// Event emitted when a voter account is closed after finalization
+#[event]
+pub struct VoterClosed {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub authority: Pubkey,
+}
 
 /// Event emitted when a candidate is initialized
 #[event]
 pub struct CandidateInitialized {
+    pub header: EventHeader,
+    pub election: Pubkey,
     pub name: String,
     pub pubkey: Pubkey,
 }
 
+/// Event emitted when a wallet self-nominates as a candidate
+#[event]
+pub struct CandidateNominated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub name: String,
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// Event emitted when the admin approves a nomination
+#[event]
+pub struct CandidateApproved {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub pubkey: Pubkey,
+}
+
+/// Event emitted when the admin rejects a nomination
+#[event]
+pub struct CandidateRejected {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub pubkey: Pubkey,
+}
+
+/// Event emitted when a candidate's owner withdraws from the race
+#[event]
+pub struct CandidateWithdrawn {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub pubkey: Pubkey,
+    pub reason: String,
+}
+
+/// Event emitted when the admin disqualifies a candidate
+#[event]
+pub struct CandidateDisqualified {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub pubkey: Pubkey,
+    pub reason: String,
+}
+
+/// Event emitted when a candidate account is closed after finalization
+#[event]
+pub struct CandidateClosed {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub pubkey: Pubkey,
+}
+
 /// Event emitted when a vote is cast
 #[event]
 pub struct VoteCast {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub positive: Vec<Pubkey>,
+    pub negative: Vec<Pubkey>,
+    pub scores: Vec<u8>,
+    pub weight: u64,
+    /// Tallies of every candidate on the ballot after it was counted
+    pub tallies: Vec<CandidateTally>,
+    pub ballots_cast: u32,
+}
+
+/// Event emitted when an election's results have been ranked and recorded
+#[event]
+pub struct ElectionFinalized {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub results: Pubkey,
+    pub winners: Vec<Pubkey>,
+    pub is_tie: bool,
+    pub registered_voters: u32,
+    pub ballots_cast: u32,
+    pub quorum_reached: bool,
+    pub finalized_at: i64,
+}
+
+/// Event emitted when the admin enables or disables commit-reveal ballots
+#[event]
+pub struct CommitRevealUpdated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub reveal_ends_at: Option<i64>,
+}
+
+/// Event emitted when the admin sets or clears the election's quorum
+#[event]
+pub struct QuorumUpdated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub quorum_bps: Option<u16>,
+}
+
+/// Event emitted when a voter commits to a hidden ballot
+#[event]
+pub struct VoteCommitted {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub commitment: [u8; 32],
+}
+
+/// Event emitted when a voter delegates their ballot
+#[event]
+pub struct VoteDelegated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    /// Voter account the ballot was delegated to
+    pub delegate: Pubkey,
+    /// Authority of the voter at the end of the delegation chain
+    pub representative: Pubkey,
+    pub weight: u64,
+}
+
+/// Event emitted when a voter takes back a delegated ballot
+#[event]
+pub struct VoteUndelegated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub delegate: Pubkey,
+    pub weight: u64,
+}
+
+/// Event emitted when the admin changes how ballots are cast and counted
+#[event]
+pub struct VotingMethodUpdated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub method: VotingMethod,
+}
+
+/// Event emitted when an instant-runoff ballot is cast
+#[event]
+pub struct RankedBallotCast {
+    pub header: EventHeader,
+    pub election: Pubkey,
     pub voter: Pubkey,
-    pub candidates: [Pubkey; 2],
+    pub rankings: Vec<Pubkey>,
+    pub weight: u64,
+    pub ballots_cast: u32,
+}
+
+/// Event emitted when instant-runoff counting begins
+#[event]
+pub struct RunoffStarted {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    /// Candidates off the ballot before the first round
+    pub eliminated: Vec<Pubkey>,
+}
+
+/// Event emitted when a runoff round has counted every ballot
+#[event]
+pub struct RunoffRoundCompleted {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub round: u8,
+    /// Counts per candidate, in `election.candidates` order
+    pub counts: Vec<u64>,
+    pub eliminated: Option<Pubkey>,
+    pub winner: Option<Pubkey>,
+}
+
+/// Event emitted when a voter replaces their ballot
+#[event]
+pub struct VoteChanged {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub previous_positive: Vec<Pubkey>,
+    pub previous_negative: Vec<Pubkey>,
+    pub previous_scores: Vec<u8>,
+    pub positive: Vec<Pubkey>,
+    pub negative: Vec<Pubkey>,
+    pub scores: Vec<u8>,
+    pub weight: u64,
+    /// Tallies of every candidate on either ballot after the change
+    pub tallies: Vec<CandidateTally>,
+}
+
+/// Event emitted when a voter withdraws their ballot
+#[event]
+pub struct VoteRevoked {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub voter: Pubkey,
+    pub positive: Vec<Pubkey>,
+    pub negative: Vec<Pubkey>,
+    pub scores: Vec<u8>,
+    pub weight: u64,
+    /// Tallies of every candidate on the withdrawn ballot after it was retracted
+    pub tallies: Vec<CandidateTally>,
+    pub ballots_cast: u32,
+}
+
+/// Event emitted when a voter from the first deployment is moved into an election
+#[event]
+pub struct VoterMigrated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    /// The closed legacy account
+    pub legacy: Pubkey,
+    pub voter: Pubkey,
+    pub version: u8,
+}
+
+/// Event emitted when a candidate from the first deployment is moved into an election
+#[event]
+pub struct CandidateMigrated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    /// The closed legacy account
+    pub legacy: Pubkey,
+    pub pubkey: Pubkey,
+    pub version: u8,
+}
+
+/// Event emitted when the upgrade authority creates the program config
+#[event]
+pub struct ConfigInitialized {
+    pub header: EventHeader,
+    pub emergency_authority: Pubkey,
+}
+
+/// Event emitted when the admin sets up an election's sponsor budget
+#[event]
+pub struct SponsorBudgetCreated {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub sponsor: Pubkey,
+    pub relayer: Pubkey,
+    pub fee_per_transaction: u64,
+}
+
+/// Event emitted when lamports are deposited into a sponsor budget
+#[event]
+pub struct SponsorBudgetFunded {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    /// Lamports available for reimbursements after the deposit
+    pub available: u64,
+}
+
+/// Event emitted when the sponsor takes lamports back out of a budget
+#[event]
+pub struct SponsorBudgetWithdrawn {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub available: u64,
+}
+
+/// Event emitted when a sponsor budget reimburses the relayer for a transaction
+#[event]
+pub struct RelayerReimbursed {
+    pub header: EventHeader,
+    pub election: Pubkey,
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub spent: u64,
+    pub available: u64,
+}
+
+/// Event emitted when the emergency authority halts registration and voting
+#[event]
+pub struct Paused {
+    pub header: EventHeader,
+    pub authority: Pubkey,
+}
+
+/// Event emitted when the emergency authority resumes registration and voting
+#[event]
+pub struct Unpaused {
+    pub header: EventHeader,
+    pub authority: Pubkey,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn header() -> EventHeader {
+        EventHeader { version: EVENT_VERSION, slot: 42, timestamp: 1_700_000_000 }
+    }
+
     #[test]
     fn test_candidate_initialized_event_creation() {
         let name = String::from("Alice");
         let pubkey = Pubkey::new_unique();
 
+        let election = Pubkey::new_unique();
+
         let event = CandidateInitialized {
+            header: header(),
+            election,
             name: name.clone(),
             pubkey,
         };
 
+        assert_eq!(event.election, election);
         assert_eq!(event.name, name);
         assert_eq!(event.pubkey, pubkey);
     }
 
+    #[test]
+    fn test_election_created_event_creation() {
+        let election = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let event = ElectionCreated {
+            header: header(),
+            election,
+            creator,
+            title: String::from("Board 2025"),
+        };
+
+        assert_eq!(event.election, election);
+        assert_eq!(event.creator, creator);
+        assert_eq!(event.title, "Board 2025");
+    }
+
     #[test]
     fn test_vote_cast_event_creation() {
         let voter = Pubkey::new_unique();
         let candidate1 = Pubkey::new_unique();
         let candidate2 = Pubkey::new_unique();
-        let candidates = [candidate1, candidate2];
+        let candidate3 = Pubkey::new_unique();
 
         let event = VoteCast {
+            header: header(),
+            election: Pubkey::new_unique(),
             voter,
-            candidates,
+            positive: vec![candidate1, candidate2],
+            negative: vec![candidate3],
+            scores: Vec::new(),
+            weight: 1,
+            tallies: Vec::new(),
+            ballots_cast: 1,
         };
 
         assert_eq!(event.voter, voter);
-        assert_eq!(event.candidates[0], candidate1);
-        assert_eq!(event.candidates[1], candidate2);
-        assert_eq!(event.candidates.len(), 2);
+        assert_eq!(event.positive, vec![candidate1, candidate2]);
+        assert_eq!(event.negative, vec![candidate3]);
     }
 
     #[test]
-    fn test_vote_cast_candidates_array() {
+    fn test_vote_cast_without_negative_votes() {
         let voter = Pubkey::new_unique();
-        let candidates = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let positive = vec![Pubkey::new_unique()];
 
-        let event = VoteCast { voter, candidates };
+        let event = VoteCast {
+            header: header(),
+            election: Pubkey::new_unique(),
+            voter,
+            positive: positive.clone(),
+            negative: Vec::new(),
+            scores: Vec::new(),
+            weight: 1_000,
+            tallies: Vec::new(),
+            ballots_cast: 1,
+        };
 
-        // Verify array has exactly 2 elements
-        assert_eq!(event.candidates.len(), 2);
-        // Verify candidates are different
-        assert_ne!(event.candidates[0], event.candidates[1]);
+        assert_eq!(event.positive, positive);
+        assert!(event.negative.is_empty());
+        assert_eq!(event.weight, 1_000);
     }
 
     #[test]
@@ -68,7 +566,7 @@ mod tests {
         let name = String::from("");
         let pubkey = Pubkey::new_unique();
 
-        let event = CandidateInitialized { name: name.clone(), pubkey };
+        let event = CandidateInitialized { header: header(), election: Pubkey::new_unique(), name: name.clone(), pubkey };
 
         assert_eq!(event.name, "");
         assert!(event.name.is_empty());
@@ -80,6 +578,8 @@ mod tests {
         let pubkey = Pubkey::new_unique();
 
         let event = CandidateInitialized {
+            header: header(),
+            election: Pubkey::new_unique(),
             name: name.clone(),
             pubkey,
         };
@@ -87,7 +587,68 @@ mod tests {
         assert_eq!(event.name.len(), 32);
         assert_eq!(event.name, name);
     }
-}
 
+    #[test]
+    fn test_vote_changed_event() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
 
+        let event = VoteChanged {
+            header: header(),
+            election: Pubkey::new_unique(),
+            voter: Pubkey::new_unique(),
+            previous_positive: vec![a, b],
+            previous_negative: vec![c],
+            previous_scores: Vec::new(),
+            positive: vec![c],
+            negative: Vec::new(),
+            scores: Vec::new(),
+            weight: 1,
+            tallies: Vec::new(),
+        };
 
+        assert_eq!(event.previous_positive, vec![a, b]);
+        assert_eq!(event.positive, vec![c]);
+        assert!(event.negative.is_empty());
+    }
+
+    #[test]
+    fn test_event_header_leads_serialized_event() {
+        let event = VoterRegistered {
+            header: header(),
+            election: Pubkey::new_unique(),
+            voter: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            weight: 1,
+            registered_voters: 3,
+        };
+        // Indexers read the version from the first byte after the discriminator
+        let data = anchor_lang::Event::data(&event);
+        assert_eq!(data[..8], *VoterRegistered::DISCRIMINATOR);
+        assert_eq!(data[8], EVENT_VERSION);
+        assert_eq!(data[9..17], 42u64.to_le_bytes());
+        assert_eq!(data[17..25], 1_700_000_000i64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_candidate_tally_copies_counts() {
+        let key = Pubkey::new_unique();
+        let candidate = CandidateAccount {
+            version: CandidateAccount::VERSION,
+            election: Pubkey::default(),
+            name: String::from("Alice"),
+            description: String::new(),
+            uri: String::new(),
+            owner: Pubkey::default(),
+            status: crate::state::CandidateStatus::Approved,
+            votes: 7,
+            negative_votes: 2,
+            score_sum: 30,
+            payer: Pubkey::default(),
+            bump: 255,
+        };
+
+        let tally = CandidateTally::new(key, &candidate);
+        assert_eq!(tally.candidate, key);
+        assert_eq!((tally.votes, tally.negative_votes, tally.score_sum), (7, 2, 30));
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Complete a two-step admin transfer.
+/// Must be signed by the pending admin nominated through `transfer_admin`.
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let new_admin = ctx.accounts.new_admin.key();
+
+    let pending_admin = election.pending_admin.ok_or(VoteError::NoPendingAdmin)?;
+    require_keys_eq!(pending_admin, new_admin, VoteError::UnauthorizedAccess);
+
+    let previous_admin = election.admin;
+    election.admin = new_admin;
+    election.pending_admin = None;
+
+    emit!(AdminTransferred {
+        header: EventHeader::now()?,
+        election: election.key(),
+        previous_admin,
+        new_admin,
+    });
+
+    msg!("Admin transferred to: {}", new_admin);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    pub new_admin: Signer<'info>,
+}
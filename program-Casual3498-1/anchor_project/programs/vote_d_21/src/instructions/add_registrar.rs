@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Allow-list a registrar who may register candidates alongside the admin.
+/// Only the election admin may add registrars.
+pub fn add_registrar(ctx: Context<AddRegistrar>, registrar: Pubkey) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+
+    require!(
+        !election.is_registrar(&registrar),
+        VoteError::RegistrarAlreadyExists
+    );
+    require!(
+        election.registrars.len() < Election::MAX_REGISTRARS,
+        VoteError::TooManyRegistrars
+    );
+
+    election.registrars.push(registrar);
+
+    emit!(RegistrarAdded {
+        header: EventHeader::now()?,
+        election: election.key(),
+        registrar,
+    });
+
+    msg!("Registrar added: {}", registrar);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddRegistrar<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
+
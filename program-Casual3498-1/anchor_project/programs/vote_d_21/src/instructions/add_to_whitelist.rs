@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Whitelist a wallet so it may register as a voter.
+/// Seeds: ["whitelist", election.key().as_ref(), voter.as_ref()]
+/// Admin only. Fails if the wallet is already whitelisted.
+pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, voter: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.whitelist_entry;
+    entry.election = ctx.accounts.election.key();
+    entry.voter = voter;
+    entry.bump = ctx.bumps.whitelist_entry;
+
+    emit!(VoterWhitelisted {
+        header: EventHeader::now()?,
+        election: entry.election,
+        voter,
+    });
+
+    msg!("Voter whitelisted: {}", voter);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct AddToWhitelist<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = WhitelistEntry::SPACE,
+        seeds = [b"whitelist", election.key().as_ref(), voter.as_ref()],
+        bump
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Approve a self-nominated candidate and add it to the ballot.
+/// Admin only, while registration is open and the election has room.
+/// Not available while the program is paused.
+pub fn approve_candidate(ctx: Context<ApproveCandidate>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+    require!(
+        election.candidates.len() < Election::MAX_CANDIDATES,
+        VoteError::TooManyCandidates
+    );
+
+    let candidate = &mut ctx.accounts.candidate;
+    require!(
+        candidate.status == CandidateStatus::Nominated,
+        VoteError::InvalidCandidateStatus
+    );
+    candidate.status = CandidateStatus::Approved;
+
+    election.candidates.push(candidate.key());
+
+    emit!(CandidateApproved {
+        header: EventHeader::now()?,
+        election: election.key(),
+        pubkey: candidate.key(),
+    });
+
+    msg!("Candidate approved: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveCandidate<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&candidate.name)],
+        bump = candidate.bump
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
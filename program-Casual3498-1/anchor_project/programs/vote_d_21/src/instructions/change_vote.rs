@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::vote::{candidate_tallies, load_candidates, tally_ballot};
+
+/// Replace a previously cast ballot while the voting window is still open.
+/// The stored choices are retracted and the new ones recorded in a single
+/// instruction, so tallies never reflect a half-applied change.
+/// Candidate accounts are passed as remaining accounts in `Ballot::union`
+/// order: the previous ballot's candidates, then those only on the new one.
+/// Not available while the program is paused.
+pub fn change_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ChangeVote<'info>>,
+    ballot: Ballot,
+) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(voter.voted, VoteError::NotVoted);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    ballot.validate(election)?;
+
+    let previous = voter.ballot();
+    let keys = previous.union(&ballot);
+    let mut candidates = load_candidates(ctx.remaining_accounts, &election.key(), &keys)?;
+
+    // The weight, including delegations, is fixed once a ballot is cast,
+    // so it matches what was recorded
+    let weight = voter.vote_weight()?;
+    tally_ballot(&mut candidates, &previous, weight, false)?;
+    tally_ballot(&mut candidates, &ballot, weight, true)?;
+    for candidate in candidates.iter() {
+        candidate.exit(&crate::ID)?;
+    }
+
+    voter.set_ballot(&ballot);
+
+    emit!(VoteChanged {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        previous_positive: previous.positive,
+        previous_negative: previous.negative,
+        previous_scores: previous.scores,
+        positive: ballot.positive,
+        negative: ballot.negative,
+        scores: ballot.scores,
+        weight,
+        tallies: candidate_tallies(&candidates),
+    });
+
+    msg!(
+        "Vote changed: {} positive, {} negative",
+        voter.votes.len(),
+        voter.negative_votes.len()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
+
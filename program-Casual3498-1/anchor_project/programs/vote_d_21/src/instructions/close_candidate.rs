@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Close a candidate account once the election is finalized, refunding rent
+/// to the wallet that paid for it. Its tally is preserved in `Results`.
+/// Admin only.
+pub fn close_candidate(ctx: Context<CloseCandidate>) -> Result<()> {
+    emit!(CandidateClosed {
+        header: EventHeader::now()?,
+        election: ctx.accounts.election.key(),
+        pubkey: ctx.accounts.candidate.key(),
+    });
+
+    msg!("Candidate account closed: {}", ctx.accounts.candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseCandidate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    /// Only exists once the election has been finalized
+    #[account(
+        seeds = [b"results", election.key().as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, Results>,
+
+    #[account(
+        mut,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&candidate.name)],
+        bump = candidate.bump,
+        has_one = payer @ VoteError::InvalidPayer,
+        close = payer
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    pub admin: Signer<'info>,
+
+    /// The wallet that paid for the candidate account
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Close an election once its voting window has ended.
+/// Permissionless crank: anyone may close it after `ends_at`.
+pub fn close_election(ctx: Context<CloseElection>) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        matches!(election.status, ElectionStatus::Pending | ElectionStatus::Active),
+        VoteError::ElectionNotActive
+    );
+    require!(now >= election.ends_at, VoteError::VotingNotEnded);
+
+    election.status = ElectionStatus::Closed;
+
+    emit!(ElectionClosed {
+        header: EventHeader::now()?,
+        election: election.key(),
+        closed_at: now,
+    });
+
+    msg!("Election closed: {}", election.title);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseElection<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_election_status_transition() {
+        let mut election = Election::with_window(ElectionStatus::Active, 100, 200);
+
+        election.status = ElectionStatus::Closed;
+        assert_eq!(election.status, ElectionStatus::Closed);
+        assert_ne!(election.status, ElectionStatus::Active);
+        // A closed election no longer accepts ballots, even inside the window
+        assert!(election.check_voting_window(150).is_err());
+    }
+}
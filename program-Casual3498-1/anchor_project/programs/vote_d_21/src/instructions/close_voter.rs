@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Close a voter account once the election is finalized, refunding rent to
+/// the wallet that paid for it.
+/// Only the voter's authority may close it, and locked tokens must have been
+/// unlocked first.
+pub fn close_voter(ctx: Context<CloseVoter>) -> Result<()> {
+    let voter = &ctx.accounts.voter;
+    require!(voter.locked_amount == 0, VoteError::TokensStillLocked);
+
+    emit!(VoterClosed {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.key(),
+        authority: voter.authority,
+    });
+
+    msg!("Voter account closed for authority: {}", voter.authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVoter<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    /// Only exists once the election has been finalized
+    #[account(
+        seeds = [b"results", election.key().as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, Results>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump,
+        has_one = payer @ VoteError::InvalidPayer,
+        close = payer
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    pub authority: Signer<'info>,
+
+    /// The wallet that paid for the voter account
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
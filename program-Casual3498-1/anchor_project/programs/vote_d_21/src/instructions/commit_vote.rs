@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Commit to a hidden ballot in a commit-reveal election.
+/// `commitment` is `Ballot::commitment(salt)`: sha256(borsh(ballot) || salt).
+/// Nothing is tallied until the ballot is revealed with `reveal_vote`; a voter
+/// may replace their commitment while the voting window is open.
+/// Not available while the program is paused.
+pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+    require!(election.is_commit_reveal(), VoteError::NotCommitReveal);
+
+    let voter = &mut ctx.accounts.voter;
+    require!(!voter.voted, VoteError::AlreadyVoted);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+    voter.check_not_delegated()?;
+    require!(voter.vote_weight()? > 0, VoteError::NoVotingWeight);
+
+    voter.commitment = Some(commitment);
+
+    emit!(VoteCommitted {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        commitment,
+    });
+
+    msg!("Ballot committed");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitVote<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Delegate this voter's ballot to another voter in the same election.
+/// The delegation chain is passed as remaining accounts: the delegate's voter
+/// account first, then each voter it delegates to in turn, ending with the
+/// representative who has not delegated. The voter's own weight plus anything
+/// already delegated to them is added to every voter on the chain, so the
+/// representative votes with it.
+/// Rejects cycles, chains longer than `MAX_DELEGATION_DEPTH`, and chains whose
+/// representative has already voted.
+/// Not available while the program is paused.
+pub fn delegate<'info>(ctx: Context<'_, '_, 'info, 'info, Delegate<'info>>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(!voter.voted, VoteError::AlreadyVoted);
+    require!(voter.delegate.is_none(), VoteError::AlreadyDelegated);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    let delegate = ctx
+        .remaining_accounts
+        .first()
+        .ok_or(VoteError::InvalidDelegationChain)?
+        .key();
+    let mut chain = load_delegation_chain(
+        ctx.remaining_accounts,
+        &election.key(),
+        &voter.key(),
+        &delegate,
+    )?;
+
+    let weight = voter.vote_weight()?;
+    for link in chain.iter_mut() {
+        link.delegated_weight = link
+            .delegated_weight
+            .checked_add(weight)
+            .ok_or(VoteError::VoteOverflow)?;
+        link.exit(&crate::ID)?;
+    }
+
+    voter.delegate = Some(delegate);
+
+    let representative = chain.last().map(|link| link.authority).unwrap_or_default();
+    emit!(VoteDelegated {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        delegate,
+        representative,
+        weight,
+    });
+
+    msg!("Ballot delegated to: {}", representative);
+    Ok(())
+}
+
+/// Deserialize a delegation chain passed as remaining accounts, starting at
+/// `first`, and check it with `VoterAccount::check_delegation_chain`.
+/// Every account must be a writable voter of `election`, and the
+/// representative at the end must not have voted yet.
+pub(crate) fn load_delegation_chain<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    election: &Pubkey,
+    origin: &Pubkey,
+    first: &Pubkey,
+) -> Result<Vec<Account<'info, VoterAccount>>> {
+    require!(
+        remaining_accounts.len() <= VoterAccount::MAX_DELEGATION_DEPTH,
+        VoteError::DelegationTooDeep
+    );
+
+    let chain = remaining_accounts
+        .iter()
+        .map(|info| {
+            require!(info.is_writable, VoteError::InvalidDelegationChain);
+            let voter = Account::<VoterAccount>::try_from(info)?;
+            require_keys_eq!(voter.election, *election, VoteError::InvalidDelegationChain);
+            Ok(voter)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let links: Vec<(Pubkey, Option<Pubkey>)> =
+        chain.iter().map(|voter| (voter.key(), voter.delegate)).collect();
+    VoterAccount::check_delegation_chain(origin, first, &links)?;
+
+    let representative = chain.last().ok_or(VoteError::InvalidDelegationChain)?;
+    require!(!representative.voted, VoteError::DelegateAlreadyVoted);
+    Ok(chain)
+}
+
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Disqualify a candidate. Admin only, until the election is finalized.
+/// Votes already cast stay on the account but the candidate can no longer
+/// receive votes and is left out of the results.
+/// Instant-runoff candidacies are fixed once tallies are final, as the count
+/// eliminates the inactive candidates when it starts.
+pub fn disqualify_candidate(ctx: Context<DisqualifyCandidate>, reason: String) -> Result<()> {
+    ctx.accounts.election.check_candidacy_changeable(Clock::get()?.unix_timestamp)?;
+
+    let candidate = &mut ctx.accounts.candidate;
+    candidate.check_removable(&reason)?;
+    candidate.status = CandidateStatus::Disqualified;
+
+    emit!(CandidateDisqualified {
+        header: EventHeader::now()?,
+        election: candidate.election,
+        pubkey: candidate.key(),
+        reason,
+    });
+
+    msg!("Candidate disqualified: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DisqualifyCandidate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&candidate.name)],
+        bump = candidate.bump
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Rank the election's candidates and write the outcome to its `Results` account.
+/// Permissionless crank: anyone may finalize once `ends_at` has passed, or
+/// `reveal_ends_at` for commit-reveal elections. Instant-runoff elections
+/// are finalized from their completed `RunoffTally`.
+/// Every registered candidate must be passed as a remaining account, in the
+/// order of `election.candidates`.
+/// An election whose turnout falls short of its quorum is marked `Invalid`:
+/// its standings are still recorded, but it has no winner.
+pub fn finalize_election<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeElection<'info>>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!election.is_finalized(), VoteError::ElectionAlreadyFinalized);
+    require!(now >= election.tally_ends_at(), VoteError::VotingNotEnded);
+
+    let (entries, mut winners, mut is_tie) = match election.method {
+        VotingMethod::D21 | VotingMethod::Approval | VotingMethod::Score { .. } => {
+            let candidates = load_election_candidates(ctx.remaining_accounts, election)?;
+            let tallies: Vec<(Pubkey, &CandidateAccount)> = candidates
+                .iter()
+                .map(|candidate| (candidate.key(), &**candidate))
+                .collect();
+            Results::rank(election.method, &tallies)
+        }
+        VotingMethod::InstantRunoff => {
+            let runoff = ctx.accounts.runoff.as_ref().ok_or(VoteError::RunoffNotComplete)?;
+            require!(runoff.complete, VoteError::RunoffNotComplete);
+            let winners: Vec<Pubkey> = runoff.winner.into_iter().collect();
+            (runoff.standings(&election.candidates), winners, false)
+        }
+    };
+
+    let quorum_reached = election.quorum_reached();
+    if quorum_reached {
+        election.status = ElectionStatus::Finalized;
+    } else {
+        election.status = ElectionStatus::Invalid;
+        winners.clear();
+        is_tie = false;
+    }
+
+    let results = &mut ctx.accounts.results;
+    results.election = election.key();
+    results.entries = entries;
+    results.winners = winners.clone();
+    results.is_tie = is_tie;
+    results.finalized_at = now;
+    results.bump = ctx.bumps.results;
+
+    emit!(ElectionFinalized {
+        header: EventHeader::now()?,
+        election: election.key(),
+        results: results.key(),
+        winners,
+        is_tie,
+        registered_voters: election.registered_voters,
+        ballots_cast: election.ballots_cast,
+        quorum_reached,
+        finalized_at: now,
+    });
+
+    msg!(
+        "Election finalized: {} ({} winner(s), {}/{} voted)",
+        election.title,
+        results.winners.len(),
+        election.ballots_cast,
+        election.registered_voters
+    );
+    Ok(())
+}
+
+/// Deserialize the full candidate set passed as remaining accounts, which must
+/// match `election.candidates` one to one, in order, and belong to `election`.
+pub(crate) fn load_election_candidates<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    election: &Account<'info, Election>,
+) -> Result<Vec<Account<'info, CandidateAccount>>> {
+    require_eq!(
+        remaining_accounts.len(),
+        election.candidates.len(),
+        VoteError::InvalidCandidate
+    );
+    remaining_accounts
+        .iter()
+        .zip(election.candidates.iter())
+        .map(|(info, key)| {
+            require_keys_eq!(info.key(), *key, VoteError::InvalidCandidate);
+            let candidate = Account::<CandidateAccount>::try_from(info)?;
+            require_keys_eq!(candidate.election, election.key(), VoteError::InvalidCandidate);
+            Ok(candidate)
+        })
+        .collect()
+}
+
+#[derive(Accounts)]
+pub struct FinalizeElection<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Results::SPACE,
+        seeds = [b"results", election.key().as_ref()],
+        bump
+    )]
+    pub results: Account<'info, Results>,
+
+    /// Required for instant-runoff elections
+    #[account(
+        seeds = [b"runoff", election.key().as_ref()],
+        bump = runoff.bump
+    )]
+    pub runoff: Option<Account<'info, RunoffTally>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::instructions::initialize_sponsor_budget::available_lamports;
+
+/// Deposit `amount` lamports into an election's sponsor budget.
+/// Anyone may fund it; only the sponsor can withdraw.
+pub fn fund_sponsor_budget(ctx: Context<FundSponsorBudget>, amount: u64) -> Result<()> {
+    let accounts = Transfer {
+        from: ctx.accounts.funder.to_account_info(),
+        to: ctx.accounts.sponsor_budget.to_account_info(),
+    };
+    transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), accounts), amount)?;
+
+    let budget = &ctx.accounts.sponsor_budget;
+    emit!(SponsorBudgetFunded {
+        header: EventHeader::now()?,
+        election: budget.election,
+        funder: ctx.accounts.funder.key(),
+        amount,
+        available: available_lamports(budget)?,
+    });
+
+    msg!("Sponsor budget funded with {} lamports", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundSponsorBudget<'info> {
+    #[account(
+        mut,
+        seeds = [b"sponsor_budget", sponsor_budget.election.as_ref()],
+        bump = sponsor_budget.bump
+    )]
+    pub sponsor_budget: Account<'info, SponsorBudget>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,17 +1,48 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::errors::*;
 use crate::events::*;
 
-/// Initialize a candidate account with a unique name.
-/// Seeds: ["candidate", name.as_bytes()]
-/// Fails if PDA already exists.
-pub fn initialize_candidate(ctx: Context<InitializeCandidate>, name: String) -> Result<()> {
+/// Initialize a candidate account with a name unique within its election.
+/// Seeds: ["candidate", election.key().as_ref(), CandidateAccount::name_seed(name)]
+/// Only the election admin or an allow-listed registrar may register candidates;
+/// they are approved straight away and owned by the registering authority.
+/// Fails if PDA already exists, the election is full or the program is paused.
+pub fn initialize_candidate(
+    ctx: Context<InitializeCandidate>,
+    name: String,
+    description: String,
+    uri: String,
+) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+    let name = CandidateAccount::normalize_name(&name)?;
+    CandidateAccount::check_metadata(&description, &uri)?;
+    require!(
+        election.candidates.len() < Election::MAX_CANDIDATES,
+        VoteError::TooManyCandidates
+    );
+
     let candidate = &mut ctx.accounts.candidate;
+    candidate.version = CandidateAccount::VERSION;
+    candidate.election = election.key();
     candidate.name = name.clone();
+    candidate.description = description;
+    candidate.uri = uri;
+    candidate.owner = ctx.accounts.authority.key();
+    candidate.status = CandidateStatus::Approved;
     candidate.votes = 0;
+    candidate.negative_votes = 0;
+    candidate.payer = ctx.accounts.payer.key();
     candidate.bump = ctx.bumps.candidate;
 
+    election.candidates.push(candidate.key());
+
     emit!(CandidateInitialized {
+        header: EventHeader::now()?,
+        election: candidate.election,
         name,
         pubkey: candidate.key(),
     });
@@ -23,15 +54,29 @@ pub fn initialize_candidate(ctx: Context<InitializeCandidate>, name: String) ->
 #[derive(Accounts)]
 #[instruction(name: String)]
 pub struct InitializeCandidate<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        constraint = election.is_registrar(&authority.key()) @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
     #[account(
         init,
         payer = payer,
         space = CandidateAccount::SPACE,
-        seeds = [b"candidate", name.as_bytes()],
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&name)],
         bump
     )]
     pub candidate: Account<'info, CandidateAccount>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// The election admin or an allow-listed registrar
+    pub authority: Signer<'info>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -43,93 +88,33 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_candidate_initialization_state() {
-        // Test initial candidate state
-        let name = String::from("Alice");
-        let candidate = CandidateAccount {
-            name: name.clone(),
-            votes: 0,
-            bump: 255,
-        };
-
-        assert_eq!(candidate.name, name);
-        assert_eq!(candidate.votes, 0);
-        assert_eq!(candidate.bump, 255);
-    }
-
-    #[test]
-    fn test_candidate_name_validation() {
-        // Test various candidate names
-        let names = vec!["Alice", "Bob", "Charlie", "Diana"];
-        
-        for name in names {
-            let candidate = CandidateAccount {
-                name: String::from(name),
-                votes: 0,
-                bump: 255,
-            };
-            assert_eq!(candidate.name, name);
-            assert!(candidate.name.len() <= CandidateAccount::MAX_NAME_LEN);
-        }
+    fn test_same_name_in_different_elections() {
+        // "Alice" may run in any number of elections, each with its own PDA
+        let (first, _) = Pubkey::find_program_address(
+            &[b"candidate", Pubkey::new_unique().as_ref(), &CandidateAccount::name_seed("Alice")],
+            &crate::ID,
+        );
+        let (second, _) = Pubkey::find_program_address(
+            &[b"candidate", Pubkey::new_unique().as_ref(), &CandidateAccount::name_seed("Alice")],
+            &crate::ID,
+        );
+        assert_ne!(first, second);
     }
 
     #[test]
-    fn test_candidate_bump_values() {
-        // Test that bump can hold valid PDA bump values (0-255)
-        let candidate_min = CandidateAccount {
-            name: String::from("Test"),
-            votes: 0,
-            bump: 0,
-        };
-        assert_eq!(candidate_min.bump, 0);
-
-        let candidate_max = CandidateAccount {
-            name: String::from("Test"),
-            votes: 0,
-            bump: 255,
-        };
-        assert_eq!(candidate_max.bump, 255);
-    }
-
-    #[test]
-    fn test_candidate_initial_votes_zero() {
-        // Verify all candidates start with 0 votes
-        let candidates = vec![
-            CandidateAccount { name: "Alice".to_string(), votes: 0, bump: 255 },
-            CandidateAccount { name: "Bob".to_string(), votes: 0, bump: 254 },
-            CandidateAccount { name: "Charlie".to_string(), votes: 0, bump: 253 },
-        ];
-
-        for candidate in candidates {
-            assert_eq!(candidate.votes, 0);
-        }
-    }
-
-    #[test]
-    fn test_candidate_name_edge_cases() {
-        // Test empty name (allowed by type system)
-        let empty = CandidateAccount {
-            name: String::from(""),
-            votes: 0,
-            bump: 255,
-        };
-        assert!(empty.name.is_empty());
-
-        // Test single character
-        let single = CandidateAccount {
-            name: String::from("A"),
-            votes: 0,
-            bump: 255,
-        };
-        assert_eq!(single.name.len(), 1);
-
-        // Test max length
-        let max = CandidateAccount {
-            name: "X".repeat(CandidateAccount::MAX_NAME_LEN),
-            votes: 0,
-            bump: 255,
+    fn test_equivalent_names_share_a_pda() {
+        // Case, surrounding whitespace and Unicode composition don't create a new candidate
+        let election = Pubkey::new_unique();
+        let pda = |name: &str| {
+            Pubkey::find_program_address(
+                &[b"candidate", election.as_ref(), &CandidateAccount::name_seed(name)],
+                &crate::ID,
+            )
+            .0
         };
-        assert_eq!(max.name.len(), CandidateAccount::MAX_NAME_LEN);
+        assert_eq!(pda("Alice"), pda("alice "));
+        assert_eq!(pda("Zoë"), pda("ZOE\u{308}"));
+        assert_ne!(pda("Alice"), pda("Alicia"));
     }
 }
 
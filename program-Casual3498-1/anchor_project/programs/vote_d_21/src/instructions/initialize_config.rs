@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::program::VoteD21;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Create the program-wide config and name the emergency authority that may
+/// pause registration and voting.
+/// Seeds: ["config"]
+/// Only the program's upgrade authority may create it, once per deployment.
+pub fn initialize_config(ctx: Context<InitializeConfig>, emergency_authority: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.emergency_authority = emergency_authority;
+    config.paused = false;
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigInitialized {
+        header: EventHeader::now()?,
+        emergency_authority,
+    });
+
+    msg!("Config initialized, emergency authority: {}", emergency_authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = Config::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The program's upgrade authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ VoteError::UnauthorizedAccess
+    )]
+    pub program: Program<'info, VoteD21>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ VoteError::UnauthorizedAccess
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Create a new election owned by the creator.
+/// Seeds: ["election", creator.key().as_ref(), election_id.to_le_bytes()]
+/// Each creator can run any number of elections side by side and starts out as its admin.
+/// Ballots are accepted in `[starts_at, ends_at)` once the election is opened.
+/// Each voter may cast up to `positive_votes` positive and `negative_votes`
+/// negative votes (D21 / Janeček method).
+pub fn initialize_election(
+    ctx: Context<InitializeElection>,
+    election_id: u64,
+    title: String,
+    starts_at: i64,
+    ends_at: i64,
+    positive_votes: u8,
+    negative_votes: u8,
+) -> Result<()> {
+    require!(
+        title.len() <= Election::MAX_TITLE_LEN,
+        VoteError::TitleTooLong
+    );
+    require!(starts_at < ends_at, VoteError::InvalidVotingWindow);
+    require!(
+        ends_at > Clock::get()?.unix_timestamp,
+        VoteError::InvalidVotingWindow
+    );
+    Election::check_vote_config(positive_votes, negative_votes)?;
+
+    let election = &mut ctx.accounts.election;
+    election.creator = ctx.accounts.creator.key();
+    election.election_id = election_id;
+    election.title = title.clone();
+    election.candidates = Vec::new();
+    election.status = ElectionStatus::Pending;
+    election.starts_at = starts_at;
+    election.ends_at = ends_at;
+    election.positive_votes = positive_votes;
+    election.negative_votes = negative_votes;
+    election.admin = election.creator;
+    election.pending_admin = None;
+    election.registrars = Vec::new();
+    election.eligibility = EligibilityMode::Open;
+    election.weighting = VoteWeighting::Equal;
+    election.reveal_ends_at = None;
+    election.method = VotingMethod::D21;
+    election.registered_voters = 0;
+    election.ballots_cast = 0;
+    election.quorum_bps = None;
+    election.bump = ctx.bumps.election;
+
+    emit!(ElectionCreated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        creator: election.creator,
+        title,
+    });
+
+    msg!("Election initialized: {}", election.title);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(election_id: u64)]
+pub struct InitializeElection<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = Election::SPACE,
+        seeds = [b"election", creator.key().as_ref(), election_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Create the election's sponsor budget, naming the relayer it reimburses and
+/// the fee it refunds per sponsored transaction on top of rent.
+/// Seeds: ["sponsor_budget", election.key().as_ref()]
+/// Admin only; the admin becomes the sponsor. Fund it with `fund_sponsor_budget`.
+pub fn initialize_sponsor_budget(
+    ctx: Context<InitializeSponsorBudget>,
+    relayer: Pubkey,
+    fee_per_transaction: u64,
+) -> Result<()> {
+    SponsorBudget::check_fee(fee_per_transaction)?;
+
+    let budget = &mut ctx.accounts.sponsor_budget;
+    budget.election = ctx.accounts.election.key();
+    budget.sponsor = ctx.accounts.admin.key();
+    budget.relayer = relayer;
+    budget.fee_per_transaction = fee_per_transaction;
+    budget.spent = 0;
+    budget.sponsored_transactions = 0;
+    budget.bump = ctx.bumps.sponsor_budget;
+
+    emit!(SponsorBudgetCreated {
+        header: EventHeader::now()?,
+        election: budget.election,
+        sponsor: budget.sponsor,
+        relayer,
+        fee_per_transaction,
+    });
+
+    msg!("Sponsor budget created for relayer: {}", relayer);
+    Ok(())
+}
+
+/// Lamports a budget holds beyond its own rent exemption.
+pub(crate) fn available_lamports(budget: &Account<SponsorBudget>) -> Result<u64> {
+    let reserved = Rent::get()?.minimum_balance(SponsorBudget::SPACE);
+    Ok(budget.get_lamports().saturating_sub(reserved))
+}
+
+/// Move `amount` lamports from `budget` to `relayer`, which must be the
+/// relayer the budget was created for.
+pub(crate) fn reimburse_relayer<'info>(
+    budget: &mut Account<'info, SponsorBudget>,
+    relayer: &Signer<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(budget.relayer, relayer.key(), VoteError::InvalidRelayer);
+    let available = available_lamports(budget)?;
+    budget.record_spend(amount, available)?;
+    budget.sub_lamports(amount)?;
+    relayer.add_lamports(amount)?;
+
+    emit!(RelayerReimbursed {
+        header: EventHeader::now()?,
+        election: budget.election,
+        relayer: relayer.key(),
+        amount,
+        spent: budget.spent,
+        available: available - amount,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeSponsorBudget<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = SponsorBudget::SPACE,
+        seeds = [b"sponsor_budget", election.key().as_ref()],
+        bump
+    )]
+    pub sponsor_budget: Account<'info, SponsorBudget>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
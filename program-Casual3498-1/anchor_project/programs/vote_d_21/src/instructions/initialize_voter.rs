@@ -1,15 +1,71 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::initialize_sponsor_budget::reimburse_relayer;
+
+/// Initialize a voter account for a specific authority within an election.
+/// Seeds: ["voter", election.key().as_ref(), authority.key().as_ref()]
+/// Marks voted = false initially and counts the voter in `registered_voters`.
+/// Not available while the program is paused.
+/// The authority must satisfy the election's eligibility mode: a whitelist
+/// entry must be passed for `Whitelist`, a Merkle `proof` for `MerkleRoot`.
+/// Token-based weighting starts the voter at zero weight; it comes only from
+/// tokens escrowed through `lock_tokens`.
+/// When the election's sponsor budget is passed, `payer` must be its relayer
+/// and is reimbursed the rent plus the budget's fee; the rent then returns to
+/// the sponsor when the voter account is closed.
+pub fn initialize_voter(ctx: Context<InitializeVoter>, proof: Vec<[u8; 32]>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+
+    let authority = ctx.accounts.authority.key();
+    let eligible = match election.eligibility {
+        EligibilityMode::Open => true,
+        // Seeds on the optional account already bind it to this election and authority
+        EligibilityMode::Whitelist => ctx.accounts.whitelist_entry.is_some(),
+        EligibilityMode::MerkleRoot { root } => {
+            EligibilityMode::verify_merkle_proof(&root, &authority, &proof)
+        }
+    };
+    require!(eligible, VoteError::IneligibleVoter);
+
+    let weight = election.weighting.registration_weight();
 
-/// Initialize a voter account for a specific authority.
-/// Seeds: ["voter", authority.key().as_ref()]
-/// Marks voted = false initially.
-pub fn initialize_voter(ctx: Context<InitializeVoter>) -> Result<()> {
     let voter = &mut ctx.accounts.voter;
-    voter.authority = ctx.accounts.authority.key();
+    voter.version = VoterAccount::VERSION;
+    voter.election = election.key();
+    voter.authority = authority;
     voter.voted = false;
-    voter.votes = [Pubkey::default(), Pubkey::default()];
+    voter.votes = Vec::new();
+    voter.negative_votes = Vec::new();
+    voter.scores = Vec::new();
+    voter.weight = weight;
+    voter.locked_amount = 0;
+    voter.commitment = None;
+    voter.delegate = None;
+    voter.delegated_weight = 0;
+    voter.payer = ctx.accounts.payer.key();
     voter.bump = ctx.bumps.voter;
+    if let Some(budget) = ctx.accounts.sponsor_budget.as_mut() {
+        let amount = Rent::get()?
+            .minimum_balance(VoterAccount::SPACE)
+            .checked_add(budget.fee_per_transaction)
+            .ok_or(VoteError::VoteOverflow)?;
+        reimburse_relayer(budget, &ctx.accounts.payer, amount)?;
+        voter.payer = budget.sponsor;
+    }
+    election.record_registration()?;
+
+    emit!(VoterRegistered {
+        header: EventHeader::now()?,
+        election: election.key(),
+        voter: voter.key(),
+        authority,
+        weight,
+        registered_voters: election.registered_voters,
+    });
 
     msg!("Voter initialized for authority: {}", voter.authority);
     Ok(())
@@ -17,15 +73,40 @@ pub fn initialize_voter(ctx: Context<InitializeVoter>) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct InitializeVoter<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
     #[account(
         init,
         payer = payer,
         space = VoterAccount::SPACE,
-        seeds = [b"voter", authority.key().as_ref()],
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
         bump
     )]
     pub voter: Account<'info, VoterAccount>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// Required when the election uses `EligibilityMode::Whitelist`
+    #[account(
+        seeds = [b"whitelist", election.key().as_ref(), authority.key().as_ref()],
+        bump = whitelist_entry.bump
+    )]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    /// Reimburses `payer`, which must be its relayer, for a sponsored registration
+    #[account(
+        mut,
+        seeds = [b"sponsor_budget", election.key().as_ref()],
+        bump = sponsor_budget.bump
+    )]
+    pub sponsor_budget: Option<Account<'info, SponsorBudget>>,
+
     /// The authority who will control this voter account
     pub authority: Signer<'info>,
 
@@ -34,138 +115,3 @@ pub struct InitializeVoter<'info> {
 
     pub system_program: Program<'info, System>,
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_voter_initialization_state() {
-        // Test initial voter state
-        let authority = Pubkey::new_unique();
-        let voter = VoterAccount {
-            authority,
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
-        };
-
-        assert_eq!(voter.authority, authority);
-        assert!(!voter.voted);
-        assert_eq!(voter.votes[0], Pubkey::default());
-        assert_eq!(voter.votes[1], Pubkey::default());
-        assert_eq!(voter.bump, 255);
-    }
-
-    #[test]
-    fn test_voter_authority_assignment() {
-        // Test that voter is correctly associated with authority
-        let authorities = vec![
-            Pubkey::new_unique(),
-            Pubkey::new_unique(),
-            Pubkey::new_unique(),
-        ];
-
-        for authority in authorities {
-            let voter = VoterAccount {
-                authority,
-                voted: false,
-                votes: [Pubkey::default(), Pubkey::default()],
-                bump: 255,
-            };
-            assert_eq!(voter.authority, authority);
-        }
-    }
-
-    #[test]
-    fn test_voter_initial_not_voted() {
-        // Verify all new voters start with voted = false
-        let voters = vec![
-            VoterAccount {
-                authority: Pubkey::new_unique(),
-                voted: false,
-                votes: [Pubkey::default(), Pubkey::default()],
-                bump: 255,
-            },
-            VoterAccount {
-                authority: Pubkey::new_unique(),
-                voted: false,
-                votes: [Pubkey::default(), Pubkey::default()],
-                bump: 254,
-            },
-        ];
-
-        for voter in voters {
-            assert!(!voter.voted, "New voter should not have voted yet");
-        }
-    }
-
-    #[test]
-    fn test_voter_votes_array_defaults() {
-        // Test that votes array is initialized with default pubkeys
-        let voter = VoterAccount {
-            authority: Pubkey::new_unique(),
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
-        };
-
-        assert_eq!(voter.votes[0], Pubkey::default());
-        assert_eq!(voter.votes[1], Pubkey::default());
-        assert_eq!(voter.votes.len(), 2);
-    }
-
-    #[test]
-    fn test_voter_bump_range() {
-        // Test bump values across valid range
-        for bump in [0u8, 127, 254, 255] {
-            let voter = VoterAccount {
-                authority: Pubkey::new_unique(),
-                voted: false,
-                votes: [Pubkey::default(), Pubkey::default()],
-                bump,
-            };
-            assert_eq!(voter.bump, bump);
-        }
-    }
-
-    #[test]
-    fn test_voter_authority_uniqueness() {
-        // Test that different voters have different authorities
-        let voter1 = VoterAccount {
-            authority: Pubkey::new_unique(),
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
-        };
-
-        let voter2 = VoterAccount {
-            authority: Pubkey::new_unique(),
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 254,
-        };
-
-        assert_ne!(voter1.authority, voter2.authority);
-    }
-
-    #[test]
-    fn test_voter_state_immutability_before_vote() {
-        // Test that voter state is as expected before any vote
-        let authority = Pubkey::new_unique();
-        let voter = VoterAccount {
-            authority,
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
-        };
-
-        // All fields should match initial state
-        assert_eq!(voter.authority, authority);
-        assert_eq!(voter.voted, false);
-        assert!(voter.votes.iter().all(|&v| v == Pubkey::default()));
-    }
-}
-
-
-
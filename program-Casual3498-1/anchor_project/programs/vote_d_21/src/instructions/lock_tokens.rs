@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Escrow governance tokens in a per-voter vault to gain voting weight.
+/// Seeds: ["vault", voter.key().as_ref()]
+/// Only for token-gated or token-weighted elections, once per voter, before voting.
+/// Gated elections need at least `min_balance` locked for one ballot.
+/// Tokens stay locked until `unlock_tokens` after the election ends.
+/// Not available while the program is paused.
+pub fn lock_tokens(ctx: Context<LockTokens>, amount: u64) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+
+    let mint = election.weighting.mint().ok_or(VoteError::TokensNotLockable)?;
+    require_keys_eq!(ctx.accounts.mint.key(), mint, VoteError::InvalidTokenAccount);
+    let weight = election.weighting.locked_weight(amount)?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(!voter.voted, VoteError::AlreadyVoted);
+    // A delegated weight is already counted along the delegation chain
+    voter.check_not_delegated()?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voter_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    voter.locked_amount = amount;
+    voter.weight = weight;
+
+    emit!(TokensLocked {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        amount,
+    });
+
+    msg!("Locked {} tokens for voter: {}", amount, voter.authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LockTokens<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", voter.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = voter
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::migrate_voter::{close_legacy, read_legacy};
+
+/// Move a candidate from the first deployment, which had no elections, into
+/// `election` with its tally, and close the legacy account.
+/// Seeds: ["candidate", election.key().as_ref(), CandidateAccount::name_seed(name)]
+/// Admin only, while the election accepts registrations under the legacy
+/// rules (see `Election::check_legacy_import`). The admin pays for the new
+/// account and receives the legacy account's rent.
+/// Not available while the program is paused.
+pub fn migrate_candidate(ctx: Context<MigrateCandidate>, name: String) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_legacy_import(Clock::get()?.unix_timestamp)?;
+    require!(
+        election.candidates.len() < Election::MAX_CANDIDATES,
+        VoteError::TooManyCandidates
+    );
+
+    let legacy_info = ctx.accounts.legacy_candidate.to_account_info();
+    let legacy: CandidateAccountV0 = read_legacy(&legacy_info, &CandidateAccountV0::DISCRIMINATOR)?;
+    require!(legacy.name == name, VoteError::InvalidCandidate);
+
+    let admin = ctx.accounts.admin.key();
+    let candidate = &mut ctx.accounts.candidate;
+    candidate.set_inner(legacy.migrate(election.key(), admin, ctx.bumps.candidate));
+    election.candidates.push(candidate.key());
+
+    close_legacy(legacy_info, ctx.accounts.admin.to_account_info())?;
+
+    emit!(CandidateMigrated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        legacy: ctx.accounts.legacy_candidate.key(),
+        pubkey: candidate.key(),
+        version: candidate.version,
+    });
+
+    msg!("Candidate migrated: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct MigrateCandidate<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    /// CHECK: a candidate account from the first deployment; its discriminator is checked in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"candidate", name.as_bytes()],
+        bump
+    )]
+    pub legacy_candidate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = CandidateAccount::SPACE,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&name)],
+        bump
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Move a voter from the first deployment, which had no elections, into
+/// `election` and close the legacy account.
+/// Seeds: ["voter", election.key().as_ref(), authority.key().as_ref()]
+/// A legacy ballot moves with the voter: its candidates must be migrated
+/// first and passed as remaining accounts in ballot order. Their migrated
+/// tallies already include it, so it is counted towards turnout only.
+/// Admin only, while the election accepts registrations under the legacy
+/// rules (see `Election::check_legacy_import`). The admin pays for the new
+/// account and receives the legacy account's rent.
+/// Not available while the program is paused.
+pub fn migrate_voter<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateVoter<'info>>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_legacy_import(Clock::get()?.unix_timestamp)?;
+
+    let legacy_info = ctx.accounts.legacy_voter.to_account_info();
+    let legacy: VoterAccountV0 = read_legacy(&legacy_info, &VoterAccountV0::DISCRIMINATOR)?;
+    require_keys_eq!(
+        legacy.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    let mut votes = Vec::new();
+    if legacy.voted {
+        require_eq!(
+            ctx.remaining_accounts.len(),
+            legacy.votes.len(),
+            VoteError::InvalidCandidate
+        );
+        for (info, legacy_key) in ctx.remaining_accounts.iter().zip(legacy.votes.iter()) {
+            let candidate = Account::<CandidateAccount>::try_from(info)?;
+            require_keys_eq!(candidate.election, election.key(), VoteError::InvalidCandidate);
+            require_keys_eq!(
+                CandidateAccountV0::address(&candidate.name),
+                *legacy_key,
+                VoteError::InvalidCandidate
+            );
+            votes.push(candidate.key());
+        }
+        let ballot = Ballot { positive: votes.clone(), negative: Vec::new(), scores: Vec::new() };
+        ballot.validate(election)?;
+        election.record_ballot()?;
+    }
+    election.record_registration()?;
+
+    let admin = ctx.accounts.admin.key();
+    let voter = &mut ctx.accounts.voter;
+    voter.set_inner(legacy.migrate(election.key(), votes, admin, ctx.bumps.voter));
+
+    close_legacy(legacy_info, ctx.accounts.admin.to_account_info())?;
+
+    emit!(VoterMigrated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        legacy: ctx.accounts.legacy_voter.key(),
+        voter: voter.key(),
+        version: voter.version,
+    });
+
+    msg!("Voter migrated for authority: {}", voter.authority);
+    Ok(())
+}
+
+/// Deserialize an account from the first deployment, checking it still
+/// carries that layout's discriminator.
+pub(crate) fn read_legacy<T: AnchorDeserialize>(account: &AccountInfo, discriminator: &[u8; 8]) -> Result<T> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.starts_with(discriminator),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    T::deserialize(&mut &data[8..]).map_err(Into::into)
+}
+
+/// Close an account from the first deployment, sending its rent to `destination`.
+pub(crate) fn close_legacy<'info>(account: AccountInfo<'info>, destination: AccountInfo<'info>) -> Result<()> {
+    let lamports = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(VoteError::VoteOverflow)?;
+    **destination.lamports.borrow_mut() = lamports;
+    **account.lamports.borrow_mut() = 0;
+    account.assign(&System::id());
+    account.resize(0).map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct MigrateVoter<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    /// CHECK: a voter account from the first deployment; its discriminator is checked in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"voter", authority.key().as_ref()],
+        bump
+    )]
+    pub legacy_voter: UncheckedAccount<'info>,
+
+    /// CHECK: the wallet the legacy voter belongs to, matched against its data in the handler
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = VoterAccount::SPACE,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
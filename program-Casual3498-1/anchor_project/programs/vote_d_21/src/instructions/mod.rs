@@ -1,10 +1,94 @@
+pub mod accept_admin;
+pub mod add_registrar;
+pub mod add_to_whitelist;
+pub mod approve_candidate;
+pub mod change_vote;
+pub mod close_candidate;
+pub mod close_election;
+pub mod close_voter;
+pub mod commit_vote;
+pub mod delegate;
+pub mod disqualify_candidate;
+pub mod finalize_election;
+pub mod fund_sponsor_budget;
 pub mod initialize_candidate;
+pub mod initialize_config;
+pub mod initialize_election;
+pub mod initialize_sponsor_budget;
 pub mod initialize_voter;
+pub mod lock_tokens;
+pub mod migrate_candidate;
+pub mod migrate_voter;
+pub mod nominate_candidate;
+pub mod open_election;
+pub mod pause;
+pub mod reject_candidate;
+pub mod remove_from_whitelist;
+pub mod remove_registrar;
+pub mod reveal_vote;
+pub mod revoke_vote;
+pub mod set_commit_reveal;
+pub mod set_eligibility;
+pub mod set_quorum;
+pub mod set_vote_weighting;
+pub mod set_voting_method;
+pub mod start_runoff;
+pub mod tabulate_round;
+pub mod transfer_admin;
+pub mod undelegate;
+pub mod unlock_tokens;
+pub mod unpause;
 pub mod vote;
+pub mod vote_ranked;
+pub mod vote_signed_batch;
+pub mod withdraw_candidacy;
+pub mod withdraw_sponsor_budget;
 
+pub use accept_admin::*;
+pub use add_registrar::*;
+pub use add_to_whitelist::*;
+pub use approve_candidate::*;
+pub use change_vote::*;
+pub use close_candidate::*;
+pub use close_election::*;
+pub use close_voter::*;
+pub use commit_vote::*;
+pub use delegate::*;
+pub use disqualify_candidate::*;
+pub use finalize_election::*;
+pub use fund_sponsor_budget::*;
 pub use initialize_candidate::*;
+pub use initialize_config::*;
+pub use initialize_election::*;
+pub use initialize_sponsor_budget::*;
 pub use initialize_voter::*;
+pub use lock_tokens::*;
+pub use migrate_candidate::*;
+pub use migrate_voter::*;
+pub use nominate_candidate::*;
+pub use open_election::*;
+pub use pause::*;
+pub use reject_candidate::*;
+pub use remove_from_whitelist::*;
+pub use remove_registrar::*;
+pub use reveal_vote::*;
+pub use revoke_vote::*;
+pub use set_commit_reveal::*;
+pub use set_eligibility::*;
+pub use set_quorum::*;
+pub use set_vote_weighting::*;
+pub use set_voting_method::*;
+pub use start_runoff::*;
+pub use tabulate_round::*;
+pub use transfer_admin::*;
+pub use undelegate::*;
+pub use unlock_tokens::*;
+pub use unpause::*;
 pub use vote::*;
+pub use vote_ranked::*;
+pub use vote_signed_batch::*;
+pub use withdraw_candidacy::*;
+pub use withdraw_sponsor_budget::*;
 
 
 
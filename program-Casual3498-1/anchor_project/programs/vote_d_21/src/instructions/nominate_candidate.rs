@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::events::*;
+
+/// Self-nominate as a candidate. Any wallet may nominate itself while
+/// registration is open; the candidate stays off the ballot until the admin
+/// approves it with `approve_candidate`.
+/// Seeds: ["candidate", election.key().as_ref(), CandidateAccount::name_seed(name)]
+/// Not available while the program is paused.
+pub fn nominate_candidate(
+    ctx: Context<NominateCandidate>,
+    name: String,
+    description: String,
+    uri: String,
+) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+    let name = CandidateAccount::normalize_name(&name)?;
+    CandidateAccount::check_metadata(&description, &uri)?;
+
+    let candidate = &mut ctx.accounts.candidate;
+    candidate.version = CandidateAccount::VERSION;
+    candidate.election = election.key();
+    candidate.name = name.clone();
+    candidate.description = description;
+    candidate.uri = uri;
+    candidate.owner = ctx.accounts.owner.key();
+    candidate.status = CandidateStatus::Nominated;
+    candidate.votes = 0;
+    candidate.negative_votes = 0;
+    candidate.payer = ctx.accounts.payer.key();
+    candidate.bump = ctx.bumps.candidate;
+
+    emit!(CandidateNominated {
+        header: EventHeader::now()?,
+        election: candidate.election,
+        name,
+        pubkey: candidate.key(),
+        owner: candidate.owner,
+    });
+
+    msg!("Candidate nominated: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct NominateCandidate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CandidateAccount::SPACE,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&name)],
+        bump
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// The wallet standing as candidate
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Open a pending election once `starts_at` has been reached.
+/// Permissionless crank: anyone may open it inside the voting window.
+pub fn open_election(ctx: Context<OpenElection>) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        election.status == ElectionStatus::Pending,
+        VoteError::ElectionNotActive
+    );
+    require!(now >= election.starts_at, VoteError::VotingNotStarted);
+    require!(now < election.ends_at, VoteError::VotingEnded);
+
+    election.status = ElectionStatus::Active;
+
+    emit!(ElectionOpened {
+        header: EventHeader::now()?,
+        election: election.key(),
+        starts_at: election.starts_at,
+        ends_at: election.ends_at,
+    });
+
+    msg!("Election opened: {}", election.title);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenElection<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opened_election_accepts_ballots_in_window() {
+        let mut election = Election::with_window(ElectionStatus::Pending, 100, 200);
+
+        assert!(election.check_voting_window(150).is_err());
+        election.status = ElectionStatus::Active;
+        assert!(election.check_voting_window(150).is_ok());
+    }
+}
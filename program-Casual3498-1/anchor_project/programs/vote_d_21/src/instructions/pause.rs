@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Halt candidate registration, voter registration and voting in every
+/// election until `unpause`. Emergency authority only.
+pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = true;
+
+    emit!(Paused {
+        header: EventHeader::now()?,
+        authority: config.emergency_authority,
+    });
+
+    msg!("Program paused");
+    Ok(())
+}
+
+/// Accounts shared by `pause` and `unpause`
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = emergency_authority @ VoteError::UnauthorizedAccess
+    )]
+    pub config: Account<'info, Config>,
+
+    pub emergency_authority: Signer<'info>,
+}
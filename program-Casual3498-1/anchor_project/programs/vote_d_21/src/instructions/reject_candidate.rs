@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Reject a self-nominated candidate; it never appears on the ballot.
+/// The account is closed and its rent refunded to the nominator, freeing the
+/// name for the admin or another nomination.
+/// Admin only.
+pub fn reject_candidate(ctx: Context<RejectCandidate>) -> Result<()> {
+    let candidate = &ctx.accounts.candidate;
+    require!(
+        candidate.status == CandidateStatus::Nominated,
+        VoteError::InvalidCandidateStatus
+    );
+
+    emit!(CandidateRejected {
+        header: EventHeader::now()?,
+        election: ctx.accounts.election.key(),
+        pubkey: candidate.key(),
+    });
+
+    msg!("Candidate rejected: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RejectCandidate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&candidate.name)],
+        bump = candidate.bump,
+        has_one = payer @ VoteError::InvalidPayer,
+        close = payer
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    pub admin: Signer<'info>,
+
+    /// The wallet that paid for the nomination
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
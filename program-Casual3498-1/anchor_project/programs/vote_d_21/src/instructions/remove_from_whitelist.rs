@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Remove a wallet from the whitelist and refund the entry's rent to the admin.
+/// Voters who already registered keep their voter account.
+pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>) -> Result<()> {
+    let entry = &ctx.accounts.whitelist_entry;
+
+    emit!(VoterRemovedFromWhitelist {
+        header: EventHeader::now()?,
+        election: entry.election,
+        voter: entry.voter,
+    });
+
+    msg!("Voter removed from whitelist: {}", entry.voter);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromWhitelist<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"whitelist", election.key().as_ref(), whitelist_entry.voter.as_ref()],
+        bump = whitelist_entry.bump
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
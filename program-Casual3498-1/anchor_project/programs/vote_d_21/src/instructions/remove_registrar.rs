@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Remove a registrar from the allow-list.
+/// Only the election admin may remove registrars.
+pub fn remove_registrar(ctx: Context<RemoveRegistrar>, registrar: Pubkey) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+
+    let index = election
+        .registrars
+        .iter()
+        .position(|key| *key == registrar)
+        .ok_or(VoteError::RegistrarNotFound)?;
+    election.registrars.swap_remove(index);
+
+    emit!(RegistrarRemoved {
+        header: EventHeader::now()?,
+        election: election.key(),
+        registrar,
+    });
+
+    msg!("Registrar removed: {}", registrar);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveRegistrar<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
+
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::vote::{candidate_tallies, load_candidates, tally_ballot};
+
+/// Reveal a committed ballot after voting ends and tally it.
+/// The ballot and salt must hash to the stored commitment; commitments that
+/// are never revealed are simply not counted.
+/// Candidate accounts are passed as remaining accounts in ballot order.
+/// Not available while the program is paused.
+pub fn reveal_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RevealVote<'info>>,
+    ballot: Ballot,
+    salt: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_reveal_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(!voter.voted, VoteError::AlreadyVoted);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    let commitment = voter.commitment.ok_or(VoteError::NoCommitment)?;
+    require!(ballot.commitment(&salt) == commitment, VoteError::InvalidReveal);
+
+    // The commitment hides the ballot, so it is only checked against the rules now
+    ballot.validate(election)?;
+
+    voter.check_not_delegated()?;
+    let weight = voter.vote_weight()?;
+    require!(weight > 0, VoteError::NoVotingWeight);
+
+    let keys: Vec<Pubkey> = ballot.candidates().copied().collect();
+    let mut candidates = load_candidates(ctx.remaining_accounts, &election.key(), &keys)?;
+
+    tally_ballot(&mut candidates, &ballot, weight, true)?;
+    for candidate in candidates.iter() {
+        candidate.exit(&crate::ID)?;
+    }
+
+    voter.set_ballot(&ballot);
+    voter.commitment = None;
+    election.record_ballot()?;
+
+    emit!(VoteCast {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        positive: ballot.positive,
+        negative: ballot.negative,
+        scores: ballot.scores,
+        weight,
+        tallies: candidate_tallies(&candidates),
+        ballots_cast: election.ballots_cast,
+    });
+
+    msg!(
+        "Ballot revealed: {} positive, {} negative",
+        voter.votes.len(),
+        voter.negative_votes.len()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::vote::{candidate_tallies, load_candidates, tally_ballot};
+
+/// Withdraw a previously cast ballot while the voting window is still open,
+/// returning the voter to the unvoted state so they may vote again.
+/// Candidate accounts from the stored ballot are passed as remaining accounts,
+/// positive first.
+/// Not available while the program is paused.
+pub fn revoke_vote<'info>(ctx: Context<'_, '_, 'info, 'info, RevokeVote<'info>>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(voter.voted, VoteError::NotVoted);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    let previous = voter.ballot();
+    let keys: Vec<Pubkey> = previous.candidates().copied().collect();
+    let mut candidates = load_candidates(ctx.remaining_accounts, &election.key(), &keys)?;
+
+    let weight = voter.vote_weight()?;
+    tally_ballot(&mut candidates, &previous, weight, false)?;
+    for candidate in candidates.iter() {
+        candidate.exit(&crate::ID)?;
+    }
+
+    voter.clear_ballot();
+    election.retract_ballot()?;
+
+    emit!(VoteRevoked {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        positive: previous.positive,
+        negative: previous.negative,
+        scores: previous.scores,
+        weight,
+        tallies: candidate_tallies(&candidates),
+        ballots_cast: election.ballots_cast,
+    });
+
+    msg!("Vote revoked");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
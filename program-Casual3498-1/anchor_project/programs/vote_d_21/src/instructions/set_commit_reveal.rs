@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Enable commit-reveal ballots by setting a reveal deadline after `ends_at`,
+/// or pass `None` to return to public ballots.
+/// Admin only, and only before the election opens.
+pub fn set_commit_reveal(ctx: Context<SetCommitReveal>, reveal_ends_at: Option<i64>) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.check_configurable()?;
+
+    if let Some(reveal_ends_at) = reveal_ends_at {
+        require!(reveal_ends_at > election.ends_at, VoteError::InvalidVotingWindow);
+    }
+    election.reveal_ends_at = reveal_ends_at;
+
+    emit!(CommitRevealUpdated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        reveal_ends_at,
+    });
+
+    msg!("Commit-reveal updated: {:?}", reveal_ends_at);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCommitReveal<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
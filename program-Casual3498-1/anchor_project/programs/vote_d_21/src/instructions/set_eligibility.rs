@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Choose who may register as a voter: anyone, whitelisted wallets, or
+/// holders of a Merkle proof against the given root.
+/// Admin only, and only before the election opens and any voter registers.
+pub fn set_eligibility(ctx: Context<SetEligibility>, mode: EligibilityMode) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.check_electorate_configurable()?;
+
+    election.eligibility = mode;
+
+    emit!(EligibilityUpdated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        mode,
+    });
+
+    msg!("Eligibility mode updated: {:?}", mode);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEligibility<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eligibility_mode_serialized_size() {
+        // The largest variant must fit the 33 bytes reserved in Election::SPACE
+        let merkle = EligibilityMode::MerkleRoot { root: [7; 32] };
+        assert_eq!(merkle.try_to_vec().unwrap().len(), 33);
+        assert_eq!(EligibilityMode::Open.try_to_vec().unwrap().len(), 1);
+        assert_eq!(EligibilityMode::Whitelist.try_to_vec().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Require a share of registered voters, in basis points, to cast a ballot
+/// for the election to be valid, or pass `None` to drop the quorum.
+/// Admin only, and only before the election opens.
+pub fn set_quorum(ctx: Context<SetQuorum>, quorum_bps: Option<u16>) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.check_configurable()?;
+    Election::check_quorum_config(quorum_bps)?;
+
+    election.quorum_bps = quorum_bps;
+
+    emit!(QuorumUpdated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        quorum_bps,
+    });
+
+    msg!("Quorum updated: {:?}", quorum_bps);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetQuorum<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Choose how ballots are weighted: equally, gated on a minimum balance of a
+/// governance mint, or weighted by holdings of that mint. Token weighting
+/// only counts tokens escrowed through `lock_tokens`.
+/// Admin only, and only before the election opens and any voter registers,
+/// since registration fixes each voter's starting weight.
+pub fn set_vote_weighting(ctx: Context<SetVoteWeighting>, weighting: VoteWeighting) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.check_electorate_configurable()?;
+
+    election.weighting = weighting;
+
+    emit!(VoteWeightingUpdated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        weighting,
+    });
+
+    msg!("Vote weighting updated: {:?}", weighting);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVoteWeighting<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Choose how ballots are cast and counted: D21, instant-runoff, approval or
+/// score voting. Score elections must allow at least one point.
+/// Admin only, and only before the election opens.
+pub fn set_voting_method(ctx: Context<SetVotingMethod>, method: VotingMethod) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.check_configurable()?;
+    Election::check_method_config(method)?;
+
+    election.method = method;
+
+    emit!(VotingMethodUpdated {
+        header: EventHeader::now()?,
+        election: election.key(),
+        method,
+    });
+
+    msg!("Voting method updated: {:?}", method);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVotingMethod<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
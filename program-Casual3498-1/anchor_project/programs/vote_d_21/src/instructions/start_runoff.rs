@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::finalize_election::load_election_candidates;
+
+/// Open the instant-runoff count once voting has ended.
+/// Seeds: ["runoff", election.key().as_ref()]
+/// Permissionless crank. Every registered candidate must be passed as a
+/// remaining account, in the order of `election.candidates`; withdrawn and
+/// disqualified candidates are eliminated before the first round.
+pub fn start_runoff<'info>(ctx: Context<'_, '_, 'info, 'info, StartRunoff<'info>>) -> Result<()> {
+    let election = &ctx.accounts.election;
+    election.check_method(VotingMethod::InstantRunoff)?;
+    require!(!election.is_finalized(), VoteError::ElectionAlreadyFinalized);
+    require!(
+        Clock::get()?.unix_timestamp >= election.tally_ends_at(),
+        VoteError::VotingNotEnded
+    );
+
+    let candidates = load_election_candidates(ctx.remaining_accounts, election)?;
+
+    let runoff = &mut ctx.accounts.runoff;
+    runoff.election = election.key();
+    runoff.round = 1;
+    runoff.ballots_counted = 0;
+    runoff.counts = vec![0; candidates.len()];
+    runoff.eliminated = candidates
+        .iter()
+        .filter(|candidate| candidate.status != CandidateStatus::Approved)
+        .map(|candidate| candidate.key())
+        .collect();
+    runoff.exhausted = 0;
+    runoff.winner = None;
+    runoff.complete = false;
+    runoff.bump = ctx.bumps.runoff;
+
+    emit!(RunoffStarted {
+        header: EventHeader::now()?,
+        election: election.key(),
+        eliminated: runoff.eliminated.clone(),
+    });
+
+    msg!(
+        "Runoff started: {} candidates, {} ballots",
+        candidates.len() - runoff.eliminated.len(),
+        election.ballots_cast
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StartRunoff<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RunoffTally::SPACE,
+        seeds = [b"runoff", election.key().as_ref()],
+        bump
+    )]
+    pub runoff: Account<'info, RunoffTally>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Count a batch of ranked ballots towards the current runoff round.
+/// Permissionless crank: ballots are passed as writable remaining accounts,
+/// in as many transactions as needed, each ballot once per round. The call
+/// that counts the last ballot of the round concludes it, recording a
+/// majority winner or eliminating the weakest candidate for the next round.
+pub fn tabulate_round<'info>(ctx: Context<'_, '_, 'info, 'info, TabulateRound<'info>>) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let runoff = &mut ctx.accounts.runoff;
+    require!(!runoff.complete, VoteError::RunoffComplete);
+
+    for info in ctx.remaining_accounts.iter() {
+        require!(info.is_writable, VoteError::InvalidBallot);
+        let mut ballot = Account::<RankedBallot>::try_from(info)?;
+        require_keys_eq!(ballot.election, election.key(), VoteError::InvalidBallot);
+        require!(ballot.counted_round < runoff.round, VoteError::BallotAlreadyCounted);
+
+        runoff.count(&election.candidates, &ballot.rankings, ballot.weight)?;
+        ballot.counted_round = runoff.round;
+        ballot.exit(&crate::ID)?;
+    }
+
+    if runoff.ballots_counted < election.ballots_cast {
+        msg!(
+            "Round {}: {}/{} ballots counted",
+            runoff.round,
+            runoff.ballots_counted,
+            election.ballots_cast
+        );
+        return Ok(());
+    }
+
+    let round = runoff.round;
+    let counts = runoff.counts.clone();
+    let outcome = runoff.conclude_round(&election.candidates);
+    let (eliminated, winner) = match outcome {
+        RoundOutcome::Eliminated(candidate) => (Some(candidate), None),
+        RoundOutcome::Winner(candidate) => (None, Some(candidate)),
+        RoundOutcome::NoWinner => (None, None),
+    };
+
+    emit!(RunoffRoundCompleted {
+        header: EventHeader::now()?,
+        election: election.key(),
+        round,
+        counts,
+        eliminated,
+        winner,
+    });
+
+    msg!("Round {} concluded: {:?}", round, outcome);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TabulateRound<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"runoff", election.key().as_ref()],
+        bump = runoff.bump
+    )]
+    pub runoff: Account<'info, RunoffTally>,
+}
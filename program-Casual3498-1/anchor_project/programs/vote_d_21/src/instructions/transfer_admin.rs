@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Start a two-step admin transfer by nominating a pending admin.
+/// The transfer completes only when the nominee calls `accept_admin`.
+/// Nominating again replaces the previous pending admin.
+pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    election.pending_admin = Some(new_admin);
+
+    emit!(AdminTransferStarted {
+        header: EventHeader::now()?,
+        election: election.key(),
+        admin: election.admin,
+        pending_admin: new_admin,
+    });
+
+    msg!("Admin transfer started to: {}", new_admin);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump,
+        has_one = admin @ VoteError::UnauthorizedAccess
+    )]
+    pub election: Account<'info, Election>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::delegate::load_delegation_chain;
+
+/// Take back a delegated ballot so the voter may vote directly.
+/// The current delegation chain is passed as remaining accounts, starting with
+/// `voter.delegate`. Fails once the representative at the end of the chain
+/// has voted, since the weight has already been counted.
+/// Not available while the program is paused.
+pub fn undelegate<'info>(ctx: Context<'_, '_, 'info, 'info, Undelegate<'info>>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &ctx.accounts.election;
+    election.check_registration_open(Clock::get()?.unix_timestamp)?;
+
+    let voter = &mut ctx.accounts.voter;
+    let delegate = voter.delegate.ok_or(VoteError::NotDelegated)?;
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+
+    let mut chain = load_delegation_chain(
+        ctx.remaining_accounts,
+        &election.key(),
+        &voter.key(),
+        &delegate,
+    )?;
+
+    let weight = voter.vote_weight()?;
+    for link in chain.iter_mut() {
+        link.delegated_weight = link
+            .delegated_weight
+            .checked_sub(weight)
+            .ok_or(VoteError::VoteUnderflow)?;
+        link.exit(&crate::ID)?;
+    }
+
+    voter.delegate = None;
+
+    emit!(VoteUndelegated {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        delegate,
+        weight,
+    });
+
+    msg!("Delegation revoked");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Undelegate<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
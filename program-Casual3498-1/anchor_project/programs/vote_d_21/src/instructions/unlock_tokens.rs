@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Return escrowed governance tokens once no ballot can be tallied any more:
+/// after the voting window, or the reveal window in commit-reveal elections,
+/// where a revealed ballot still counts the locked weight.
+/// Closes the vault and refunds its rent to the voter authority.
+pub fn unlock_tokens(ctx: Context<UnlockTokens>) -> Result<()> {
+    let election = &ctx.accounts.election;
+    require!(
+        Clock::get()?.unix_timestamp >= election.tally_ends_at(),
+        VoteError::VotingNotEnded
+    );
+
+    let voter = &ctx.accounts.voter;
+    let amount = ctx.accounts.vault.amount;
+    let election_key = election.key();
+    let authority_key = ctx.accounts.authority.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"voter",
+        election_key.as_ref(),
+        authority_key.as_ref(),
+        &[voter.bump],
+    ]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.voter_token_account.to_account_info(),
+                authority: ctx.accounts.voter.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.voter.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    // The ballot stays counted; only the escrow is released
+    let voter = &mut ctx.accounts.voter;
+    voter.locked_amount = 0;
+
+    emit!(TokensUnlocked {
+        header: EventHeader::now()?,
+        election: election_key,
+        voter: authority_key,
+        amount,
+    });
+
+    msg!("Unlocked {} tokens for voter: {}", amount, authority_key);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnlockTokens<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", voter.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = authority
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
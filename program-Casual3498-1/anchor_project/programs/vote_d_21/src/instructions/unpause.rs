@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use crate::events::*;
+use crate::instructions::pause::SetPaused;
+
+/// Resume registration and voting after `pause`. Emergency authority only.
+pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = false;
+
+    emit!(Unpaused {
+        header: EventHeader::now()?,
+        authority: config.emergency_authority,
+    });
+
+    msg!("Program unpaused");
+    Ok(())
+}
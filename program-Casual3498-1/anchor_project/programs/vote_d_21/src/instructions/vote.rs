@@ -2,10 +2,25 @@ use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::*;
 use crate::events::*;
+use crate::instructions::initialize_sponsor_budget::reimburse_relayer;
 
-/// Cast votes for two distinct candidates.
+/// Cast a ballot for distinct candidates. D21 elections take up to
+/// `election.positive_votes` positive and `election.negative_votes` negative
+/// votes, approval elections any number of approvals, and score elections a
+/// score of `0..=max_score` for each rated candidate.
+/// Candidate accounts are passed as remaining accounts in ballot order.
 /// Requires signer authority match, prevents duplicate candidates and double voting.
-pub fn vote(ctx: Context<Vote>, candidate_keys: [Pubkey; 2]) -> Result<()> {
+/// Only accepted while the election's voting window is open, and only in
+/// elections without commit-reveal, and not while the program is paused.
+/// When the election's sponsor budget and its relayer are passed, the relayer
+/// pays the transaction fee and is reimbursed from the budget.
+pub fn vote<'info>(ctx: Context<'_, '_, 'info, 'info, Vote<'info>>, ballot: Ballot) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+    require!(!election.is_commit_reveal(), VoteError::CommitRevealRequired);
+
     let voter = &mut ctx.accounts.voter;
 
     // Check if voter has already voted
@@ -18,187 +33,169 @@ pub fn vote(ctx: Context<Vote>, candidate_keys: [Pubkey; 2]) -> Result<()> {
         VoteError::UnauthorizedAccess
     );
 
-    // Check for duplicate candidates
-    require_neq!(
-        candidate_keys[0],
-        candidate_keys[1],
-        VoteError::DuplicateCandidates
-    );
-
-    // Verify both candidates are valid PDAs and increment their votes
-    let candidate1 = &mut ctx.accounts.candidate1;
-    let candidate2 = &mut ctx.accounts.candidate2;
+    cast_ballot(election, voter, ctx.remaining_accounts, ballot)?;
 
-    // Verify candidate1 matches first key
-    require_keys_eq!(
-        candidate1.key(),
-        candidate_keys[0],
-        VoteError::InvalidCandidate
-    );
+    match (ctx.accounts.sponsor_budget.as_mut(), ctx.accounts.relayer.as_ref()) {
+        (Some(budget), Some(relayer)) => {
+            let fee = budget.fee_per_transaction;
+            reimburse_relayer(budget, relayer, fee)?;
+        }
+        (None, None) => {}
+        _ => return err!(VoteError::InvalidRelayer),
+    }
 
-    // Verify candidate2 matches second key
-    require_keys_eq!(
-        candidate2.key(),
-        candidate_keys[1],
-        VoteError::InvalidCandidate
+    msg!(
+        "Vote cast successfully: {} positive, {} negative",
+        voter.votes.len(),
+        voter.negative_votes.len()
     );
+    Ok(())
+}
 
-    // Increment votes
-    candidate1.votes = candidate1
-        .votes
-        .checked_add(1)
-        .ok_or(VoteError::VoteOverflow)?;
-    candidate2.votes = candidate2
-        .votes
-        .checked_add(1)
-        .ok_or(VoteError::VoteOverflow)?;
+/// Tally `ballot` for `voter` on the candidate accounts `candidate_accounts`
+/// and record it on the voter and the election. The caller has checked the
+/// election accepts ballots and that `voter` has not voted and may cast this one.
+pub(crate) fn cast_ballot<'info>(
+    election: &mut Account<'info, Election>,
+    voter: &mut Account<'info, VoterAccount>,
+    candidate_accounts: &'info [AccountInfo<'info>],
+    ballot: Ballot,
+) -> Result<()> {
+    // Check vote counts, the negative vote rule and duplicate candidates
+    ballot.validate(election)?;
+
+    // Token-weighted voters count by their snapshotted or locked balance,
+    // plus the weight of anyone who delegated to them
+    voter.check_not_delegated()?;
+    let weight = voter.vote_weight()?;
+    require!(weight > 0, VoteError::NoVotingWeight);
+
+    // Verify every candidate account matches the ballot and belongs to this election
+    let keys: Vec<Pubkey> = ballot.candidates().copied().collect();
+    let mut candidates = load_candidates(candidate_accounts, &election.key(), &keys)?;
+
+    // Increment votes by the voter's weight
+    tally_ballot(&mut candidates, &ballot, weight, true)?;
+    for candidate in candidates.iter() {
+        candidate.exit(&crate::ID)?;
+    }
 
     // Mark voter as having voted and store their choices
-    voter.voted = true;
-    voter.votes = candidate_keys;
+    voter.set_ballot(&ballot);
+    election.record_ballot()?;
 
     emit!(VoteCast {
+        header: EventHeader::now()?,
+        election: voter.election,
         voter: voter.authority,
-        candidates: candidate_keys,
+        positive: ballot.positive,
+        negative: ballot.negative,
+        scores: ballot.scores,
+        weight,
+        tallies: candidate_tallies(&candidates),
+        ballots_cast: election.ballots_cast,
     });
+    Ok(())
+}
+
+/// Deserialize the candidate accounts passed as remaining accounts.
+/// They must match `keys` one to one, in order, be writable and belong to
+/// `election`.
+pub(crate) fn load_candidates<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    election: &Pubkey,
+    keys: &[Pubkey],
+) -> Result<Vec<Account<'info, CandidateAccount>>> {
+    require_eq!(remaining_accounts.len(), keys.len(), VoteError::InvalidCandidate);
+
+    remaining_accounts
+        .iter()
+        .zip(keys)
+        .map(|(info, key)| {
+            require_keys_eq!(info.key(), *key, VoteError::InvalidCandidate);
+            require!(info.is_writable, VoteError::InvalidCandidate);
+            let candidate = Account::<CandidateAccount>::try_from(info)?;
+            require_keys_eq!(candidate.election, *election, VoteError::InvalidCandidate);
+            Ok(candidate)
+        })
+        .collect()
+}
 
-    msg!("Vote cast successfully for {} and {}", candidate1.name, candidate2.name);
+/// Add (`record = true`) or remove `weight` for every choice on `ballot`,
+/// along with each score counted `weight` times. `candidates` must contain
+/// every candidate on the ballot. New votes may only go to approved
+/// candidates; earlier votes can always be retracted.
+pub(crate) fn tally_ballot(
+    candidates: &mut [Account<'_, CandidateAccount>],
+    ballot: &Ballot,
+    weight: u64,
+    record: bool,
+) -> Result<()> {
+    let choices = ballot
+        .positive
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key, false, ballot.scores.get(i).copied()))
+        .chain(ballot.negative.iter().map(|key| (key, true, None)));
+    for (key, negative, score) in choices {
+        let candidate = candidates
+            .iter_mut()
+            .find(|candidate| candidate.key() == *key)
+            .ok_or(VoteError::InvalidCandidate)?;
+        if record {
+            candidate.check_votable()?;
+            candidate.record_vote(weight, negative)?;
+            if let Some(score) = score {
+                candidate.record_score(weight, score)?;
+            }
+        } else {
+            candidate.retract_vote(weight, negative)?;
+            if let Some(score) = score {
+                candidate.retract_score(weight, score)?;
+            }
+        }
+    }
     Ok(())
 }
 
+/// Current tallies of `candidates`, for events.
+pub(crate) fn candidate_tallies(candidates: &[Account<'_, CandidateAccount>]) -> Vec<CandidateTally> {
+    candidates
+        .iter()
+        .map(|candidate| CandidateTally::new(candidate.key(), candidate))
+        .collect()
+}
+
 #[derive(Accounts)]
-#[instruction(candidate_keys: [Pubkey; 2])]
 pub struct Vote<'info> {
     #[account(
         mut,
-        seeds = [b"voter", authority.key().as_ref()],
-        bump = voter.bump
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
     )]
-    pub voter: Account<'info, VoterAccount>,
+    pub election: Account<'info, Election>,
 
     #[account(
         mut,
-        seeds = [b"candidate", candidate1.name.as_bytes()],
-        bump = candidate1.bump
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
     )]
-    pub candidate1: Account<'info, CandidateAccount>,
+    pub voter: Account<'info, VoterAccount>,
 
-    #[account(
-        mut,
-        seeds = [b"candidate", candidate2.name.as_bytes()],
-        bump = candidate2.bump
-    )]
-    pub candidate2: Account<'info, CandidateAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
     pub authority: Signer<'info>,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_checked_add_vote_overflow() {
-        // Test that checked_add properly handles overflow
-        let max_votes = u64::MAX;
-        let result = max_votes.checked_add(1);
-        assert!(result.is_none(), "Should return None on overflow");
-    }
 
-    #[test]
-    fn test_checked_add_normal_increment() {
-        // Test normal vote increment
-        let votes = 0u64;
-        let result = votes.checked_add(1);
-        assert_eq!(result, Some(1));
-
-        let votes = 999u64;
-        let result = votes.checked_add(1);
-        assert_eq!(result, Some(1000));
-    }
-
-    #[test]
-    fn test_candidate_keys_uniqueness() {
-        // Test that two different pubkeys are unique
-        let key1 = Pubkey::new_unique();
-        let key2 = Pubkey::new_unique();
-        assert_ne!(key1, key2);
-    }
-
-    #[test]
-    fn test_candidate_keys_equality() {
-        // Test pubkey equality
-        let key1 = Pubkey::new_unique();
-        let key2 = key1;
-        assert_eq!(key1, key2);
-    }
-
-    #[test]
-    fn test_voter_state_transitions() {
-        // Test voter state changes from not voted to voted
-        let authority = Pubkey::new_unique();
-        let mut voter = VoterAccount {
-            authority,
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
-        };
-
-        // Initial state
-        assert!(!voter.voted);
-        assert_eq!(voter.votes[0], Pubkey::default());
-        assert_eq!(voter.votes[1], Pubkey::default());
-
-        // Simulate voting
-        let candidate1 = Pubkey::new_unique();
-        let candidate2 = Pubkey::new_unique();
-        voter.voted = true;
-        voter.votes = [candidate1, candidate2];
-
-        // After voting state
-        assert!(voter.voted);
-        assert_eq!(voter.votes[0], candidate1);
-        assert_eq!(voter.votes[1], candidate2);
-    }
-
-    #[test]
-    fn test_multiple_vote_increments() {
-        // Test multiple sequential vote increments
-        let mut candidate = CandidateAccount {
-            name: String::from("Alice"),
-            votes: 0,
-            bump: 255,
-        };
-
-        for i in 1..=10 {
-            candidate.votes = candidate.votes.checked_add(1).unwrap();
-            assert_eq!(candidate.votes, i);
-        }
-
-        assert_eq!(candidate.votes, 10);
-    }
+    /// Reimburses `relayer` for the transaction fee; passed together with it
+    #[account(
+        mut,
+        seeds = [b"sponsor_budget", election.key().as_ref()],
+        bump = sponsor_budget.bump
+    )]
+    pub sponsor_budget: Option<Account<'info, SponsorBudget>>,
 
-    #[test]
-    fn test_candidate_vote_count_persistence() {
-        // Test that vote counts are properly stored
-        let mut candidate1 = CandidateAccount {
-            name: String::from("Alice"),
-            votes: 5,
-            bump: 255,
-        };
-
-        let mut candidate2 = CandidateAccount {
-            name: String::from("Bob"),
-            votes: 3,
-            bump: 254,
-        };
-
-        // Increment both
-        candidate1.votes = candidate1.votes.checked_add(1).unwrap();
-        candidate2.votes = candidate2.votes.checked_add(1).unwrap();
-
-        assert_eq!(candidate1.votes, 6);
-        assert_eq!(candidate2.votes, 4);
-    }
+    /// Fee payer of a sponsored vote
+    #[account(mut)]
+    pub relayer: Option<Signer<'info>>,
 }
-
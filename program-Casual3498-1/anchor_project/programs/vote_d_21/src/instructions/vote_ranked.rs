@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Cast an instant-runoff ballot ranking candidates in order of preference.
+/// Seeds: ["ballot", voter.key().as_ref()]
+/// Rankings may list any number of the election's candidates, each once.
+/// Only accepted while the voting window is open; a voter casts one ballot.
+/// Not available while the program is paused.
+pub fn vote_ranked(ctx: Context<VoteRanked>, rankings: Vec<Pubkey>) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    let election = &mut ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_method(VotingMethod::InstantRunoff)?;
+
+    let voter = &mut ctx.accounts.voter;
+    require!(!voter.voted, VoteError::AlreadyVoted);
+    require_keys_eq!(
+        voter.authority,
+        ctx.accounts.authority.key(),
+        VoteError::UnauthorizedAccess
+    );
+    voter.check_not_delegated()?;
+
+    RankedBallot::validate(&rankings, election)?;
+
+    let weight = voter.vote_weight()?;
+    require!(weight > 0, VoteError::NoVotingWeight);
+
+    let ballot = &mut ctx.accounts.ballot;
+    ballot.election = election.key();
+    ballot.voter = voter.key();
+    ballot.rankings = rankings.clone();
+    ballot.weight = weight;
+    ballot.counted_round = 0;
+    ballot.bump = ctx.bumps.ballot;
+
+    voter.voted = true;
+    election.record_ballot()?;
+
+    emit!(RankedBallotCast {
+        header: EventHeader::now()?,
+        election: voter.election,
+        voter: voter.authority,
+        rankings,
+        weight,
+        ballots_cast: election.ballots_cast,
+    });
+
+    msg!("Ranked ballot cast: {} preferences", ballot.rankings.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoteRanked<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", election.key().as_ref(), authority.key().as_ref()],
+        bump = voter.bump
+    )]
+    pub voter: Account<'info, VoterAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RankedBallot::SPACE,
+        seeds = [b"ballot", voter.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, RankedBallot>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
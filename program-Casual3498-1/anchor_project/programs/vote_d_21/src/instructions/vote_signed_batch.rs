@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use crate::state::*;
+use crate::errors::*;
+use crate::instructions::vote::cast_ballot;
+
+/// Offsets of one signature in Ed25519 program instruction data, after the
+/// one byte signature count and one byte of padding
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+/// Instruction index meaning the Ed25519 instruction's own data
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Cast ballots that voters signed off-chain, submitted by anyone in one
+/// transaction. The instruction right before this one must be an Ed25519
+/// program instruction verifying one signature per ballot, in order, by the
+/// ballot's authority over `SignedBallot::message`. Each ballot's nonce must
+/// match its voter account, which then advances it so the signature cannot
+/// be replayed.
+/// For each ballot, its voter account followed by its candidate accounts in
+/// ballot order are passed as remaining accounts.
+/// Subject to the same rules as `vote`, and not available while the program
+/// is paused.
+pub fn vote_signed_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VoteSignedBatch<'info>>,
+    ballots: Vec<SignedBallot>,
+) -> Result<()> {
+    ctx.accounts.config.check_not_paused()?;
+    require!(
+        !ballots.is_empty() && ballots.len() <= SignedBallot::MAX_BATCH,
+        VoteError::InvalidBatchSize
+    );
+    let election = &mut ctx.accounts.election;
+    election.check_voting_window(Clock::get()?.unix_timestamp)?;
+    election.check_tallied_method()?;
+    require!(!election.is_commit_reveal(), VoteError::CommitRevealRequired);
+
+    // The runtime rejects the transaction unless every signature in the
+    // Ed25519 instruction is valid, so only what it signed is checked here
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current = load_current_index_checked(&instructions)?;
+    require!(current > 0, VoteError::InvalidBallotSignature);
+    let ed25519 = load_instruction_at_checked(usize::from(current - 1), &instructions)?;
+    require_keys_eq!(ed25519.program_id, ed25519_program::ID, VoteError::InvalidBallotSignature);
+    let signatures = verified_signatures(&ed25519.data)?;
+    require_eq!(signatures.len(), ballots.len(), VoteError::InvalidBallotSignature);
+
+    let count = ballots.len();
+    let mut accounts = ctx.remaining_accounts;
+    for (signed, (signer, message)) in ballots.into_iter().zip(signatures) {
+        require!(
+            signer == signed.authority && message == signed.message(&election.key()),
+            VoteError::InvalidBallotSignature
+        );
+
+        let (voter_info, rest) = accounts.split_first().ok_or(ErrorCode::AccountNotEnoughKeys)?;
+        let candidate_count = signed.ballot.candidates().count();
+        require!(rest.len() >= candidate_count, ErrorCode::AccountNotEnoughKeys);
+        let (candidate_accounts, rest) = rest.split_at(candidate_count);
+        accounts = rest;
+
+        require!(voter_info.is_writable, ErrorCode::AccountNotMutable);
+        let mut voter = Account::<VoterAccount>::try_from(voter_info)?;
+        require_keys_eq!(voter.election, election.key(), VoteError::UnauthorizedAccess);
+        require_keys_eq!(voter.authority, signed.authority, VoteError::UnauthorizedAccess);
+        require!(!voter.voted, VoteError::AlreadyVoted);
+        require_eq!(signed.nonce, voter.nonce, VoteError::InvalidNonce);
+        voter.nonce = voter.nonce.checked_add(1).ok_or(VoteError::VoteOverflow)?;
+
+        cast_ballot(election, &mut voter, candidate_accounts, signed.ballot)?;
+        // Written back now, so a later ballot in the batch sees this one
+        voter.exit(&crate::ID)?;
+    }
+
+    msg!("Signed ballots cast: {}", count);
+    Ok(())
+}
+
+/// Public keys and messages of the signatures an Ed25519 program instruction
+/// verifies. Signatures taking their key or message from another instruction
+/// are rejected, since only this instruction's data is read here.
+pub(crate) fn verified_signatures(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+    let count = usize::from(*data.first().ok_or(VoteError::InvalidBallotSignature)?);
+    (0..count)
+        .map(|i| {
+            let start = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SIZE;
+            let offsets = data
+                .get(start..start + SIGNATURE_OFFSETS_SIZE)
+                .ok_or(VoteError::InvalidBallotSignature)?;
+            // signature offset, signature instruction, public key offset,
+            // public key instruction, message offset, message size, message instruction
+            let field = |n: usize| u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]);
+            require!(
+                [field(1), field(3), field(6)].iter().all(|&index| index == CURRENT_INSTRUCTION),
+                VoteError::InvalidBallotSignature
+            );
+            let key_start = usize::from(field(2));
+            let message_start = usize::from(field(4));
+            let key = data
+                .get(key_start..key_start + 32)
+                .ok_or(VoteError::InvalidBallotSignature)?;
+            let message = data
+                .get(message_start..message_start + usize::from(field(5)))
+                .ok_or(VoteError::InvalidBallotSignature)?;
+            Ok((Pubkey::try_from(key).unwrap(), message))
+        })
+        .collect()
+}
+
+#[derive(Accounts)]
+pub struct VoteSignedBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: the instructions sysvar, checked by address
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 instruction data for `entries`, laid out as the client does:
+    /// offsets first, then each key, signature and message.
+    fn ed25519_data(entries: &[(Pubkey, &[u8])], instruction_index: u16) -> Vec<u8> {
+        let mut data = vec![entries.len() as u8, 0];
+        let mut offset = SIGNATURE_OFFSETS_START + entries.len() * SIGNATURE_OFFSETS_SIZE;
+        let mut payload = Vec::new();
+        for (key, message) in entries {
+            let (key_offset, signature_offset) = (offset, offset + 32);
+            let message_offset = signature_offset + 64;
+            for field in [
+                signature_offset as u16,
+                instruction_index,
+                key_offset as u16,
+                instruction_index,
+                message_offset as u16,
+                message.len() as u16,
+                instruction_index,
+            ] {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+            payload.extend_from_slice(key.as_ref());
+            payload.extend_from_slice(&[0; 64]);
+            payload.extend_from_slice(message);
+            offset = message_offset + message.len();
+        }
+        data.extend_from_slice(&payload);
+        data
+    }
+
+    #[test]
+    fn test_verified_signatures_reads_keys_and_messages() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = ed25519_data(&[(a, b"first"), (b, b"second ballot")], CURRENT_INSTRUCTION);
+        let signatures = verified_signatures(&data).unwrap();
+        assert_eq!(signatures, vec![(a, &b"first"[..]), (b, &b"second ballot"[..])]);
+    }
+
+    #[test]
+    fn test_verified_signatures_rejects_foreign_data() {
+        // Keys or messages read from another instruction are not what was parsed here
+        let data = ed25519_data(&[(Pubkey::new_unique(), b"ballot")], 0);
+        assert_eq!(
+            verified_signatures(&data).unwrap_err(),
+            VoteError::InvalidBallotSignature.into()
+        );
+    }
+
+    #[test]
+    fn test_verified_signatures_rejects_truncated_data() {
+        let data = ed25519_data(&[(Pubkey::new_unique(), b"ballot")], CURRENT_INSTRUCTION);
+        assert!(verified_signatures(&data[..data.len() - 1]).is_err());
+        assert!(verified_signatures(&data[..10]).is_err());
+        assert!(verified_signatures(&[]).is_err());
+        assert!(verified_signatures(&[0, 0]).unwrap().is_empty());
+    }
+}
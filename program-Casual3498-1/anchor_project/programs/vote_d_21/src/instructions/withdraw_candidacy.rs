@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+/// Withdraw a candidacy. Only the candidate's owner may withdraw, until the
+/// election is finalized. Votes already cast stay on the account but the
+/// candidate can no longer receive votes and is left out of the results.
+/// Instant-runoff candidacies are fixed once tallies are final, as the count
+/// eliminates the inactive candidates when it starts.
+pub fn withdraw_candidacy(ctx: Context<WithdrawCandidacy>, reason: String) -> Result<()> {
+    ctx.accounts.election.check_candidacy_changeable(Clock::get()?.unix_timestamp)?;
+
+    let candidate = &mut ctx.accounts.candidate;
+    candidate.check_removable(&reason)?;
+    candidate.status = CandidateStatus::Withdrawn;
+
+    emit!(CandidateWithdrawn {
+        header: EventHeader::now()?,
+        election: candidate.election,
+        pubkey: candidate.key(),
+        reason,
+    });
+
+    msg!("Candidate withdrawn: {}", candidate.name);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCandidacy<'info> {
+    #[account(
+        seeds = [b"election", election.creator.as_ref(), election.election_id.to_le_bytes().as_ref()],
+        bump = election.bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"candidate", election.key().as_ref(), &CandidateAccount::name_seed(&candidate.name)],
+        bump = candidate.bump,
+        has_one = owner @ VoteError::UnauthorizedAccess
+    )]
+    pub candidate: Account<'info, CandidateAccount>,
+
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::initialize_sponsor_budget::available_lamports;
+
+/// Return `amount` unspent lamports from a sponsor budget to its sponsor.
+/// The budget keeps its rent exemption, so it can be funded again later.
+pub fn withdraw_sponsor_budget(ctx: Context<WithdrawSponsorBudget>, amount: u64) -> Result<()> {
+    let budget = &mut ctx.accounts.sponsor_budget;
+    let available = available_lamports(budget)?;
+    require!(amount <= available, VoteError::SponsorBudgetExhausted);
+    budget.sub_lamports(amount)?;
+    ctx.accounts.sponsor.add_lamports(amount)?;
+
+    emit!(SponsorBudgetWithdrawn {
+        header: EventHeader::now()?,
+        election: budget.election,
+        sponsor: budget.sponsor,
+        amount,
+        available: available - amount,
+    });
+
+    msg!("Withdrew {} lamports from sponsor budget", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSponsorBudget<'info> {
+    #[account(
+        mut,
+        seeds = [b"sponsor_budget", sponsor_budget.election.as_ref()],
+        bump = sponsor_budget.bump,
+        has_one = sponsor @ VoteError::UnauthorizedAccess
+    )]
+    pub sponsor_budget: Account<'info, SponsorBudget>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+}
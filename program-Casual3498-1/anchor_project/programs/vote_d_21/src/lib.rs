@@ -1,3 +1,6 @@
+// `#[program]` still expands to the deprecated `AccountInfo::realloc` in Anchor 0.31.
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 
 // Module declarations
@@ -20,23 +23,305 @@ declare_id!("7qsdAz3ta9gg3eikuzQuJMj928zFnPUB8C4rb42pr6RN");
 pub mod vote_d_21 {
     use super::*;
 
-    /// Initialize a candidate account with a unique name.
-    /// Seeds: ["candidate", name.as_bytes()]
+    /// Create a new election owned by the creator.
+    /// Seeds: ["election", creator.key().as_ref(), election_id.to_le_bytes()]
+    /// Ballots are accepted in `[starts_at, ends_at)` once opened.
+    /// Voters get `positive_votes` positive and `negative_votes` negative votes.
+    pub fn initialize_election(
+        ctx: Context<InitializeElection>,
+        election_id: u64,
+        title: String,
+        starts_at: i64,
+        ends_at: i64,
+        positive_votes: u8,
+        negative_votes: u8,
+    ) -> Result<()> {
+        instructions::initialize_election::initialize_election(
+            ctx,
+            election_id,
+            title,
+            starts_at,
+            ends_at,
+            positive_votes,
+            negative_votes,
+        )
+    }
+
+    /// Open a pending election once `starts_at` is reached. Anyone may call it.
+    pub fn open_election(ctx: Context<OpenElection>) -> Result<()> {
+        instructions::open_election::open_election(ctx)
+    }
+
+    /// Close an election after `ends_at`. Anyone may call it.
+    pub fn close_election(ctx: Context<CloseElection>) -> Result<()> {
+        instructions::close_election::close_election(ctx)
+    }
+
+    /// Rank all candidates after `ends_at` and record winners and ties in the
+    /// election's `Results` account. Anyone may call it.
+    pub fn finalize_election<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeElection<'info>>,
+    ) -> Result<()> {
+        instructions::finalize_election::finalize_election(ctx)
+    }
+
+    /// Allow-list a registrar who may register candidates. Admin only.
+    pub fn add_registrar(ctx: Context<AddRegistrar>, registrar: Pubkey) -> Result<()> {
+        instructions::add_registrar::add_registrar(ctx, registrar)
+    }
+
+    /// Remove a registrar from the allow-list. Admin only.
+    pub fn remove_registrar(ctx: Context<RemoveRegistrar>, registrar: Pubkey) -> Result<()> {
+        instructions::remove_registrar::remove_registrar(ctx, registrar)
+    }
+
+    /// Nominate a new admin. Takes effect once the nominee calls `accept_admin`.
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::transfer_admin::transfer_admin(ctx, new_admin)
+    }
+
+    /// Accept a pending admin nomination. Must be signed by the nominee.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::accept_admin(ctx)
+    }
+
+    /// Set who may register as a voter. Admin only, before the election opens.
+    pub fn set_eligibility(ctx: Context<SetEligibility>, mode: EligibilityMode) -> Result<()> {
+        instructions::set_eligibility::set_eligibility(ctx, mode)
+    }
+
+    /// Whitelist a wallet for voter registration. Admin only.
+    /// Seeds: ["whitelist", election.key().as_ref(), voter.as_ref()]
+    pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, voter: Pubkey) -> Result<()> {
+        instructions::add_to_whitelist::add_to_whitelist(ctx, voter)
+    }
+
+    /// Remove a wallet from the whitelist. Admin only.
+    pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>) -> Result<()> {
+        instructions::remove_from_whitelist::remove_from_whitelist(ctx)
+    }
+
+    /// Set how ballots are weighted. Admin only, before the election opens.
+    pub fn set_vote_weighting(ctx: Context<SetVoteWeighting>, weighting: VoteWeighting) -> Result<()> {
+        instructions::set_vote_weighting::set_vote_weighting(ctx, weighting)
+    }
+
+    /// Escrow governance tokens to gain voting weight in a locked token-weighted election.
+    /// Seeds: ["vault", voter.key().as_ref()]
+    pub fn lock_tokens(ctx: Context<LockTokens>, amount: u64) -> Result<()> {
+        instructions::lock_tokens::lock_tokens(ctx, amount)
+    }
+
+    /// Return escrowed governance tokens after the election ends.
+    pub fn unlock_tokens(ctx: Context<UnlockTokens>) -> Result<()> {
+        instructions::unlock_tokens::unlock_tokens(ctx)
+    }
+
+    /// Initialize a candidate account with a name unique within its election.
+    /// Only the admin or an allow-listed registrar may call it.
+    /// Seeds: ["candidate", election.key().as_ref(), CandidateAccount::name_seed(name)]
     /// Fails if PDA already exists.
-    pub fn initialize_candidate(ctx: Context<InitializeCandidate>, name: String) -> Result<()> {
-        instructions::initialize_candidate::initialize_candidate(ctx, name)
+    pub fn initialize_candidate(
+        ctx: Context<InitializeCandidate>,
+        name: String,
+        description: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::initialize_candidate::initialize_candidate(ctx, name, description, uri)
+    }
+
+    /// Self-nominate as a candidate. The nomination must be approved by the
+    /// admin before it appears on the ballot.
+    pub fn nominate_candidate(
+        ctx: Context<NominateCandidate>,
+        name: String,
+        description: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::nominate_candidate::nominate_candidate(ctx, name, description, uri)
+    }
+
+    /// Approve a nomination and add the candidate to the ballot. Admin only.
+    pub fn approve_candidate(ctx: Context<ApproveCandidate>) -> Result<()> {
+        instructions::approve_candidate::approve_candidate(ctx)
     }
 
-    /// Initialize a voter account for a specific authority.
-    /// Seeds: ["voter", authority.key().as_ref()]
-    /// Marks voted = false initially.
-    pub fn initialize_voter(ctx: Context<InitializeVoter>) -> Result<()> {
-        instructions::initialize_voter::initialize_voter(ctx)
+    /// Withdraw from the race. Candidate owner only; votes already cast stay
+    /// recorded but the candidate can no longer receive votes or win.
+    pub fn withdraw_candidacy(ctx: Context<WithdrawCandidacy>, reason: String) -> Result<()> {
+        instructions::withdraw_candidacy::withdraw_candidacy(ctx, reason)
     }
 
-    /// Cast votes for two distinct candidates.
+    /// Disqualify a candidate, recording the reason in an event. Admin only.
+    pub fn disqualify_candidate(ctx: Context<DisqualifyCandidate>, reason: String) -> Result<()> {
+        instructions::disqualify_candidate::disqualify_candidate(ctx, reason)
+    }
+
+    /// Reject a nomination, closing it so the name can be registered again.
+    /// Admin only.
+    pub fn reject_candidate(ctx: Context<RejectCandidate>) -> Result<()> {
+        instructions::reject_candidate::reject_candidate(ctx)
+    }
+
+    /// Initialize a voter account for a specific authority within an election.
+    /// Seeds: ["voter", election.key().as_ref(), authority.key().as_ref()]
+    /// Marks voted = false initially. `proof` is only used for Merkle eligibility.
+    /// A sponsored registration reimburses the relayer paying for it.
+    pub fn initialize_voter(ctx: Context<InitializeVoter>, proof: Vec<[u8; 32]>) -> Result<()> {
+        instructions::initialize_voter::initialize_voter(ctx, proof)
+    }
+
+    /// Cast a D21, approval or score ballot for distinct candidates.
+    /// Candidate accounts are passed as remaining accounts in ballot order.
     /// Requires signer authority match, prevents duplicate candidates and double voting.
-    pub fn vote(ctx: Context<Vote>, candidate_keys: [Pubkey; 2]) -> Result<()> {
-        instructions::vote::vote(ctx, candidate_keys)
+    /// A sponsored vote reimburses the relayer paying its fee.
+    pub fn vote<'info>(ctx: Context<'_, '_, 'info, 'info, Vote<'info>>, ballot: Ballot) -> Result<()> {
+        instructions::vote::vote(ctx, ballot)
+    }
+
+    /// Cast a batch of ballots signed off-chain by their voters, verified by
+    /// the Ed25519 program instruction preceding this one. Anyone may submit
+    /// them; each ballot's nonce prevents replays.
+    pub fn vote_signed_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VoteSignedBatch<'info>>,
+        ballots: Vec<SignedBallot>,
+    ) -> Result<()> {
+        instructions::vote_signed_batch::vote_signed_batch(ctx, ballots)
+    }
+
+    /// Delegate this voter's ballot along a chain of voters passed as remaining
+    /// accounts. The representative at the end votes with the delegated weight.
+    pub fn delegate<'info>(ctx: Context<'_, '_, 'info, 'info, Delegate<'info>>) -> Result<()> {
+        instructions::delegate::delegate(ctx)
+    }
+
+    /// Take back a delegated ballot before the representative has voted.
+    pub fn undelegate<'info>(ctx: Context<'_, '_, 'info, 'info, Undelegate<'info>>) -> Result<()> {
+        instructions::undelegate::undelegate(ctx)
+    }
+
+    /// Replace a cast ballot while voting is open, moving the voter's weight
+    /// from the old choices to the new ones atomically.
+    pub fn change_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ChangeVote<'info>>,
+        ballot: Ballot,
+    ) -> Result<()> {
+        instructions::change_vote::change_vote(ctx, ballot)
+    }
+
+    /// Close a voter account after finalization and refund its rent to the
+    /// original payer. Voter authority only.
+    pub fn close_voter(ctx: Context<CloseVoter>) -> Result<()> {
+        instructions::close_voter::close_voter(ctx)
+    }
+
+    /// Close a candidate account after finalization and refund its rent to
+    /// the original payer. Admin only.
+    pub fn close_candidate(ctx: Context<CloseCandidate>) -> Result<()> {
+        instructions::close_candidate::close_candidate(ctx)
+    }
+
+    /// Choose D21, instant-runoff, approval or score voting. Admin only, before the election opens.
+    pub fn set_voting_method(ctx: Context<SetVotingMethod>, method: VotingMethod) -> Result<()> {
+        instructions::set_voting_method::set_voting_method(ctx, method)
+    }
+
+    /// Cast an instant-runoff ballot: candidate keys in order of preference.
+    pub fn vote_ranked(ctx: Context<VoteRanked>, rankings: Vec<Pubkey>) -> Result<()> {
+        instructions::vote_ranked::vote_ranked(ctx, rankings)
+    }
+
+    /// Open the instant-runoff count after voting ends. Anyone may call it.
+    /// All candidates are passed as remaining accounts.
+    pub fn start_runoff<'info>(ctx: Context<'_, '_, 'info, 'info, StartRunoff<'info>>) -> Result<()> {
+        instructions::start_runoff::start_runoff(ctx)
+    }
+
+    /// Count a batch of ranked ballots, passed as remaining accounts, towards the
+    /// current runoff round; concludes the round once all ballots are counted.
+    /// Anyone may call it.
+    pub fn tabulate_round<'info>(ctx: Context<'_, '_, 'info, 'info, TabulateRound<'info>>) -> Result<()> {
+        instructions::tabulate_round::tabulate_round(ctx)
+    }
+
+    /// Enable commit-reveal ballots with a reveal deadline after `ends_at`,
+    /// or disable them with `None`. Admin only, before the election opens.
+    pub fn set_commit_reveal(ctx: Context<SetCommitReveal>, reveal_ends_at: Option<i64>) -> Result<()> {
+        instructions::set_commit_reveal::set_commit_reveal(ctx, reveal_ends_at)
+    }
+
+    /// Require `quorum_bps` basis points of registered voters to vote, or
+    /// clear the quorum with `None`. Admin only, before the election opens.
+    pub fn set_quorum(ctx: Context<SetQuorum>, quorum_bps: Option<u16>) -> Result<()> {
+        instructions::set_quorum::set_quorum(ctx, quorum_bps)
+    }
+
+    /// Commit to a hidden ballot, sha256(borsh(ballot) || salt), while voting is open.
+    pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_vote::commit_vote(ctx, commitment)
+    }
+
+    /// Reveal a committed ballot during the reveal window and tally it.
+    /// Candidate accounts are passed as remaining accounts in ballot order.
+    pub fn reveal_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RevealVote<'info>>,
+        ballot: Ballot,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_vote::reveal_vote(ctx, ballot, salt)
+    }
+
+    /// Withdraw a cast ballot while voting is open and reset the voter to unvoted.
+    pub fn revoke_vote<'info>(ctx: Context<'_, '_, 'info, 'info, RevokeVote<'info>>) -> Result<()> {
+        instructions::revoke_vote::revoke_vote(ctx)
+    }
+
+    /// Create the program-wide config naming the emergency authority.
+    /// Upgrade authority only, once per deployment.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, emergency_authority: Pubkey) -> Result<()> {
+        instructions::initialize_config::initialize_config(ctx, emergency_authority)
+    }
+
+    /// Halt candidate registration, voter registration and voting in every
+    /// election. Emergency authority only.
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        instructions::pause::pause(ctx)
+    }
+
+    /// Resume registration and voting. Emergency authority only.
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        instructions::unpause::unpause(ctx)
+    }
+
+    /// Move a voter from the first deployment into an election, with its
+    /// ballot. Admin only.
+    pub fn migrate_voter<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateVoter<'info>>) -> Result<()> {
+        instructions::migrate_voter::migrate_voter(ctx)
+    }
+
+    /// Move a candidate from the first deployment into an election, with its
+    /// tally. Admin only.
+    pub fn migrate_candidate(ctx: Context<MigrateCandidate>, name: String) -> Result<()> {
+        instructions::migrate_candidate::migrate_candidate(ctx, name)
+    }
+
+    /// Create the election's sponsor budget, which reimburses `relayer` for
+    /// fees and rent it pays on behalf of voters. Admin only.
+    pub fn initialize_sponsor_budget(
+        ctx: Context<InitializeSponsorBudget>,
+        relayer: Pubkey,
+        fee_per_transaction: u64,
+    ) -> Result<()> {
+        instructions::initialize_sponsor_budget::initialize_sponsor_budget(ctx, relayer, fee_per_transaction)
+    }
+
+    /// Deposit lamports into a sponsor budget. Anyone may fund it.
+    pub fn fund_sponsor_budget(ctx: Context<FundSponsorBudget>, amount: u64) -> Result<()> {
+        instructions::fund_sponsor_budget::fund_sponsor_budget(ctx, amount)
+    }
+
+    /// Return unspent lamports from a sponsor budget to its sponsor.
+    pub fn withdraw_sponsor_budget(ctx: Context<WithdrawSponsorBudget>, amount: u64) -> Result<()> {
+        instructions::withdraw_sponsor_budget::withdraw_sponsor_budget(ctx, amount)
     }
 }
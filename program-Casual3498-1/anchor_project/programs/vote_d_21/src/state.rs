@@ -1,43 +1,1598 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use caseless::default_case_fold_str;
+use unicode_normalization::UnicodeNormalization;
 
-/// Candidate account storing candidate information and vote count
+/// Lifecycle of an election
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ElectionStatus {
+    /// Created, voting window not opened yet
+    Pending,
+    /// Voting window opened
+    Active,
+    /// Voting window ended, tallies are final
+    Closed,
+    /// Tallies ranked and written to the election's `Results` account
+    Finalized,
+    /// Finalized without reaching quorum: results are recorded but there is no winner
+    Invalid,
+}
+
+/// How ballots are cast and counted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingMethod {
+    /// Positive and negative votes (Janeček method), tallied on candidate accounts
+    D21,
+    /// Ordered preferences stored in `RankedBallot` accounts and counted by
+    /// instant-runoff rounds in a `RunoffTally`
+    InstantRunoff,
+    /// Voters approve any subset of candidates, tallied on candidate accounts
+    Approval,
+    /// Voters give each candidate they rate `0..=max_score` points
+    Score { max_score: u8 },
+}
+
+/// Who may register as a voter in an election
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EligibilityMode {
+    /// Any wallet may register
+    Open,
+    /// Only wallets with a `WhitelistEntry` created by the admin
+    Whitelist,
+    /// Only wallets proving membership in a Merkle tree of eligible pubkeys
+    MerkleRoot { root: [u8; 32] },
+}
+
+impl EligibilityMode {
+    pub const MAX_PROOF_LEN: usize = 32;
+
+    /// Leaf hash of an eligible voter: sha256(0x00 || pubkey).
+    pub fn merkle_leaf(voter: &Pubkey) -> [u8; 32] {
+        hashv(&[&[0u8], voter.as_ref()]).to_bytes()
+    }
+
+    /// Inner node hash: sha256(0x01 || min(a, b) || max(a, b)).
+    pub fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        hashv(&[&[1u8], left, right]).to_bytes()
+    }
+
+    /// Verify that `voter` is a leaf of the tree committed to by `root`.
+    pub fn verify_merkle_proof(root: &[u8; 32], voter: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        if proof.len() > Self::MAX_PROOF_LEN {
+            return false;
+        }
+        let computed = proof
+            .iter()
+            .fold(Self::merkle_leaf(voter), |node, sibling| Self::merkle_node(&node, sibling));
+        computed == *root
+    }
+}
+
+/// How much each registered voter's ballot counts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VoteWeighting {
+    /// One wallet, one ballot
+    Equal,
+    /// One ballot per voter locking at least `min_balance` of `mint` through `lock_tokens`
+    TokenGated { mint: Pubkey, min_balance: u64 },
+    /// Ballot weighted by the amount of `mint` escrowed in a vault through
+    /// `lock_tokens` until the election ends
+    TokenWeighted { mint: Pubkey },
+}
+
+impl VoteWeighting {
+    /// Governance mint the weighting depends on, if any.
+    pub fn mint(&self) -> Option<Pubkey> {
+        match self {
+            VoteWeighting::Equal => None,
+            VoteWeighting::TokenGated { mint, .. } | VoteWeighting::TokenWeighted { mint, .. } => {
+                Some(*mint)
+            }
+        }
+    }
+
+    /// Weight granted at registration. Token weighting grants nothing until
+    /// tokens are locked.
+    pub fn registration_weight(&self) -> u64 {
+        match self {
+            VoteWeighting::Equal => 1,
+            VoteWeighting::TokenGated { .. } | VoteWeighting::TokenWeighted { .. } => 0,
+        }
+    }
+
+    /// Weight granted for escrowing `amount` tokens through `lock_tokens`.
+    pub fn locked_weight(&self, amount: u64) -> Result<u64> {
+        match *self {
+            VoteWeighting::Equal => err!(crate::errors::VoteError::TokensNotLockable),
+            VoteWeighting::TokenGated { min_balance, .. } => {
+                require!(
+                    amount > 0 && amount >= min_balance,
+                    crate::errors::VoteError::InsufficientTokenBalance
+                );
+                Ok(1)
+            }
+            VoteWeighting::TokenWeighted { .. } => {
+                require!(amount > 0, crate::errors::VoteError::InsufficientTokenBalance);
+                Ok(amount)
+            }
+        }
+    }
+}
+
+/// Election (ballot) account scoping candidates and voters of a single poll
 #[account]
+pub struct Election {
+    pub creator: Pubkey,                 // 32 bytes
+    pub election_id: u64,                // 8 bytes
+    pub title: String,                   // Max 64 chars = 4 + 64 = 68 bytes
+    pub candidates: Vec<Pubkey>,         // Max 16 keys = 4 + 16 * 32 = 516 bytes
+    pub status: ElectionStatus,          // 1 byte
+    pub starts_at: i64,                  // 8 bytes
+    pub ends_at: i64,                    // 8 bytes
+    pub positive_votes: u8,              // 1 byte
+    pub negative_votes: u8,              // 1 byte
+    pub admin: Pubkey,                   // 32 bytes
+    pub pending_admin: Option<Pubkey>,   // 1 + 32 = 33 bytes
+    pub registrars: Vec<Pubkey>,         // Max 4 keys = 4 + 4 * 32 = 132 bytes
+    pub eligibility: EligibilityMode,    // 1 + 32 = 33 bytes
+    pub weighting: VoteWeighting,        // 1 + 32 + 8 = 41 bytes
+    pub reveal_ends_at: Option<i64>,     // 1 + 8 = 9 bytes, Some for commit-reveal elections
+    pub method: VotingMethod,            // 1 + 1 = 2 bytes
+    pub registered_voters: u32,          // 4 bytes
+    pub ballots_cast: u32,               // 4 bytes
+    pub quorum_bps: Option<u16>,         // 1 + 2 = 3 bytes, share of registered voters that must vote
+    pub bump: u8,                        // 1 byte
+}
+
+impl Election {
+    pub const MAX_TITLE_LEN: usize = 64;
+    pub const MAX_CANDIDATES: usize = 16;
+    pub const MAX_POSITIVE_VOTES: usize = Self::MAX_CANDIDATES;
+    pub const MAX_NEGATIVE_VOTES: usize = Self::MAX_POSITIVE_VOTES / 2;
+    pub const MAX_REGISTRARS: usize = 4;
+    /// A quorum of 10 000 basis points requires every registered voter to vote.
+    pub const MAX_QUORUM_BPS: u16 = 10_000;
+    // 8 (discriminator) + 32 (creator) + 8 (election_id) + 68 (title) + 516 (candidates) + 1 (status) + 8 (starts_at) + 8 (ends_at)
+    // + 1 (positive_votes) + 1 (negative_votes) + 32 (admin) + 33 (pending_admin) + 132 (registrars) + 33 (eligibility)
+    // + 41 (weighting) + 9 (reveal_ends_at) + 2 (method) + 4 (registered_voters) + 4 (ballots_cast)
+    // + 3 (quorum_bps) + 1 (bump) = 945 bytes
+    pub const SPACE: usize = 8 + 32 + 8 + (4 + Self::MAX_TITLE_LEN) + (4 + Self::MAX_CANDIDATES * 32) + 1 + 8 + 8 + 1 + 1
+        + 32 + (1 + 32) + (4 + Self::MAX_REGISTRARS * 32) + (1 + 32) + (1 + 32 + 8) + (1 + 8) + (1 + 1) + 4 + 4 + (1 + 2) + 1;
+
+    /// The admin and allow-listed registrars may register candidates.
+    pub fn is_registrar(&self, key: &Pubkey) -> bool {
+        *key == self.admin || self.registrars.contains(key)
+    }
+
+    /// D21 parameters: at least one positive vote, and at most one negative
+    /// vote for every two positive votes a voter may cast.
+    pub fn check_vote_config(positive_votes: u8, negative_votes: u8) -> Result<()> {
+        require!(
+            positive_votes >= 1 && positive_votes as usize <= Self::MAX_POSITIVE_VOTES,
+            crate::errors::VoteError::InvalidVoteConfig
+        );
+        require!(
+            negative_votes <= positive_votes / 2,
+            crate::errors::VoteError::InvalidVoteConfig
+        );
+        Ok(())
+    }
+
+    /// Settings that shape the electorate can only change before voting opens.
+    pub fn check_configurable(&self) -> Result<()> {
+        require!(
+            self.status == ElectionStatus::Pending,
+            crate::errors::VoteError::ConfigurationLocked
+        );
+        Ok(())
+    }
+
+    /// Rules that decide who registers, and with what weight, are fixed by
+    /// the first registration, since existing voters are never rechecked.
+    pub fn check_electorate_configurable(&self) -> Result<()> {
+        self.check_configurable()?;
+        require!(
+            self.registered_voters == 0,
+            crate::errors::VoteError::VotersAlreadyRegistered
+        );
+        Ok(())
+    }
+
+    /// Accounts from the first deployment only join elections run by the
+    /// rules they were cast under: public, equally weighted D21 ballots.
+    pub fn check_legacy_import(&self, now: i64) -> Result<()> {
+        self.check_registration_open(now)?;
+        require!(
+            self.method == VotingMethod::D21
+                && self.weighting == VoteWeighting::Equal
+                && !self.is_commit_reveal(),
+            crate::errors::VoteError::WrongVotingMethod
+        );
+        Ok(())
+    }
+
+    /// Candidates and voters may register until the voting window ends.
+    pub fn check_registration_open(&self, now: i64) -> Result<()> {
+        require!(
+            matches!(self.status, ElectionStatus::Pending | ElectionStatus::Active),
+            crate::errors::VoteError::ElectionNotActive
+        );
+        require!(now < self.ends_at, crate::errors::VoteError::VotingEnded);
+        Ok(())
+    }
+
+    /// Ballots are accepted only in `[starts_at, ends_at)` of an active election.
+    pub fn check_voting_window(&self, now: i64) -> Result<()> {
+        require!(
+            self.status == ElectionStatus::Active,
+            crate::errors::VoteError::ElectionNotActive
+        );
+        require!(now >= self.starts_at, crate::errors::VoteError::VotingNotStarted);
+        require!(now < self.ends_at, crate::errors::VoteError::VotingEnded);
+        Ok(())
+    }
+
+    /// Commit-reveal elections hide choices until the voting window ends.
+    pub fn is_commit_reveal(&self) -> bool {
+        self.reveal_ends_at.is_some()
+    }
+
+    /// Commitments are revealed in `[ends_at, reveal_ends_at)`, after voting
+    /// closes and before the election is finalized.
+    pub fn check_reveal_window(&self, now: i64) -> Result<()> {
+        let reveal_ends_at = self
+            .reveal_ends_at
+            .ok_or(crate::errors::VoteError::NotCommitReveal)?;
+        require!(
+            matches!(self.status, ElectionStatus::Active | ElectionStatus::Closed),
+            crate::errors::VoteError::ElectionNotActive
+        );
+        require!(now >= self.ends_at, crate::errors::VoteError::VotingNotEnded);
+        require!(now < reveal_ends_at, crate::errors::VoteError::RevealEnded);
+        Ok(())
+    }
+
+    /// Count a newly registered voter towards the electorate.
+    pub fn record_registration(&mut self) -> Result<()> {
+        self.registered_voters = self
+            .registered_voters
+            .checked_add(1)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+
+    /// Count a newly cast ballot towards turnout.
+    pub fn record_ballot(&mut self) -> Result<()> {
+        self.ballots_cast = self
+            .ballots_cast
+            .checked_add(1)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+
+    /// Remove a revoked ballot from turnout.
+    pub fn retract_ballot(&mut self) -> Result<()> {
+        self.ballots_cast = self
+            .ballots_cast
+            .checked_sub(1)
+            .ok_or(crate::errors::VoteError::VoteUnderflow)?;
+        Ok(())
+    }
+
+    /// Instructions specific to one voting method reject the others.
+    pub fn check_method(&self, method: VotingMethod) -> Result<()> {
+        require!(self.method == method, crate::errors::VoteError::WrongVotingMethod);
+        Ok(())
+    }
+
+    /// D21, approval and score ballots share the `vote` instructions and are
+    /// tallied directly on candidate accounts; instant-runoff ballots are not.
+    pub fn check_tallied_method(&self) -> Result<()> {
+        require!(
+            self.method != VotingMethod::InstantRunoff,
+            crate::errors::VoteError::WrongVotingMethod
+        );
+        Ok(())
+    }
+
+    /// A score election must allow at least one point.
+    pub fn check_method_config(method: VotingMethod) -> Result<()> {
+        if let VotingMethod::Score { max_score } = method {
+            require!(max_score >= 1, crate::errors::VoteError::InvalidVoteConfig);
+        }
+        Ok(())
+    }
+
+    /// A quorum, if any, is a share of registered voters in basis points.
+    pub fn check_quorum_config(quorum_bps: Option<u16>) -> Result<()> {
+        if let Some(quorum_bps) = quorum_bps {
+            require!(
+                (1..=Self::MAX_QUORUM_BPS).contains(&quorum_bps),
+                crate::errors::VoteError::InvalidQuorum
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether enough registered voters cast a ballot. Elections without a
+    /// quorum always reach it.
+    pub fn quorum_reached(&self) -> bool {
+        match self.quorum_bps {
+            None => true,
+            Some(quorum_bps) => {
+                self.ballots_cast as u64 * Self::MAX_QUORUM_BPS as u64
+                    >= quorum_bps as u64 * self.registered_voters as u64
+            }
+        }
+    }
+
+    /// Finalized elections, valid or not, accept no further changes.
+    pub fn is_finalized(&self) -> bool {
+        matches!(self.status, ElectionStatus::Finalized | ElectionStatus::Invalid)
+    }
+
+    /// Tallies are final once voting, and any reveal window, has ended.
+    pub fn tally_ends_at(&self) -> i64 {
+        self.reveal_ends_at.unwrap_or(self.ends_at)
+    }
+
+    /// Candidates may withdraw or be disqualified until the election is
+    /// finalized. An instant-runoff count fixes its field when it starts, so
+    /// there the statuses freeze once tallies are final.
+    pub fn check_candidacy_changeable(&self, now: i64) -> Result<()> {
+        require!(!self.is_finalized(), crate::errors::VoteError::ElectionAlreadyFinalized);
+        if self.method == VotingMethod::InstantRunoff {
+            require!(now < self.tally_ends_at(), crate::errors::VoteError::VotingEnded);
+        }
+        Ok(())
+    }
+}
+
+/// Where a candidate stands in the nomination workflow
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CandidateStatus {
+    /// Self-nominated, awaiting the admin's decision
+    Nominated,
+    /// On the ballot; registered by the admin or a registrar, or an approved nomination
+    Approved,
+    /// Nomination turned down by the admin. The account is closed on
+    /// rejection, so only indexers record this status
+    Rejected,
+    /// Left the race at the owner's request
+    Withdrawn,
+    /// Removed from the race by the admin
+    Disqualified,
+}
+
+/// Candidate account storing candidate information and vote count.
+/// Versioned layouts carry their own discriminator, `sha256("account:CandidateAccountV1")[..8]`,
+/// so accounts from before `version` existed are rejected until migrated.
+#[account(discriminator = [168, 8, 109, 192, 130, 16, 87, 31])]
+#[derive(InitSpace)]
 pub struct CandidateAccount {
-    pub name: String,       // Max 32 chars = 4 + 32 = 36 bytes
-    pub votes: u64,         // 8 bytes
+    /// Layout version, `CandidateAccount::VERSION` once created or migrated
+    pub version: u8,
+    pub election: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(256)]
+    pub description: String,
+    /// Off-chain metadata
+    #[max_len(200)]
+    pub uri: String,
+    /// Wallet that controls the candidacy
+    pub owner: Pubkey,
+    pub status: CandidateStatus,
+    /// Positive tally
+    pub votes: u64,
+    /// Negative tally
+    pub negative_votes: u64,
+    /// Points from score ballots
+    pub score_sum: u64,
+    /// Refunded when the account is closed
+    pub payer: Pubkey,
+    pub bump: u8,
+}
+
+impl CandidateAccount {
+    pub const VERSION: u8 = 1;
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const MAX_REASON_LEN: usize = 200;
+    pub const SPACE: usize = 8 + Self::INIT_SPACE;
+
+    /// Validate a candidate name and return the form stored on the account:
+    /// surrounding whitespace trimmed and Unicode NFC-normalized. Rejects empty
+    /// names, names over `MAX_NAME_LEN` bytes (stored or case-folded), and
+    /// control, line-breaking or invisible formatting characters.
+    pub fn normalize_name(name: &str) -> Result<String> {
+        use crate::errors::VoteError;
+
+        let display: String = name.trim().nfc().collect();
+        require!(!display.is_empty(), VoteError::NameEmpty);
+        require!(
+            display.chars().all(Self::is_valid_name_char),
+            VoteError::InvalidNameCharacters
+        );
+        require!(
+            display.len() <= Self::MAX_NAME_LEN && Self::name_key(&display).len() <= Self::MAX_NAME_LEN,
+            VoteError::NameTooLong
+        );
+        Ok(display)
+    }
+
+    fn is_valid_name_char(c: char) -> bool {
+        let invisible = matches!(
+            c,
+            '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+        );
+        !c.is_control() && !invisible && (c == ' ' || !c.is_whitespace())
+    }
+
+    /// Canonical form of a name: trimmed, NFC-normalized and case-folded, so
+    /// "Alice", "alice " and a decomposed "Alicé" variant all compare equal.
+    pub fn name_key(name: &str) -> String {
+        let nfc: String = name.trim().nfc().collect();
+        default_case_fold_str(&nfc).nfc().collect()
+    }
+
+    /// PDA seed for a candidate name: sha256 of its canonical form. Hashing
+    /// keeps the seed within the 32-byte limit for any name.
+    pub fn name_seed(name: &str) -> [u8; 32] {
+        hash(Self::name_key(name).as_bytes()).to_bytes()
+    }
+
+    /// Bound the free-form metadata so it fits the account.
+    pub fn check_metadata(description: &str, uri: &str) -> Result<()> {
+        require!(
+            description.len() <= Self::MAX_DESCRIPTION_LEN,
+            crate::errors::VoteError::DescriptionTooLong
+        );
+        require!(uri.len() <= Self::MAX_URI_LEN, crate::errors::VoteError::UriTooLong);
+        Ok(())
+    }
+
+    /// Only approved candidates may receive new votes.
+    pub fn check_votable(&self) -> Result<()> {
+        match self.status {
+            CandidateStatus::Approved => Ok(()),
+            CandidateStatus::Withdrawn | CandidateStatus::Disqualified => {
+                err!(crate::errors::VoteError::CandidateInactive)
+            }
+            CandidateStatus::Nominated | CandidateStatus::Rejected => {
+                err!(crate::errors::VoteError::CandidateNotApproved)
+            }
+        }
+    }
+
+    /// Withdrawal and disqualification take a nominated or approved candidate
+    /// out of the race, with a short reason recorded in the emitted event.
+    pub fn check_removable(&self, reason: &str) -> Result<()> {
+        require!(
+            matches!(self.status, CandidateStatus::Nominated | CandidateStatus::Approved),
+            crate::errors::VoteError::InvalidCandidateStatus
+        );
+        require!(
+            reason.len() <= Self::MAX_REASON_LEN,
+            crate::errors::VoteError::ReasonTooLong
+        );
+        Ok(())
+    }
+
+    /// Net D21 score: positive minus negative votes.
+    pub fn net_votes(&self) -> i128 {
+        self.votes as i128 - self.negative_votes as i128
+    }
+
+    /// Add `weight` to the positive or negative tally.
+    pub fn record_vote(&mut self, weight: u64, negative: bool) -> Result<()> {
+        let tally = if negative { &mut self.negative_votes } else { &mut self.votes };
+        *tally = tally
+            .checked_add(weight)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+
+    /// Remove `weight` previously added by `record_vote`.
+    pub fn retract_vote(&mut self, weight: u64, negative: bool) -> Result<()> {
+        let tally = if negative { &mut self.negative_votes } else { &mut self.votes };
+        *tally = tally
+            .checked_sub(weight)
+            .ok_or(crate::errors::VoteError::VoteUnderflow)?;
+        Ok(())
+    }
+
+    /// Add `score` points, counted `weight` times, to the score sum.
+    pub fn record_score(&mut self, weight: u64, score: u8) -> Result<()> {
+        self.score_sum = (score as u64)
+            .checked_mul(weight)
+            .and_then(|points| self.score_sum.checked_add(points))
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+
+    /// Remove points previously added by `record_score`.
+    pub fn retract_score(&mut self, weight: u64, score: u8) -> Result<()> {
+        self.score_sum = (score as u64)
+            .checked_mul(weight)
+            .and_then(|points| self.score_sum.checked_sub(points))
+            .ok_or(crate::errors::VoteError::VoteUnderflow)?;
+        Ok(())
+    }
+}
+
+/// Admin-created proof that a wallet may register as a voter
+#[account]
+pub struct WhitelistEntry {
+    pub election: Pubkey,   // 32 bytes
+    pub voter: Pubkey,      // 32 bytes
     pub bump: u8,           // 1 byte
 }
 
-impl CandidateAccount {
-    pub const MAX_NAME_LEN: usize = 32;
-    // 8 (discriminator) + 36 (name) + 8 (votes) + 1 (bump) = 53 bytes
-    pub const SPACE: usize = 8 + 36 + 8 + 1;
-}
+impl WhitelistEntry {
+    // 8 (discriminator) + 32 (election) + 32 (voter) + 1 (bump) = 73 bytes
+    pub const SPACE: usize = 8 + 32 + 32 + 1;
+}
+
+/// Voter account tracking voter's authority and voting status within an election.
+/// Versioned layouts carry their own discriminator, `sha256("account:VoterAccountV2")[..8]`,
+/// so accounts in an older layout are rejected until migrated.
+#[account(discriminator = [69, 193, 15, 1, 137, 106, 205, 90])]
+#[derive(InitSpace)]
+pub struct VoterAccount {
+    /// Layout version, `VoterAccount::VERSION` once created or migrated
+    pub version: u8,
+    pub election: Pubkey,
+    pub authority: Pubkey,
+    pub voted: bool,
+    #[max_len(16)]
+    pub votes: Vec<Pubkey>,
+    #[max_len(8)]
+    pub negative_votes: Vec<Pubkey>,
+    /// One score per positive vote
+    #[max_len(16)]
+    pub scores: Vec<u8>,
+    pub weight: u64,
+    pub locked_amount: u64,
+    /// Unrevealed commit-reveal ballot
+    pub commitment: Option<[u8; 32]>,
+    /// Voter account this ballot is delegated to
+    pub delegate: Option<Pubkey>,
+    /// Weight delegated to this voter, directly or transitively
+    pub delegated_weight: u64,
+    /// Nonce the next off-chain signed ballot must carry
+    pub nonce: u64,
+    /// Refunded when the account is closed
+    pub payer: Pubkey,
+    pub bump: u8,
+}
+
+impl VoterAccount {
+    pub const VERSION: u8 = 2;
+    pub const SPACE: usize = 8 + Self::INIT_SPACE;
+
+    /// Longest chain of voter accounts a delegation may pass through.
+    pub const MAX_DELEGATION_DEPTH: usize = 8;
+
+    /// Weight of a ballot cast by this voter: their own weight plus everything
+    /// delegated to them by voters who did not vote themselves.
+    pub fn vote_weight(&self) -> Result<u64> {
+        self.weight
+            .checked_add(self.delegated_weight)
+            .ok_or_else(|| crate::errors::VoteError::VoteOverflow.into())
+    }
+
+    /// Voters who delegated their ballot cannot vote until they undelegate.
+    pub fn check_not_delegated(&self) -> Result<()> {
+        require!(self.delegate.is_none(), crate::errors::VoteError::VoterDelegated);
+        Ok(())
+    }
+
+    /// Check a delegation chain given as `(voter account, its delegate)` links.
+    /// It must start at `first`, follow each delegate in turn, end at a voter
+    /// that has not delegated, stay within `MAX_DELEGATION_DEPTH` and never
+    /// pass through `origin`, the voter whose weight travels along it.
+    pub fn check_delegation_chain(
+        origin: &Pubkey,
+        first: &Pubkey,
+        chain: &[(Pubkey, Option<Pubkey>)],
+    ) -> Result<()> {
+        use crate::errors::VoteError;
+
+        require!(!chain.is_empty(), VoteError::InvalidDelegationChain);
+        require!(
+            chain.len() <= Self::MAX_DELEGATION_DEPTH,
+            VoteError::DelegationTooDeep
+        );
+
+        let mut expected = *first;
+        for (i, (key, delegate)) in chain.iter().enumerate() {
+            require_keys_eq!(*key, expected, VoteError::InvalidDelegationChain);
+            require_keys_neq!(*key, *origin, VoteError::DelegationCycle);
+            match delegate {
+                Some(next) => expected = *next,
+                // The terminal voter must be the last account passed
+                None if i + 1 == chain.len() => return Ok(()),
+                None => return err!(VoteError::InvalidDelegationChain),
+            }
+        }
+
+        // The last link still points onwards
+        require!(
+            chain.len() < Self::MAX_DELEGATION_DEPTH,
+            VoteError::DelegationTooDeep
+        );
+        require_keys_neq!(expected, *origin, VoteError::DelegationCycle);
+        err!(VoteError::InvalidDelegationChain)
+    }
+
+    /// The ballot currently stored on the account.
+    pub fn ballot(&self) -> Ballot {
+        Ballot {
+            positive: self.votes.clone(),
+            negative: self.negative_votes.clone(),
+            scores: self.scores.clone(),
+        }
+    }
+
+    /// Store `ballot` and mark the voter as having voted.
+    pub fn set_ballot(&mut self, ballot: &Ballot) {
+        self.voted = true;
+        self.votes = ballot.positive.clone();
+        self.negative_votes = ballot.negative.clone();
+        self.scores = ballot.scores.clone();
+    }
+
+    /// Return the voter to the unvoted state.
+    pub fn clear_ballot(&mut self) {
+        self.voted = false;
+        self.votes.clear();
+        self.negative_votes.clear();
+        self.scores.clear();
+    }
+}
+
+/// A ballot for the methods tallied on candidate accounts: candidates
+/// receiving a positive vote (D21), approval (approval) or rating (score),
+/// candidates receiving a negative vote (D21 only), and the points given to
+/// each positive candidate (score only). Candidate accounts are passed as
+/// remaining accounts in the same order, positive first.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Ballot {
+    pub positive: Vec<Pubkey>,
+    pub negative: Vec<Pubkey>,
+    pub scores: Vec<u8>,
+}
+
+impl Ballot {
+    /// All candidate keys on the ballot, positive first.
+    pub fn candidates(&self) -> impl Iterator<Item = &Pubkey> {
+        self.positive.iter().chain(self.negative.iter())
+    }
+
+    /// Commit-reveal commitment: sha256(borsh(ballot) || salt).
+    pub fn commitment(&self, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&self.to_bytes(), salt]).to_bytes()
+    }
+
+    /// Borsh encoding of the ballot, written out so it cannot fail.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            12 + 32 * (self.positive.len() + self.negative.len()) + self.scores.len(),
+        );
+        for side in [&self.positive, &self.negative] {
+            data.extend_from_slice(&(side.len() as u32).to_le_bytes());
+            for key in side.iter() {
+                data.extend_from_slice(key.as_ref());
+            }
+        }
+        data.extend_from_slice(&(self.scores.len() as u32).to_le_bytes());
+        data.extend_from_slice(&self.scores);
+        data
+    }
+
+    /// Candidates touched when replacing `self` with `next`: this ballot's
+    /// candidates followed by those only on `next`. This is the order in
+    /// which `change_vote` expects its remaining accounts.
+    pub fn union(&self, next: &Ballot) -> Vec<Pubkey> {
+        let mut keys: Vec<Pubkey> = self.candidates().copied().collect();
+        for key in next.candidates() {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+        keys
+    }
+
+    /// Check the ballot against the election's voting method: D21 vote
+    /// limits, any number of approvals, or one score of at most `max_score`
+    /// per rated candidate.
+    pub fn validate(&self, election: &Election) -> Result<()> {
+        use crate::errors::VoteError;
+
+        require!(!self.positive.is_empty(), VoteError::EmptyBallot);
+        match election.method {
+            VotingMethod::D21 => {
+                require!(
+                    self.positive.len() <= election.positive_votes as usize,
+                    VoteError::TooManyVotes
+                );
+                require!(
+                    self.negative.len() <= election.negative_votes as usize,
+                    VoteError::TooManyNegativeVotes
+                );
+                // A negative vote is only allowed once at least two positive votes are cast
+                require!(
+                    self.negative.is_empty() || self.positive.len() >= 2,
+                    VoteError::NegativeVoteNotAllowed
+                );
+                require!(self.scores.is_empty(), VoteError::InvalidScores);
+            }
+            VotingMethod::Approval => {
+                require!(
+                    self.positive.len() <= Election::MAX_CANDIDATES,
+                    VoteError::TooManyVotes
+                );
+                require!(self.negative.is_empty(), VoteError::NegativeVoteNotAllowed);
+                require!(self.scores.is_empty(), VoteError::InvalidScores);
+            }
+            VotingMethod::Score { max_score } => {
+                require!(
+                    self.positive.len() <= Election::MAX_CANDIDATES,
+                    VoteError::TooManyVotes
+                );
+                require!(self.negative.is_empty(), VoteError::NegativeVoteNotAllowed);
+                require_eq!(self.scores.len(), self.positive.len(), VoteError::InvalidScores);
+                require!(
+                    self.scores.iter().all(|score| *score <= max_score),
+                    VoteError::ScoreTooHigh
+                );
+            }
+            VotingMethod::InstantRunoff => return err!(VoteError::WrongVotingMethod),
+        }
+
+        let keys: Vec<&Pubkey> = self.candidates().collect();
+        for (i, key) in keys.iter().enumerate() {
+            require!(
+                !keys[i + 1..].contains(key),
+                VoteError::DuplicateCandidates
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A ballot signed off-chain by a voter's authority, for a relayer to submit
+/// in a batch with `vote_signed_batch`. The authority signs `message` with
+/// ed25519; `nonce` must match the voter account's, so each signature is
+/// accepted once.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SignedBallot {
+    pub authority: Pubkey,
+    pub nonce: u64,
+    pub ballot: Ballot,
+}
+
+impl SignedBallot {
+    /// Prefix keeping ballot signatures from verifying any other message
+    pub const DOMAIN: &'static [u8] = b"vote_d_21 signed ballot v1";
+    /// Ballots per batch; a transaction cannot carry many more signatures
+    pub const MAX_BATCH: usize = 8;
+
+    /// Bytes the authority signs:
+    /// DOMAIN || election || authority || nonce (LE) || borsh(ballot).
+    pub fn message(&self, election: &Pubkey) -> Vec<u8> {
+        [
+            Self::DOMAIN,
+            election.as_ref(),
+            self.authority.as_ref(),
+            &self.nonce.to_le_bytes(),
+            &self.ballot.to_bytes(),
+        ]
+        .concat()
+    }
+}
+
+/// One candidate's final standing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResultEntry {
+    pub candidate: Pubkey,   // 32 bytes
+    pub votes: u64,          // 8 bytes
+    pub negative_votes: u64, // 8 bytes
+    pub score_sum: u64,      // 8 bytes
+    pub average_score: u64,  // 8 bytes, thousandths of a point per unit of weight that rated the candidate
+    pub net_votes: i128,     // 16 bytes
+}
+
+impl ResultEntry {
+    pub const SPACE: usize = 32 + 8 + 8 + 8 + 8 + 16;
+    /// `average_score` is fixed-point with this many units per point.
+    pub const SCORE_SCALE: u64 = 1000;
+
+    /// Standing of `candidate`. `votes` counts the weight that rated it, so
+    /// the average score is the score sum over the positive tally.
+    pub fn new(key: Pubkey, candidate: &CandidateAccount) -> Self {
+        let average_score = (candidate.score_sum as u128 * Self::SCORE_SCALE as u128)
+            .checked_div(candidate.votes as u128)
+            .unwrap_or(0) as u64;
+        Self {
+            candidate: key,
+            votes: candidate.votes,
+            negative_votes: candidate.negative_votes,
+            score_sum: candidate.score_sum,
+            average_score,
+            net_votes: candidate.net_votes(),
+        }
+    }
+}
+
+/// Final, ranked outcome of an election written by `finalize_election`
+#[account]
+pub struct Results {
+    pub election: Pubkey,          // 32 bytes
+    pub entries: Vec<ResultEntry>, // Max 16 entries = 4 + 16 * 80 = 1284 bytes
+    pub winners: Vec<Pubkey>,      // Max 16 keys = 4 + 16 * 32 = 516 bytes
+    pub is_tie: bool,              // 1 byte
+    pub finalized_at: i64,         // 8 bytes
+    pub bump: u8,                  // 1 byte
+}
+
+impl Results {
+    // 8 (discriminator) + 32 (election) + 1284 (entries) + 516 (winners) + 1 (is_tie)
+    // + 8 (finalized_at) + 1 (bump) = 1850 bytes
+    pub const SPACE: usize = 8 + 32
+        + (4 + Election::MAX_CANDIDATES * ResultEntry::SPACE)
+        + (4 + Election::MAX_CANDIDATES * 32)
+        + 1 + 8 + 1;
+
+    /// Rank candidates, highest first: by net votes under D21 and approval
+    /// voting, by score sum under score voting. Candidates with equal
+    /// standing keep their registration order. Withdrawn and disqualified
+    /// candidates are left out. Every candidate sharing the top score is a
+    /// winner; more than one winner is a tie.
+    pub fn rank(
+        method: VotingMethod,
+        candidates: &[(Pubkey, &CandidateAccount)],
+    ) -> (Vec<ResultEntry>, Vec<Pubkey>, bool) {
+        let standing = |entry: &ResultEntry| match method {
+            VotingMethod::Score { .. } => entry.score_sum as i128,
+            _ => entry.net_votes,
+        };
+        let mut entries: Vec<ResultEntry> = candidates
+            .iter()
+            .filter(|(_, candidate)| candidate.status == CandidateStatus::Approved)
+            .map(|(key, candidate)| ResultEntry::new(*key, candidate))
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(standing(entry)));
+
+        let winners: Vec<Pubkey> = match entries.first() {
+            Some(top) => entries
+                .iter()
+                .take_while(|entry| standing(entry) == standing(top))
+                .map(|entry| entry.candidate)
+                .collect(),
+            None => Vec::new(),
+        };
+        let is_tie = winners.len() > 1;
+        (entries, winners, is_tie)
+    }
+}
+
+/// An instant-runoff ballot: candidates in order of preference
+/// Seeds: ["ballot", voter.key().as_ref()]
+#[account]
+pub struct RankedBallot {
+    pub election: Pubkey,        // 32 bytes
+    pub voter: Pubkey,           // 32 bytes, the voter account that cast it
+    pub rankings: Vec<Pubkey>,   // Max 16 keys = 4 + 16 * 32 = 516 bytes
+    pub weight: u64,             // 8 bytes
+    pub counted_round: u8,       // 1 byte, last runoff round this ballot was counted in
+    pub bump: u8,                // 1 byte
+}
+
+impl RankedBallot {
+    // 8 (discriminator) + 32 (election) + 32 (voter) + 516 (rankings) + 8 (weight) + 1 (counted_round)
+    // + 1 (bump) = 598 bytes
+    pub const SPACE: usize = 8 + 32 + 32 + (4 + Election::MAX_CANDIDATES * 32) + 8 + 1 + 1;
+
+    /// Rankings must be non-empty, distinct and drawn from the ballot.
+    pub fn validate(rankings: &[Pubkey], election: &Election) -> Result<()> {
+        use crate::errors::VoteError;
+
+        require!(!rankings.is_empty(), VoteError::InvalidRanking);
+        for (i, key) in rankings.iter().enumerate() {
+            require!(election.candidates.contains(key), VoteError::InvalidRanking);
+            require!(!rankings[i + 1..].contains(key), VoteError::InvalidRanking);
+        }
+        Ok(())
+    }
+}
+
+/// Instant-runoff count in progress for an election, advanced by the
+/// `tabulate_round` crank. Each round counts every ranked ballot for its
+/// highest-ranked continuing candidate, then either records a majority winner
+/// or eliminates the weakest candidate.
+/// Seeds: ["runoff", election.key().as_ref()]
+#[account]
+pub struct RunoffTally {
+    pub election: Pubkey,         // 32 bytes
+    pub round: u8,                // 1 byte, starts at 1
+    pub ballots_counted: u32,     // 4 bytes, in the current round
+    pub counts: Vec<u64>,         // Max 16 = 4 + 16 * 8 = 132 bytes, parallel to election.candidates
+    pub eliminated: Vec<Pubkey>,  // Max 16 keys = 4 + 16 * 32 = 516 bytes, in elimination order
+    pub exhausted: u64,           // 8 bytes, weight with no continuing candidate this round
+    pub winner: Option<Pubkey>,   // 1 + 32 = 33 bytes
+    pub complete: bool,           // 1 byte
+    pub bump: u8,                 // 1 byte
+}
+
+/// What concluding a runoff round decided
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoundOutcome {
+    /// A continuing candidate holds a majority, or is the last one left
+    Winner(Pubkey),
+    /// No majority; this candidate is out and another round follows
+    Eliminated(Pubkey),
+    /// No continuing candidate received any weight
+    NoWinner,
+}
+
+impl RunoffTally {
+    // 8 (discriminator) + 32 (election) + 1 (round) + 4 (ballots_counted) + 132 (counts) + 516 (eliminated)
+    // + 8 (exhausted) + 33 (winner) + 1 (complete) + 1 (bump) = 736 bytes
+    pub const SPACE: usize = 8 + 32 + 1 + 4
+        + (4 + Election::MAX_CANDIDATES * 8)
+        + (4 + Election::MAX_CANDIDATES * 32)
+        + 8 + (1 + 32) + 1 + 1;
+
+    /// Index into `candidates` of the ballot's highest-ranked candidate that
+    /// is still in the race, or `None` if the ballot is exhausted.
+    pub fn top_choice(&self, candidates: &[Pubkey], rankings: &[Pubkey]) -> Option<usize> {
+        rankings
+            .iter()
+            .filter(|key| !self.eliminated.contains(key))
+            .find_map(|key| candidates.iter().position(|candidate| candidate == key))
+    }
+
+    /// Count one ballot towards the current round.
+    pub fn count(&mut self, candidates: &[Pubkey], rankings: &[Pubkey], weight: u64) -> Result<()> {
+        let tally = match self.top_choice(candidates, rankings) {
+            Some(index) => &mut self.counts[index],
+            None => &mut self.exhausted,
+        };
+        *tally = tally
+            .checked_add(weight)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        self.ballots_counted = self
+            .ballots_counted
+            .checked_add(1)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+
+    /// Decide the current round once every ballot has been counted.
+    /// A continuing candidate wins with more than half of the non-exhausted
+    /// weight, or by being the only one left. Otherwise the candidate with the
+    /// fewest votes is eliminated (ties go against the later-registered one)
+    /// and the counts of continuing candidates are reset for the next round.
+    /// Eliminated candidates keep the count of the round they went out in.
+    pub fn conclude_round(&mut self, candidates: &[Pubkey]) -> RoundOutcome {
+        let continuing: Vec<usize> = (0..candidates.len())
+            .filter(|&i| !self.eliminated.contains(&candidates[i]))
+            .collect();
+        let total: u128 = continuing.iter().map(|&i| self.counts[i] as u128).sum();
+
+        let outcome = if total == 0 {
+            RoundOutcome::NoWinner
+        } else {
+            let leader = continuing
+                .iter()
+                .copied()
+                .max_by(|&a, &b| self.counts[a].cmp(&self.counts[b]).then(b.cmp(&a)))
+                .unwrap_or_default();
+            if continuing.len() == 1 || self.counts[leader] as u128 * 2 > total {
+                RoundOutcome::Winner(candidates[leader])
+            } else {
+                let weakest = continuing
+                    .iter()
+                    .copied()
+                    .min_by(|&a, &b| self.counts[a].cmp(&self.counts[b]).then(b.cmp(&a)))
+                    .unwrap_or_default();
+                RoundOutcome::Eliminated(candidates[weakest])
+            }
+        };
+
+        match outcome {
+            RoundOutcome::Winner(winner) => {
+                self.winner = Some(winner);
+                self.complete = true;
+            }
+            RoundOutcome::NoWinner => self.complete = true,
+            RoundOutcome::Eliminated(candidate) => {
+                self.eliminated.push(candidate);
+                for (i, key) in candidates.iter().enumerate() {
+                    if !self.eliminated.contains(key) {
+                        self.counts[i] = 0;
+                    }
+                }
+                self.exhausted = 0;
+                self.ballots_counted = 0;
+                self.round = self.round.saturating_add(1);
+            }
+        }
+        outcome
+    }
+
+    /// Final standings for `Results`: the winner, then the other continuing
+    /// candidates by final count, then the eliminated ones, last out first.
+    pub fn standings(&self, candidates: &[Pubkey]) -> Vec<ResultEntry> {
+        let entry = |key: &Pubkey| {
+            let votes = candidates
+                .iter()
+                .position(|candidate| candidate == key)
+                .map(|i| self.counts[i])
+                .unwrap_or_default();
+            ResultEntry {
+                candidate: *key,
+                votes,
+                negative_votes: 0,
+                score_sum: 0,
+                average_score: 0,
+                net_votes: votes as i128,
+            }
+        };
+
+        let mut continuing: Vec<ResultEntry> = candidates
+            .iter()
+            .filter(|key| !self.eliminated.contains(key))
+            .map(entry)
+            .collect();
+        continuing.sort_by_key(|e| (Some(e.candidate) != self.winner, std::cmp::Reverse(e.votes)));
+        continuing.extend(self.eliminated.iter().rev().map(entry));
+        continuing
+    }
+}
+
+/// `CandidateAccount` as first deployed, when every candidate stood in a
+/// single program-wide poll. Seeds: ["candidate", name]
+/// Only read by `migrate_candidate`, which moves it into an election.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CandidateAccountV0 {
+    pub name: String,
+    pub votes: u64,
+    pub bump: u8,
+}
+
+impl CandidateAccountV0 {
+    /// `sha256("account:CandidateAccount")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [69, 203, 73, 43, 203, 170, 96, 121];
+    /// Size deployed candidate accounts were allocated with
+    pub const SPACE: usize = 8 + 36 + 8 + 1;
+
+    /// Address of the deployed candidate named `name`
+    pub fn address(name: &str) -> Pubkey {
+        Pubkey::find_program_address(&[b"candidate", name.as_bytes()], &crate::ID).0
+    }
+
+    /// The same candidate and tally in `election`, approved and owned by
+    /// `admin`, who pays for the new account
+    pub fn migrate(self, election: Pubkey, admin: Pubkey, bump: u8) -> CandidateAccount {
+        CandidateAccount {
+            version: CandidateAccount::VERSION,
+            election,
+            name: self.name,
+            description: String::new(),
+            uri: String::new(),
+            owner: admin,
+            status: CandidateStatus::Approved,
+            votes: self.votes,
+            negative_votes: 0,
+            score_sum: 0,
+            payer: admin,
+            bump,
+        }
+    }
+}
+
+/// `VoterAccount` as first deployed, holding one ballot of two positive votes
+/// in the program-wide poll. Seeds: ["voter", authority]
+/// Only read by `migrate_voter`, which moves it into an election.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct VoterAccountV0 {
+    pub authority: Pubkey,
+    pub voted: bool,
+    pub votes: [Pubkey; 2],
+    pub bump: u8,
+}
+
+impl VoterAccountV0 {
+    /// `sha256("account:VoterAccount")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [24, 202, 161, 124, 196, 184, 105, 236];
+    /// Size deployed voter accounts were allocated with
+    pub const SPACE: usize = 8 + 32 + 1 + 64 + 1;
+
+    /// The same voter in `election`, its ballot pointing at `votes`, the
+    /// migrated accounts of the candidates it voted for. `payer` pays for
+    /// the new account.
+    pub fn migrate(self, election: Pubkey, votes: Vec<Pubkey>, payer: Pubkey, bump: u8) -> VoterAccount {
+        VoterAccount {
+            version: VoterAccount::VERSION,
+            election,
+            authority: self.authority,
+            voted: self.voted,
+            votes,
+            negative_votes: Vec::new(),
+            scores: Vec::new(),
+            weight: 1,
+            locked_amount: 0,
+            commitment: None,
+            delegate: None,
+            delegated_weight: 0,
+            nonce: 0,
+            payer,
+            bump,
+        }
+    }
+}
+
+/// Program-wide settings, one per deployment.
+/// Seeds: ["config"]
+#[account]
+pub struct Config {
+    pub emergency_authority: Pubkey, // 32 bytes
+    pub paused: bool,                // 1 byte
+    pub bump: u8,                    // 1 byte
+}
+
+impl Config {
+    // 8 (discriminator) + 32 (emergency_authority) + 1 (paused) + 1 (bump) = 42 bytes
+    pub const SPACE: usize = 8 + 32 + 1 + 1;
+
+    /// Registration and voting are halted while the program is paused.
+    pub fn check_not_paused(&self) -> Result<()> {
+        require!(!self.paused, crate::errors::VoteError::ProgramPaused);
+        Ok(())
+    }
+}
+
+/// Lamports set aside for an election so voters without SOL can take part:
+/// a relayer pays the transaction fees and rent of `initialize_voter` and
+/// `vote`, and is reimbursed from this account.
+/// Seeds: ["sponsor_budget", election]
+#[account]
+pub struct SponsorBudget {
+    pub election: Pubkey,            // 32 bytes
+    pub sponsor: Pubkey,             // 32 bytes - may withdraw; refunded sponsored voters' rent
+    pub relayer: Pubkey,             // 32 bytes - the only wallet reimbursed
+    pub fee_per_transaction: u64,    // 8 bytes - reimbursed on top of rent
+    pub spent: u64,                  // 8 bytes
+    pub sponsored_transactions: u64, // 8 bytes
+    pub bump: u8,                    // 1 byte
+}
+
+impl SponsorBudget {
+    // 8 (discriminator) + 32 (election) + 32 (sponsor) + 32 (relayer) + 8 (fee_per_transaction)
+    // + 8 (spent) + 8 (sponsored_transactions) + 1 (bump) = 129 bytes
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+    /// Cap on the per-transaction reimbursement, so a relayer cannot drain
+    /// the budget much faster than it actually spends on fees
+    pub const MAX_FEE_PER_TRANSACTION: u64 = 100_000;
+
+    pub fn check_fee(fee_per_transaction: u64) -> Result<()> {
+        require!(
+            fee_per_transaction <= Self::MAX_FEE_PER_TRANSACTION,
+            crate::errors::VoteError::InvalidSponsorFee
+        );
+        Ok(())
+    }
+
+    /// Account for reimbursing `amount` out of `available` lamports.
+    pub fn record_spend(&mut self, amount: u64, available: u64) -> Result<()> {
+        require!(amount <= available, crate::errors::VoteError::SponsorBudgetExhausted);
+        self.spent = self
+            .spent
+            .checked_add(amount)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        self.sponsored_transactions = self
+            .sponsored_transactions
+            .checked_add(1)
+            .ok_or(crate::errors::VoteError::VoteOverflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Election {
+    /// D21 election voting in `[starts_at, ends_at)` with open eligibility and
+    /// equal weighting, for unit tests; override fields with struct update syntax.
+    pub(crate) fn with_window(status: ElectionStatus, starts_at: i64, ends_at: i64) -> Self {
+        Election {
+            creator: Pubkey::new_unique(),
+            election_id: 1,
+            title: String::from("Poll"),
+            candidates: Vec::new(),
+            status,
+            starts_at,
+            ends_at,
+            positive_votes: 3,
+            negative_votes: 1,
+            admin: Pubkey::new_unique(),
+            pending_admin: None,
+            registrars: Vec::new(),
+            eligibility: EligibilityMode::Open,
+            weighting: VoteWeighting::Equal,
+            reveal_ends_at: None,
+            method: VotingMethod::D21,
+            registered_voters: 0,
+            ballots_cast: 0,
+            quorum_bps: None,
+            bump: 255,
+        }
+    }
+}
+
+#[cfg(test)]
+impl CandidateAccount {
+    /// Approved candidate holding the given tallies, for unit tests.
+    pub(crate) fn tallied(votes: u64, negative_votes: u64) -> Self {
+        CandidateAccount {
+            version: CandidateAccount::VERSION,
+            election: Pubkey::default(),
+            name: String::from("Candidate"),
+            description: String::new(),
+            uri: String::new(),
+            owner: Pubkey::default(),
+            status: CandidateStatus::Approved,
+            votes,
+            negative_votes,
+            score_sum: 0,
+            payer: Pubkey::default(),
+            bump: 255,
+        }
+    }
+}
+
+#[cfg(test)]
+impl VoterAccount {
+    /// Registered voter of `weight` who has not voted yet, for unit tests.
+    pub(crate) fn registered(weight: u64) -> Self {
+        VoterAccount {
+            version: VoterAccount::VERSION,
+            election: Pubkey::default(),
+            authority: Pubkey::new_unique(),
+            voted: false,
+            votes: Vec::new(),
+            negative_votes: Vec::new(),
+            scores: Vec::new(),
+            weight,
+            locked_amount: 0,
+            commitment: None,
+            delegate: None,
+            delegated_weight: 0,
+            nonce: 0,
+            payer: Pubkey::default(),
+            bump: 255,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_election_account_space() {
+        // discriminator + creator + election_id + title + candidates + status + window + vote config
+        // + admin + pending_admin + registrars + eligibility + weighting + reveal_ends_at + method
+        // + registered_voters + ballots_cast + quorum_bps + bump
+        let expected_space = 8 + 32 + 8 + 68 + 516 + 1 + 16 + 2 + 32 + 33 + 132 + 33 + 41 + 9 + 2 + 4 + 4 + 3 + 1;
+        assert_eq!(Election::SPACE, expected_space);
+        assert_eq!(Election::SPACE, 945);
+    }
+
+    #[test]
+    fn test_election_space_fits_max_payload() {
+        let election = Election {
+            creator: Pubkey::new_unique(),
+            election_id: u64::MAX,
+            title: "T".repeat(Election::MAX_TITLE_LEN),
+            candidates: (0..Election::MAX_CANDIDATES).map(|_| Pubkey::new_unique()).collect(),
+            status: ElectionStatus::Active,
+            starts_at: i64::MIN,
+            ends_at: i64::MAX,
+            positive_votes: u8::MAX,
+            negative_votes: u8::MAX,
+            admin: Pubkey::new_unique(),
+            pending_admin: Some(Pubkey::new_unique()),
+            registrars: (0..Election::MAX_REGISTRARS).map(|_| Pubkey::new_unique()).collect(),
+            eligibility: EligibilityMode::MerkleRoot { root: [0xff; 32] },
+            weighting: VoteWeighting::TokenGated { mint: Pubkey::new_unique(), min_balance: u64::MAX },
+            reveal_ends_at: Some(i64::MAX),
+            method: VotingMethod::Score { max_score: u8::MAX },
+            registered_voters: u32::MAX,
+            ballots_cast: u32::MAX,
+            quorum_bps: Some(u16::MAX),
+            bump: 255,
+        };
+        let serialized = election.try_to_vec().unwrap();
+        assert_eq!(8 + serialized.len(), Election::SPACE);
+    }
+
+    #[test]
+    fn test_registrar_permissions() {
+        let mut election = Election::with_window(ElectionStatus::Pending, 100, 200);
+        let registrar = Pubkey::new_unique();
+
+        assert!(election.is_registrar(&election.admin));
+        assert!(!election.is_registrar(&registrar));
+
+        election.registrars.push(registrar);
+        assert!(election.is_registrar(&registrar));
+        assert!(!election.is_registrar(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_voting_window_bounds() {
+        let election = Election::with_window(ElectionStatus::Active, 100, 200);
+
+        assert_eq!(
+            election.check_voting_window(99).unwrap_err(),
+            crate::errors::VoteError::VotingNotStarted.into()
+        );
+        assert!(election.check_voting_window(100).is_ok());
+        assert!(election.check_voting_window(199).is_ok());
+        // ends_at is exclusive
+        assert_eq!(
+            election.check_voting_window(200).unwrap_err(),
+            crate::errors::VoteError::VotingEnded.into()
+        );
+    }
+
+    #[test]
+    fn test_voting_window_requires_active_status() {
+        let pending = Election::with_window(ElectionStatus::Pending, 100, 200);
+        assert_eq!(
+            pending.check_voting_window(150).unwrap_err(),
+            crate::errors::VoteError::ElectionNotActive.into()
+        );
+
+        let closed = Election::with_window(ElectionStatus::Closed, 100, 200);
+        assert_eq!(
+            closed.check_voting_window(150).unwrap_err(),
+            crate::errors::VoteError::ElectionNotActive.into()
+        );
+    }
+
+    #[test]
+    fn test_registration_open_until_end() {
+        let election = Election::with_window(ElectionStatus::Pending, 100, 200);
+        assert!(election.check_registration_open(0).is_ok());
+        assert!(election.check_registration_open(199).is_ok());
+        assert_eq!(
+            election.check_registration_open(200).unwrap_err(),
+            crate::errors::VoteError::VotingEnded.into()
+        );
+    }
+
+    #[test]
+    fn test_configurable_only_while_pending() {
+        let pending = Election::with_window(ElectionStatus::Pending, 100, 200);
+        assert!(pending.check_configurable().is_ok());
+
+        let active = Election::with_window(ElectionStatus::Active, 100, 200);
+        assert_eq!(
+            active.check_configurable().unwrap_err(),
+            crate::errors::VoteError::ConfigurationLocked.into()
+        );
+    }
+
+    #[test]
+    fn test_electorate_fixed_by_first_registration() {
+        let mut election = Election::with_window(ElectionStatus::Pending, 100, 200);
+        assert!(election.check_electorate_configurable().is_ok());
+
+        election.record_registration().unwrap();
+        assert!(election.check_configurable().is_ok());
+        assert_eq!(
+            election.check_electorate_configurable().unwrap_err(),
+            crate::errors::VoteError::VotersAlreadyRegistered.into()
+        );
+    }
+
+    #[test]
+    fn test_merkle_proof_verification() {
+        let voters: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = voters.iter().map(EligibilityMode::merkle_leaf).collect();
+        let left = EligibilityMode::merkle_node(&leaves[0], &leaves[1]);
+        let right = EligibilityMode::merkle_node(&leaves[2], &leaves[3]);
+        let root = EligibilityMode::merkle_node(&left, &right);
+
+        // Proof for voter 2: sibling leaf 3, then the left subtree
+        assert!(EligibilityMode::verify_merkle_proof(&root, &voters[2], &[leaves[3], left]));
+        // Node ordering is canonical, so sibling order does not matter
+        assert!(EligibilityMode::verify_merkle_proof(&root, &voters[1], &[leaves[0], right]));
+        // Wrong proof or outsider fails
+        assert!(!EligibilityMode::verify_merkle_proof(&root, &voters[2], &[leaves[0], right]));
+        assert!(!EligibilityMode::verify_merkle_proof(&root, &Pubkey::new_unique(), &[leaves[3], left]));
+        // A leaf alone is only valid for a single-voter tree
+        assert!(EligibilityMode::verify_merkle_proof(&leaves[0], &voters[0], &[]));
+        assert!(!EligibilityMode::verify_merkle_proof(&root, &voters[0], &[]));
+    }
+
+    #[test]
+    fn test_registration_weight() {
+        let mint = Pubkey::new_unique();
+        let gated = VoteWeighting::TokenGated { mint, min_balance: 100 };
+        let weighted = VoteWeighting::TokenWeighted { mint };
 
-/// Voter account tracking voter's authority and voting status
-#[account]
-pub struct VoterAccount {
-    pub authority: Pubkey,  // 32 bytes
-    pub voted: bool,        // 1 byte
-    pub votes: [Pubkey; 2], // 64 bytes
-    pub bump: u8,           // 1 byte
-}
+        // Token weighting only counts tokens moved into the vault
+        assert_eq!(VoteWeighting::Equal.registration_weight(), 1);
+        assert_eq!(gated.registration_weight(), 0);
+        assert_eq!(weighted.registration_weight(), 0);
 
-impl VoterAccount {
-    // 8 (discriminator) + 32 (authority) + 1 (voted) + 64 (votes) + 1 (bump) = 106 bytes
-    pub const SPACE: usize = 8 + 32 + 1 + 64 + 1;
-}
+        assert_eq!(VoteWeighting::Equal.mint(), None);
+        assert_eq!(weighted.mint(), Some(mint));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_locked_weight() {
+        let mint = Pubkey::new_unique();
+        let insufficient: Error = crate::errors::VoteError::InsufficientTokenBalance.into();
+
+        let gated = VoteWeighting::TokenGated { mint, min_balance: 100 };
+        assert_eq!(gated.locked_weight(100).unwrap(), 1);
+        assert_eq!(gated.locked_weight(1_000_000).unwrap(), 1);
+        assert_eq!(gated.locked_weight(99).unwrap_err(), insufficient);
+
+        let weighted = VoteWeighting::TokenWeighted { mint };
+        assert_eq!(weighted.locked_weight(250).unwrap(), 250);
+        assert_eq!(weighted.locked_weight(0).unwrap_err(), insufficient);
+
+        assert_eq!(
+            VoteWeighting::Equal.locked_weight(250).unwrap_err(),
+            crate::errors::VoteError::TokensNotLockable.into()
+        );
+    }
+
+    #[test]
+    fn test_vote_weighting_serialized_size() {
+        let largest = VoteWeighting::TokenGated { mint: Pubkey::new_unique(), min_balance: 1 };
+        assert_eq!(largest.try_to_vec().unwrap().len(), 41);
+    }
+
+    #[test]
+    fn test_whitelist_entry_space() {
+        assert_eq!(WhitelistEntry::SPACE, 8 + 32 + 32 + 1);
+    }
+
+    #[test]
+    fn test_config_space() {
+        let config = Config { emergency_authority: Pubkey::new_unique(), paused: true, bump: 255 };
+        assert_eq!(Config::SPACE, 42);
+        assert_eq!(8 + config.try_to_vec().unwrap().len(), Config::SPACE);
+    }
+
+    #[test]
+    fn test_sponsor_budget_space() {
+        let budget = SponsorBudget {
+            election: Pubkey::new_unique(),
+            sponsor: Pubkey::new_unique(),
+            relayer: Pubkey::new_unique(),
+            fee_per_transaction: u64::MAX,
+            spent: u64::MAX,
+            sponsored_transactions: u64::MAX,
+            bump: 255,
+        };
+        assert_eq!(8 + budget.try_to_vec().unwrap().len(), SponsorBudget::SPACE);
+    }
+
+    #[test]
+    fn test_sponsor_budget_spend() {
+        assert!(SponsorBudget::check_fee(SponsorBudget::MAX_FEE_PER_TRANSACTION).is_ok());
+        assert_eq!(
+            SponsorBudget::check_fee(SponsorBudget::MAX_FEE_PER_TRANSACTION + 1).unwrap_err(),
+            crate::errors::VoteError::InvalidSponsorFee.into()
+        );
+
+        let mut budget = SponsorBudget {
+            election: Pubkey::new_unique(),
+            sponsor: Pubkey::new_unique(),
+            relayer: Pubkey::new_unique(),
+            fee_per_transaction: 5_000,
+            spent: 0,
+            sponsored_transactions: 0,
+            bump: 255,
+        };
+        budget.record_spend(5_000, 5_000).unwrap();
+        budget.record_spend(0, 0).unwrap();
+        assert_eq!((budget.spent, budget.sponsored_transactions), (5_000, 2));
+
+        assert_eq!(
+            budget.record_spend(5_001, 5_000).unwrap_err(),
+            crate::errors::VoteError::SponsorBudgetExhausted.into()
+        );
+        assert_eq!((budget.spent, budget.sponsored_transactions), (5_000, 2));
+    }
+
+    #[test]
+    fn test_config_pause_check() {
+        let mut config = Config { emergency_authority: Pubkey::new_unique(), paused: false, bump: 255 };
+        assert!(config.check_not_paused().is_ok());
+        config.paused = true;
+        assert_eq!(
+            config.check_not_paused().unwrap_err(),
+            crate::errors::VoteError::ProgramPaused.into()
+        );
+    }
 
     #[test]
     fn test_candidate_account_space() {
-        // Test that SPACE constant is correctly calculated
-        let expected_space = 8 + 36 + 8 + 1; // discriminator + name + votes + bump
+        // The derived size must agree with the length limits enforced on input
+        // discriminator + version + election + name + description + uri + owner + status + votes
+        // + negative_votes + score_sum + payer + bump
+        let expected_space = 8 + 1 + 32 + (4 + CandidateAccount::MAX_NAME_LEN)
+            + (4 + CandidateAccount::MAX_DESCRIPTION_LEN) + (4 + CandidateAccount::MAX_URI_LEN)
+            + 32 + 1 + 8 + 8 + 8 + 32 + 1;
         assert_eq!(CandidateAccount::SPACE, expected_space);
-        assert_eq!(CandidateAccount::SPACE, 53);
+    }
+
+    #[test]
+    fn test_versioned_discriminators() {
+        let discriminator = |name: &str| hash(format!("account:{name}").as_bytes()).to_bytes()[..8].to_vec();
+
+        assert_eq!(CandidateAccountV0::DISCRIMINATOR.to_vec(), discriminator("CandidateAccount"));
+        assert_eq!(VoterAccountV0::DISCRIMINATOR.to_vec(), discriminator("VoterAccount"));
+        assert_eq!(CandidateAccount::DISCRIMINATOR, discriminator("CandidateAccountV1"));
+        assert_eq!(VoterAccount::DISCRIMINATOR, discriminator("VoterAccountV2"));
+    }
+
+    #[test]
+    fn test_legacy_layouts_match_first_deployment() {
+        // name (String) + votes (u64) + bump, as the first program wrote them
+        let legacy = CandidateAccountV0 { name: String::from("alice"), votes: 5, bump: 254 };
+        let mut expected = 5u32.to_le_bytes().to_vec();
+        expected.extend(b"alice");
+        expected.extend(5u64.to_le_bytes());
+        expected.push(254);
+        assert_eq!(legacy.try_to_vec().unwrap(), expected);
+        let full = CandidateAccountV0 { name: "A".repeat(CandidateAccount::MAX_NAME_LEN), ..legacy };
+        assert_eq!(8 + full.try_to_vec().unwrap().len(), CandidateAccountV0::SPACE);
+
+        // authority + voted (bool) + votes ([Pubkey; 2]) + bump
+        let (authority, first, second) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let legacy = VoterAccountV0 { authority, voted: true, votes: [first, second], bump: 253 };
+        let mut expected = authority.to_bytes().to_vec();
+        expected.push(1);
+        expected.extend(first.to_bytes());
+        expected.extend(second.to_bytes());
+        expected.push(253);
+        assert_eq!(legacy.try_to_vec().unwrap(), expected);
+        assert_eq!(8 + expected.len(), VoterAccountV0::SPACE);
+    }
+
+    #[test]
+    fn test_legacy_accounts_migrate() {
+        let (election, admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let candidate = CandidateAccountV0 { name: String::from("alice"), votes: 5, bump: 254 }.migrate(election, admin, 250);
+        assert_eq!(candidate.version, CandidateAccount::VERSION);
+        assert_eq!((candidate.election, candidate.owner, candidate.payer), (election, admin, admin));
+        assert_eq!((candidate.name.as_str(), candidate.votes, candidate.bump), ("alice", 5, 250));
+        assert_eq!(candidate.status, CandidateStatus::Approved);
+
+        let legacy = VoterAccountV0 {
+            authority: Pubkey::new_unique(),
+            voted: true,
+            votes: [Pubkey::new_unique(), Pubkey::new_unique()],
+            bump: 253,
+        };
+        let votes = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let voter = legacy.clone().migrate(election, votes.clone(), admin, 251);
+        assert_eq!((voter.version, voter.nonce), (VoterAccount::VERSION, 0));
+        assert_eq!((voter.election, voter.authority, voter.payer), (election, legacy.authority, admin));
+        assert_eq!((voter.voted, voter.votes, voter.weight, voter.bump), (true, votes, 1, 251));
+        assert!(voter.negative_votes.is_empty() && voter.delegate.is_none());
     }
 
     #[test]
@@ -47,68 +1602,826 @@ mod tests {
 
     #[test]
     fn test_voter_account_space() {
-        // Test that SPACE constant is correctly calculated
-        let expected_space = 8 + 32 + 1 + 64 + 1; // discriminator + authority + voted + votes + bump
+        // The derived size must agree with the ballot limits
+        // discriminator + version + election + authority + voted + votes + negative_votes + scores
+        // + weight + locked_amount + commitment + delegate + delegated_weight + nonce + payer + bump
+        let expected_space = 8 + 1 + 32 + 32 + 1
+            + (4 + Election::MAX_POSITIVE_VOTES * 32)
+            + (4 + Election::MAX_NEGATIVE_VOTES * 32)
+            + (4 + Election::MAX_POSITIVE_VOTES)
+            + 8 + 8 + 33 + 33 + 8 + 8 + 32 + 1;
         assert_eq!(VoterAccount::SPACE, expected_space);
-        assert_eq!(VoterAccount::SPACE, 106);
     }
 
     #[test]
-    fn test_voter_account_votes_array_size() {
-        // Ensure votes array can hold exactly 2 pubkeys
+    fn test_voter_space_fits_full_ballot() {
+        // Ensure a ballot using every positive and negative vote fits
         let voter = VoterAccount {
+            version: VoterAccount::VERSION,
+            election: Pubkey::default(),
             authority: Pubkey::default(),
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
+            voted: true,
+            votes: (0..Election::MAX_POSITIVE_VOTES).map(|_| Pubkey::new_unique()).collect(),
+            negative_votes: (0..Election::MAX_NEGATIVE_VOTES).map(|_| Pubkey::new_unique()).collect(),
+            scores: vec![u8::MAX; Election::MAX_POSITIVE_VOTES],
+            weight: 1,
+            locked_amount: 0,
+            commitment: Some([7; 32]),
+            delegate: Some(Pubkey::new_unique()),
+            delegated_weight: u64::MAX,
+            nonce: u64::MAX,
+            payer: Pubkey::default(),
             bump: 255,
         };
-        assert_eq!(voter.votes.len(), 2);
+        let serialized = voter.try_to_vec().unwrap();
+        assert_eq!(8 + serialized.len(), VoterAccount::SPACE);
     }
 
     #[test]
-    fn test_default_voter_state() {
-        let authority = Pubkey::new_unique();
-        let voter = VoterAccount {
-            authority,
-            voted: false,
-            votes: [Pubkey::default(), Pubkey::default()],
-            bump: 255,
+    fn test_vote_config_bounds() {
+        assert!(Election::check_vote_config(2, 0).is_ok());
+        assert!(Election::check_vote_config(3, 1).is_ok());
+        assert!(Election::check_vote_config(4, 2).is_ok());
+        assert!(Election::check_vote_config(0, 0).is_err());
+        assert!(Election::check_vote_config(1, 1).is_err());
+        assert!(Election::check_vote_config(3, 2).is_err());
+        assert!(Election::check_vote_config(Election::MAX_POSITIVE_VOTES as u8 + 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_ballot_validation() {
+        let election = Election::with_window(ElectionStatus::Active, 0, 100);
+        let (a, b, c, d) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let ok = Ballot { positive: vec![a, b], negative: vec![c], scores: Vec::new() };
+        assert!(ok.validate(&election).is_ok());
+
+        let single = Ballot { positive: vec![a], negative: vec![], scores: Vec::new() };
+        assert!(single.validate(&election).is_ok());
+
+        let empty = Ballot::default();
+        assert_eq!(
+            empty.validate(&election).unwrap_err(),
+            crate::errors::VoteError::EmptyBallot.into()
+        );
+
+        let too_many = Ballot { positive: vec![a, b, c, d], negative: vec![], scores: Vec::new() };
+        assert_eq!(
+            too_many.validate(&election).unwrap_err(),
+            crate::errors::VoteError::TooManyVotes.into()
+        );
+
+        let too_many_negative = Ballot { positive: vec![a, b], negative: vec![c, d], scores: Vec::new() };
+        assert_eq!(
+            too_many_negative.validate(&election).unwrap_err(),
+            crate::errors::VoteError::TooManyNegativeVotes.into()
+        );
+
+        let early_negative = Ballot { positive: vec![a], negative: vec![b], scores: Vec::new() };
+        assert_eq!(
+            early_negative.validate(&election).unwrap_err(),
+            crate::errors::VoteError::NegativeVoteNotAllowed.into()
+        );
+
+        let duplicate = Ballot { positive: vec![a, b], negative: vec![a], scores: Vec::new() };
+        assert_eq!(
+            duplicate.validate(&election).unwrap_err(),
+            crate::errors::VoteError::DuplicateCandidates.into()
+        );
+    }
+
+    #[test]
+    fn test_candidate_net_votes() {
+        let candidate = CandidateAccount::tallied(2, 5);
+        assert_eq!(candidate.net_votes(), -3);
+    }
+
+    #[test]
+    fn test_candidate_record_and_retract() {
+        let mut candidate = CandidateAccount::tallied(0, 0);
+
+        candidate.record_vote(5, false).unwrap();
+        candidate.record_vote(2, true).unwrap();
+        assert_eq!((candidate.votes, candidate.negative_votes), (5, 2));
+
+        candidate.retract_vote(5, false).unwrap();
+        assert_eq!(candidate.votes, 0);
+        // Retracting more than was recorded must not wrap
+        assert!(candidate.retract_vote(3, true).is_err());
+        assert_eq!(candidate.negative_votes, 2);
+
+        candidate.votes = u64::MAX;
+        assert!(candidate.record_vote(1, false).is_err());
+    }
+
+    #[test]
+    fn test_candidate_record_and_retract_score() {
+        let mut candidate = CandidateAccount::tallied(0, 0);
+
+        candidate.record_score(3, 4).unwrap();
+        candidate.record_score(1, 0).unwrap();
+        assert_eq!(candidate.score_sum, 12);
+
+        candidate.retract_score(3, 4).unwrap();
+        assert_eq!(candidate.score_sum, 0);
+        assert!(candidate.retract_score(1, 1).is_err());
+
+        assert!(candidate.record_score(u64::MAX, 2).is_err());
+        assert_eq!(candidate.score_sum, 0);
+    }
+
+    #[test]
+    fn test_ballot_union() {
+        let (a, b, c, d) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let previous = Ballot { positive: vec![a, b], negative: vec![c], scores: Vec::new() };
+        let next = Ballot { positive: vec![c, d], negative: Vec::new(), scores: Vec::new() };
+
+        // Previous ballot first, then candidates only on the new ballot
+        assert_eq!(previous.union(&next), vec![a, b, c, d]);
+        assert_eq!(previous.union(&previous), vec![a, b, c]);
+    }
+
+    #[test]
+    fn test_voter_set_and_clear_ballot() {
+        let mut voter = VoterAccount::registered(1);
+        let ballot = Ballot {
+            positive: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            negative: vec![Pubkey::new_unique()],
+            scores: Vec::new(),
         };
 
-        assert_eq!(voter.authority, authority);
+        voter.set_ballot(&ballot);
+        assert!(voter.voted);
+        assert_eq!(voter.ballot(), ballot);
+
+        voter.clear_ballot();
         assert!(!voter.voted);
-        assert_eq!(voter.votes[0], Pubkey::default());
-        assert_eq!(voter.votes[1], Pubkey::default());
-        assert_eq!(voter.bump, 255);
+        assert_eq!(voter.ballot(), Ballot::default());
     }
 
     #[test]
-    fn test_candidate_vote_increment() {
-        let mut candidate = CandidateAccount {
-            name: String::from("Alice"),
-            votes: 0,
+    fn test_results_space() {
+        let entry = ResultEntry {
+            candidate: Pubkey::new_unique(),
+            votes: u64::MAX,
+            negative_votes: u64::MAX,
+            score_sum: u64::MAX,
+            average_score: u64::MAX,
+            net_votes: i128::MIN,
+        };
+        let results = Results {
+            election: Pubkey::new_unique(),
+            entries: vec![entry; Election::MAX_CANDIDATES],
+            winners: vec![Pubkey::new_unique(); Election::MAX_CANDIDATES],
+            is_tie: true,
+            finalized_at: i64::MAX,
             bump: 255,
         };
+        assert_eq!(8 + results.try_to_vec().unwrap().len(), Results::SPACE);
+        assert_eq!(Results::SPACE, 1850);
+    }
 
-        // Test initial state
-        assert_eq!(candidate.votes, 0);
-        assert_eq!(candidate.name, "Alice");
+    #[test]
+    fn test_results_rank_single_winner() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (ca, cb, cc) = (
+            CandidateAccount::tallied(3, 0),
+            CandidateAccount::tallied(5, 3),
+            CandidateAccount::tallied(4, 0),
+        );
 
-        // Simulate vote increments
-        candidate.votes = candidate.votes.checked_add(1).unwrap();
-        assert_eq!(candidate.votes, 1);
+        let (entries, winners, is_tie) = Results::rank(VotingMethod::D21, &[(a, &ca), (b, &cb), (c, &cc)]);
 
-        candidate.votes = candidate.votes.checked_add(1).unwrap();
-        assert_eq!(candidate.votes, 2);
+        let order: Vec<Pubkey> = entries.iter().map(|e| e.candidate).collect();
+        assert_eq!(order, vec![c, a, b]);
+        assert_eq!(entries[2].net_votes, 2);
+        assert_eq!(winners, vec![c]);
+        assert!(!is_tie);
     }
 
     #[test]
-    fn test_candidate_name_length() {
-        let short_name = String::from("Bob");
-        let max_name = "A".repeat(CandidateAccount::MAX_NAME_LEN);
-        
-        assert!(short_name.len() <= CandidateAccount::MAX_NAME_LEN);
-        assert_eq!(max_name.len(), CandidateAccount::MAX_NAME_LEN);
+    fn test_results_rank_tie_and_empty() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (ca, cb, cc) = (
+            CandidateAccount::tallied(1, 1),
+            CandidateAccount::tallied(4, 1),
+            CandidateAccount::tallied(3, 0),
+        );
+
+        let (entries, winners, is_tie) = Results::rank(VotingMethod::D21, &[(a, &ca), (b, &cb), (c, &cc)]);
+        // Equal scores keep registration order
+        assert_eq!(entries[0].candidate, b);
+        assert_eq!(winners, vec![b, c]);
+        assert!(is_tie);
+
+        let (entries, winners, is_tie) = Results::rank(VotingMethod::D21, &[]);
+        assert!(entries.is_empty() && winners.is_empty() && !is_tie);
+    }
+
+    #[test]
+    fn test_ballot_commitment_matches_borsh() {
+        let ballot = Ballot {
+            positive: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            negative: vec![Pubkey::new_unique()],
+            scores: Vec::new(),
+        };
+        let salt = [9u8; 32];
+        let expected = hashv(&[&ballot.try_to_vec().unwrap(), &salt]).to_bytes();
+        assert_eq!(ballot.commitment(&salt), expected);
+
+        // A different salt or ballot produces a different commitment
+        assert_ne!(ballot.commitment(&[0u8; 32]), expected);
+        let mut swapped = ballot.clone();
+        swapped.positive.swap(0, 1);
+        assert_ne!(swapped.commitment(&salt), expected);
+
+        // Scores are committed to as well
+        let scored = Ballot {
+            positive: ballot.positive.clone(),
+            negative: Vec::new(),
+            scores: vec![3, 0],
+        };
+        let expected = hashv(&[&scored.try_to_vec().unwrap(), &salt]).to_bytes();
+        assert_eq!(scored.commitment(&salt), expected);
+    }
+
+    #[test]
+    fn test_signed_ballot_message() {
+        let signed = SignedBallot {
+            authority: Pubkey::new_unique(),
+            nonce: 3,
+            ballot: Ballot { positive: vec![Pubkey::new_unique()], negative: Vec::new(), scores: vec![2] },
+        };
+        let election = Pubkey::new_unique();
+        let message = signed.message(&election);
+
+        let (domain, rest) = message.split_at(SignedBallot::DOMAIN.len());
+        assert_eq!(domain, SignedBallot::DOMAIN);
+        assert_eq!(&rest[..32], election.as_ref());
+        assert_eq!(&rest[32..64], signed.authority.as_ref());
+        assert_eq!(rest[64..72], 3u64.to_le_bytes());
+        assert_eq!(rest[72..], signed.ballot.try_to_vec().unwrap()[..]);
+
+        // Bound to its election and nonce
+        assert_ne!(signed.message(&Pubkey::new_unique()), message);
+        let next = SignedBallot { nonce: 4, ..signed.clone() };
+        assert_ne!(next.message(&election), message);
+    }
+
+    #[test]
+    fn test_reveal_window() {
+        let mut election = Election::with_window(ElectionStatus::Active, 100, 200);
+        assert!(!election.is_commit_reveal());
+        assert!(election.check_reveal_window(150).is_err());
+        assert_eq!(election.tally_ends_at(), 200);
+
+        election.reveal_ends_at = Some(300);
+        assert!(election.is_commit_reveal());
+        assert!(election.check_reveal_window(199).is_err());
+        assert!(election.check_reveal_window(200).is_ok());
+        assert!(election.check_reveal_window(299).is_ok());
+        assert!(election.check_reveal_window(300).is_err());
+        assert_eq!(election.tally_ends_at(), 300);
+
+        // Reveals continue after the election is closed, but not once finalized
+        election.status = ElectionStatus::Closed;
+        assert!(election.check_reveal_window(250).is_ok());
+        election.status = ElectionStatus::Finalized;
+        assert!(election.check_reveal_window(250).is_err());
+    }
+
+    #[test]
+    fn test_vote_weight_includes_delegations() {
+        let mut voter = VoterAccount { delegated_weight: 4, ..VoterAccount::registered(3) };
+        assert_eq!(voter.vote_weight().unwrap(), 7);
+        assert!(voter.check_not_delegated().is_ok());
+
+        voter.delegated_weight = u64::MAX;
+        assert!(voter.vote_weight().is_err());
+
+        voter.delegate = Some(Pubkey::new_unique());
+        assert!(voter.check_not_delegated().is_err());
+    }
+
+    #[test]
+    fn test_delegation_chain_valid() {
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (origin, a, b, c) = (keys[0], keys[1], keys[2], keys[3]);
+
+        // origin -> a (terminal)
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[(a, None)]).is_ok());
+        // origin -> a -> b -> c (terminal)
+        let chain = [(a, Some(b)), (b, Some(c)), (c, None)];
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &chain).is_ok());
+    }
+
+    #[test]
+    fn test_delegation_chain_rejects_bad_links() {
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (origin, a, b, c) = (keys[0], keys[1], keys[2], keys[3]);
+
+        // Empty chain, wrong first link, broken link, a chain that stops early
+        // and accounts passed after the terminal voter
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[]).is_err());
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[(a, None), (b, None)]).is_err());
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[(b, None)]).is_err());
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[(a, Some(b)), (c, None)]).is_err());
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &[(a, Some(b))]).is_err());
+
+        // Self-delegation and longer cycles back to the origin
+        assert!(VoterAccount::check_delegation_chain(&origin, &origin, &[(origin, None)]).is_err());
+        let cycle = [(a, Some(b)), (b, Some(origin))];
+        assert!(VoterAccount::check_delegation_chain(&origin, &a, &cycle).is_err());
+    }
+
+    #[test]
+    fn test_delegation_chain_depth_limit() {
+        let origin = Pubkey::new_unique();
+        let keys: Vec<Pubkey> = (0..=VoterAccount::MAX_DELEGATION_DEPTH)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let link = |i: usize, len: usize| (keys[i], (i + 1 < len).then(|| keys[i + 1]));
+
+        let max: Vec<_> = (0..VoterAccount::MAX_DELEGATION_DEPTH)
+            .map(|i| link(i, VoterAccount::MAX_DELEGATION_DEPTH))
+            .collect();
+        assert!(VoterAccount::check_delegation_chain(&origin, &keys[0], &max).is_ok());
+
+        let too_deep: Vec<_> = (0..=VoterAccount::MAX_DELEGATION_DEPTH)
+            .map(|i| link(i, VoterAccount::MAX_DELEGATION_DEPTH + 1))
+            .collect();
+        assert!(VoterAccount::check_delegation_chain(&origin, &keys[0], &too_deep).is_err());
+        assert!(VoterAccount::check_delegation_chain(&origin, &keys[0], &too_deep[..VoterAccount::MAX_DELEGATION_DEPTH]).is_err());
+    }
+
+    #[test]
+    fn test_candidate_space_fits_max_metadata() {
+        let candidate = CandidateAccount {
+            version: CandidateAccount::VERSION,
+            election: Pubkey::new_unique(),
+            name: "N".repeat(CandidateAccount::MAX_NAME_LEN),
+            description: "D".repeat(CandidateAccount::MAX_DESCRIPTION_LEN),
+            uri: "U".repeat(CandidateAccount::MAX_URI_LEN),
+            owner: Pubkey::new_unique(),
+            status: CandidateStatus::Nominated,
+            votes: u64::MAX,
+            negative_votes: u64::MAX,
+            score_sum: 0,
+            payer: Pubkey::new_unique(),
+            bump: 255,
+        };
+        assert_eq!(8 + candidate.try_to_vec().unwrap().len(), CandidateAccount::SPACE);
+    }
+
+    #[test]
+    fn test_candidate_metadata_bounds() {
+        let description = "D".repeat(CandidateAccount::MAX_DESCRIPTION_LEN);
+        let uri = "U".repeat(CandidateAccount::MAX_URI_LEN);
+        assert!(CandidateAccount::check_metadata(&description, &uri).is_ok());
+        assert!(CandidateAccount::check_metadata("", "").is_ok());
+        assert!(CandidateAccount::check_metadata(&(description + "D"), "").is_err());
+        assert!(CandidateAccount::check_metadata("", &(uri + "U")).is_err());
+    }
+
+    #[test]
+    fn test_only_approved_candidates_are_votable() {
+        let mut candidate = CandidateAccount::tallied(0, 0);
+        assert!(candidate.check_votable().is_ok());
+
+        for status in [CandidateStatus::Nominated, CandidateStatus::Rejected] {
+            candidate.status = status;
+            assert!(candidate.check_votable().is_err());
+        }
+    }
+
+    #[test]
+    fn test_withdrawn_and_disqualified_candidates() {
+        let mut candidate = CandidateAccount::tallied(0, 0);
+        assert!(candidate.check_removable("Personal reasons").is_ok());
+        assert!(candidate.check_removable(&"R".repeat(CandidateAccount::MAX_REASON_LEN + 1)).is_err());
+
+        for status in [CandidateStatus::Withdrawn, CandidateStatus::Disqualified] {
+            candidate.status = status;
+            assert_eq!(
+                candidate.check_votable().unwrap_err(),
+                crate::errors::VoteError::CandidateInactive.into()
+            );
+            // Already out of the race
+            assert!(candidate.check_removable("").is_err());
+        }
+
+        candidate.status = CandidateStatus::Nominated;
+        assert!(candidate.check_removable("").is_ok());
+    }
+
+    #[test]
+    fn test_results_rank_skips_inactive_candidates() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (ca, cc) = (CandidateAccount::tallied(2, 0), CandidateAccount::tallied(1, 0));
+        let mut cb = CandidateAccount::tallied(9, 0);
+        cb.status = CandidateStatus::Disqualified;
+
+        let (entries, winners, is_tie) = Results::rank(VotingMethod::D21, &[(a, &ca), (b, &cb), (c, &cc)]);
+        let order: Vec<Pubkey> = entries.iter().map(|e| e.candidate).collect();
+        assert_eq!(order, vec![a, c]);
+        assert_eq!(winners, vec![a]);
+        assert!(!is_tie);
+    }
+
+    fn name_error(name: &str) -> Error {
+        CandidateAccount::normalize_name(name).unwrap_err()
+    }
+
+    #[test]
+    fn test_normalize_name_trims_and_composes() {
+        assert_eq!(CandidateAccount::normalize_name("Alice").unwrap(), "Alice");
+        assert_eq!(CandidateAccount::normalize_name("  Alice \t").unwrap(), "Alice");
+        // Inner spaces are kept, case is preserved for display
+        assert_eq!(CandidateAccount::normalize_name("Mary Ann").unwrap(), "Mary Ann");
+        // Decomposed e + combining diaeresis is stored composed
+        assert_eq!(CandidateAccount::normalize_name("Zoe\u{308}").unwrap(), "Zo\u{eb}");
+    }
+
+    #[test]
+    fn test_normalize_name_rejects_empty() {
+        assert_eq!(name_error(""), crate::errors::VoteError::NameEmpty.into());
+        assert_eq!(name_error("   "), crate::errors::VoteError::NameEmpty.into());
+    }
+
+    #[test]
+    fn test_normalize_name_rejects_too_long() {
+        let max = "A".repeat(CandidateAccount::MAX_NAME_LEN);
+        assert!(CandidateAccount::normalize_name(&max).is_ok());
+        assert_eq!(name_error(&(max.clone() + "A")), crate::errors::VoteError::NameTooLong.into());
+        // Surrounding whitespace doesn't count towards the limit
+        assert!(CandidateAccount::normalize_name(&format!("  {}  ", max)).is_ok());
+        // Multi-byte characters count by encoded length
+        assert_eq!(name_error(&"\u{e9}".repeat(17)), crate::errors::VoteError::NameTooLong.into());
+        // So does the case-folded form: "\u{130}" (2 bytes) folds to "i\u{307}" (3 bytes)
+        let dotted = "\u{130}".repeat(CandidateAccount::MAX_NAME_LEN / 2);
+        assert_eq!(dotted.len(), CandidateAccount::MAX_NAME_LEN);
+        assert_eq!(name_error(&dotted), crate::errors::VoteError::NameTooLong.into());
+    }
+
+    #[test]
+    fn test_normalize_name_rejects_invalid_characters() {
+        for name in ["Ali\nce", "Ali\tce", "Ali\u{0}ce", "Ali\u{200B}ce", "Ali\u{202E}ce", "Ali\u{FEFF}ce"] {
+            assert_eq!(
+                name_error(name),
+                crate::errors::VoteError::InvalidNameCharacters.into(),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_name_key_folds_case_and_composition() {
+        assert_eq!(CandidateAccount::name_key("Alice"), "alice");
+        assert_eq!(CandidateAccount::name_key(" ALICE "), "alice");
+        assert_eq!(CandidateAccount::name_key("Stra\u{df}e"), "strasse");
+        assert_eq!(CandidateAccount::name_key("ZOE\u{308}"), CandidateAccount::name_key("zo\u{eb}"));
+        assert_eq!(CandidateAccount::name_seed("Alice"), CandidateAccount::name_seed("alice "));
+        assert_ne!(CandidateAccount::name_seed("Alice"), CandidateAccount::name_seed("Bob"));
+    }
+
+    #[test]
+    fn test_ranked_ballot_and_runoff_space() {
+        let ballot = RankedBallot {
+            election: Pubkey::new_unique(),
+            voter: Pubkey::new_unique(),
+            rankings: vec![Pubkey::new_unique(); Election::MAX_CANDIDATES],
+            weight: u64::MAX,
+            counted_round: u8::MAX,
+            bump: 255,
+        };
+        assert_eq!(8 + ballot.try_to_vec().unwrap().len(), RankedBallot::SPACE);
+        assert_eq!(RankedBallot::SPACE, 598);
+
+        let runoff = RunoffTally {
+            election: Pubkey::new_unique(),
+            round: u8::MAX,
+            ballots_counted: u32::MAX,
+            counts: vec![u64::MAX; Election::MAX_CANDIDATES],
+            eliminated: vec![Pubkey::new_unique(); Election::MAX_CANDIDATES],
+            exhausted: u64::MAX,
+            winner: Some(Pubkey::new_unique()),
+            complete: true,
+            bump: 255,
+        };
+        assert_eq!(8 + runoff.try_to_vec().unwrap().len(), RunoffTally::SPACE);
+        assert_eq!(RunoffTally::SPACE, 736);
+    }
+
+    #[test]
+    fn test_ranked_ballot_validation() {
+        let mut election = Election::with_window(ElectionStatus::Active, 100, 200);
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        election.candidates = vec![a, b];
+
+        assert!(RankedBallot::validate(&[b, a], &election).is_ok());
+        assert!(RankedBallot::validate(&[a], &election).is_ok());
+        assert!(RankedBallot::validate(&[], &election).is_err());
+        assert!(RankedBallot::validate(&[a, a], &election).is_err());
+        assert!(RankedBallot::validate(&[a, c], &election).is_err());
+    }
+
+    fn new_runoff(candidates: usize) -> RunoffTally {
+        RunoffTally {
+            election: Pubkey::default(),
+            round: 1,
+            ballots_counted: 0,
+            counts: vec![0; candidates],
+            eliminated: Vec::new(),
+            exhausted: 0,
+            winner: None,
+            complete: false,
+            bump: 255,
+        }
+    }
+
+    /// Run every round over in-memory ballots the way `tabulate_round` does.
+    fn run_runoff(
+        runoff: &mut RunoffTally,
+        candidates: &[Pubkey],
+        ballots: &[(Vec<Pubkey>, u64)],
+    ) -> Vec<RoundOutcome> {
+        let mut outcomes = Vec::new();
+        while !runoff.complete {
+            for (rankings, weight) in ballots {
+                runoff.count(candidates, rankings, *weight).unwrap();
+            }
+            outcomes.push(runoff.conclude_round(candidates));
+        }
+        outcomes
+    }
+
+    #[test]
+    fn test_runoff_first_round_majority() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let mut runoff = new_runoff(3);
+        let ballots = vec![
+            (vec![keys[0], keys[1]], 3),
+            (vec![keys[1]], 1),
+            (vec![keys[2], keys[0]], 1),
+        ];
+
+        let outcomes = run_runoff(&mut runoff, &keys, &ballots);
+        assert_eq!(outcomes, vec![RoundOutcome::Winner(keys[0])]);
+        assert_eq!(runoff.winner, Some(keys[0]));
+        assert_eq!(runoff.round, 1);
+    }
+
+    #[test]
+    fn test_runoff_redistributes_until_majority() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let (a, b, c) = (keys[0], keys[1], keys[2]);
+        let mut runoff = new_runoff(3);
+        // First round: A 4, B 3, C 2 (no majority of 9). C is eliminated and
+        // its ballots move to B, who then wins 5 to 4.
+        let ballots = vec![
+            (vec![a], 4),
+            (vec![b, a], 3),
+            (vec![c, b], 2),
+        ];
+
+        let outcomes = run_runoff(&mut runoff, &keys, &ballots);
+        assert_eq!(outcomes, vec![RoundOutcome::Eliminated(c), RoundOutcome::Winner(b)]);
+        assert_eq!(runoff.eliminated, vec![c]);
+        assert_eq!(runoff.counts, vec![4, 5, 2]);
+        assert_eq!(runoff.round, 2);
+
+        // Winner first, then the runner-up, then the eliminated candidate
+        let order: Vec<Pubkey> = runoff.standings(&keys).iter().map(|e| e.candidate).collect();
+        assert_eq!(order, vec![b, a, c]);
+    }
+
+    #[test]
+    fn test_runoff_exhausted_ballots_and_ties() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let (a, b, c) = (keys[0], keys[1], keys[2]);
+        let mut runoff = new_runoff(3);
+        // B and C tie for last: the later-registered C goes first. Its only
+        // ballot ranks nobody else and is exhausted, so A wins 2 to 1.
+        let ballots = vec![(vec![a], 2), (vec![b], 1), (vec![c], 1)];
+
+        let outcomes = run_runoff(&mut runoff, &keys, &ballots);
+        assert_eq!(
+            outcomes,
+            vec![RoundOutcome::Eliminated(c), RoundOutcome::Winner(a)]
+        );
+        assert_eq!(runoff.exhausted, 1);
+    }
+
+    #[test]
+    fn test_runoff_skips_pre_eliminated_and_handles_no_ballots() {
+        let keys: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+        let mut runoff = new_runoff(2);
+        runoff.eliminated = vec![keys[0]];
+        // A withdrawn first choice passes the ballot on to the next preference
+        assert_eq!(runoff.top_choice(&keys, &[keys[0], keys[1]]), Some(1));
+        assert_eq!(runoff.top_choice(&keys, &[keys[0]]), None);
+
+        let mut empty = new_runoff(2);
+        assert_eq!(run_runoff(&mut empty, &keys, &[]), vec![RoundOutcome::NoWinner]);
+        assert!(empty.winner.is_none());
+    }
+
+    #[test]
+    fn test_method_config() {
+        assert!(Election::check_method_config(VotingMethod::D21).is_ok());
+        assert!(Election::check_method_config(VotingMethod::Approval).is_ok());
+        assert!(Election::check_method_config(VotingMethod::Score { max_score: 10 }).is_ok());
+        assert_eq!(
+            Election::check_method_config(VotingMethod::Score { max_score: 0 }).unwrap_err(),
+            crate::errors::VoteError::InvalidVoteConfig.into()
+        );
+
+        let mut election = Election::with_window(ElectionStatus::Active, 0, 100);
+        for method in [VotingMethod::D21, VotingMethod::Approval, VotingMethod::Score { max_score: 5 }] {
+            election.method = method;
+            assert!(election.check_tallied_method().is_ok());
+        }
+        election.method = VotingMethod::InstantRunoff;
+        assert!(election.check_tallied_method().is_err());
+    }
+
+    #[test]
+    fn test_approval_ballot_validation() {
+        let mut election = Election::with_window(ElectionStatus::Active, 0, 100);
+        election.method = VotingMethod::Approval;
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+        // Approvals are not limited by the D21 positive vote count
+        let all = Ballot { positive: keys.clone(), negative: Vec::new(), scores: Vec::new() };
+        assert!(all.validate(&election).is_ok());
+
+        let negative = Ballot { positive: keys[..2].to_vec(), negative: vec![keys[2]], scores: Vec::new() };
+        assert_eq!(
+            negative.validate(&election).unwrap_err(),
+            crate::errors::VoteError::NegativeVoteNotAllowed.into()
+        );
+
+        let scored = Ballot { positive: vec![keys[0]], negative: Vec::new(), scores: vec![1] };
+        assert_eq!(
+            scored.validate(&election).unwrap_err(),
+            crate::errors::VoteError::InvalidScores.into()
+        );
+
+        let duplicate = Ballot { positive: vec![keys[0], keys[0]], negative: Vec::new(), scores: Vec::new() };
+        assert_eq!(
+            duplicate.validate(&election).unwrap_err(),
+            crate::errors::VoteError::DuplicateCandidates.into()
+        );
+
+        assert_eq!(
+            Ballot::default().validate(&election).unwrap_err(),
+            crate::errors::VoteError::EmptyBallot.into()
+        );
+    }
+
+    #[test]
+    fn test_score_ballot_validation() {
+        let mut election = Election::with_window(ElectionStatus::Active, 0, 100);
+        election.method = VotingMethod::Score { max_score: 5 };
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let ok = Ballot { positive: vec![a, b, c], negative: Vec::new(), scores: vec![5, 0, 3] };
+        assert!(ok.validate(&election).is_ok());
+
+        let missing = Ballot { positive: vec![a, b], negative: Vec::new(), scores: vec![5] };
+        assert_eq!(
+            missing.validate(&election).unwrap_err(),
+            crate::errors::VoteError::InvalidScores.into()
+        );
+
+        let too_high = Ballot { positive: vec![a], negative: Vec::new(), scores: vec![6] };
+        assert_eq!(
+            too_high.validate(&election).unwrap_err(),
+            crate::errors::VoteError::ScoreTooHigh.into()
+        );
+
+        let negative = Ballot { positive: vec![a, b], negative: vec![c], scores: vec![1, 1] };
+        assert_eq!(
+            negative.validate(&election).unwrap_err(),
+            crate::errors::VoteError::NegativeVoteNotAllowed.into()
+        );
+
+        // D21 ballots carry no scores
+        election.method = VotingMethod::D21;
+        assert_eq!(
+            ok.validate(&election).unwrap_err(),
+            crate::errors::VoteError::InvalidScores.into()
+        );
+        election.method = VotingMethod::InstantRunoff;
+        assert!(Ballot { scores: Vec::new(), ..ok }.validate(&election).is_err());
+    }
+
+    #[test]
+    fn test_results_rank_by_score_with_averages() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        // a: rated by 2 voters, 9 points; b: rated by 4 voters, 10 points; c: unrated
+        let mut ca = CandidateAccount::tallied(2, 0);
+        ca.score_sum = 9;
+        let mut cb = CandidateAccount::tallied(4, 0);
+        cb.score_sum = 10;
+        let cc = CandidateAccount::tallied(0, 0);
+
+        let method = VotingMethod::Score { max_score: 5 };
+        let (entries, winners, is_tie) = Results::rank(method, &[(a, &ca), (b, &cb), (c, &cc)]);
+
+        let order: Vec<Pubkey> = entries.iter().map(|e| e.candidate).collect();
+        assert_eq!(order, vec![b, a, c]);
+        assert_eq!(entries[0].average_score, 2_500);
+        assert_eq!(entries[1].average_score, 4_500);
+        assert_eq!(entries[2].average_score, 0);
+        assert_eq!(winners, vec![b]);
+        assert!(!is_tie);
+
+        // The same tallies ranked by approvals
+        let (entries, winners, _) = Results::rank(VotingMethod::Approval, &[(a, &ca), (b, &cb), (c, &cc)]);
+        assert_eq!(entries[0].candidate, b);
+        assert_eq!(winners, vec![b]);
+    }
+
+    #[test]
+    fn test_quorum_config_bounds() {
+        assert!(Election::check_quorum_config(None).is_ok());
+        assert!(Election::check_quorum_config(Some(1)).is_ok());
+        assert!(Election::check_quorum_config(Some(Election::MAX_QUORUM_BPS)).is_ok());
+        for invalid in [0, Election::MAX_QUORUM_BPS + 1] {
+            assert_eq!(
+                Election::check_quorum_config(Some(invalid)).unwrap_err(),
+                crate::errors::VoteError::InvalidQuorum.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_quorum_reached() {
+        let mut election = Election::with_window(ElectionStatus::Active, 0, 100);
+        for _ in 0..4 {
+            election.record_registration().unwrap();
+        }
+        election.record_ballot().unwrap();
+        assert_eq!((election.registered_voters, election.ballots_cast), (4, 1));
+
+        // No quorum: any turnout is enough
+        assert!(election.quorum_reached());
+
+        // 1 of 4 voters is exactly 25%
+        election.quorum_bps = Some(2_500);
+        assert!(election.quorum_reached());
+        election.quorum_bps = Some(2_501);
+        assert!(!election.quorum_reached());
+
+        election.record_ballot().unwrap();
+        election.quorum_bps = Some(5_000);
+        assert!(election.quorum_reached());
+        election.retract_ballot().unwrap();
+        assert!(!election.quorum_reached());
     }
-}
 
+    #[test]
+    fn test_invalid_elections_are_finalized() {
+        let mut election = Election::with_window(ElectionStatus::Closed, 0, 100);
+        assert!(!election.is_finalized());
+        election.status = ElectionStatus::Finalized;
+        assert!(election.is_finalized());
+        election.status = ElectionStatus::Invalid;
+        assert!(election.is_finalized());
+        assert!(election.check_reveal_window(50).is_err());
+    }
+
+    #[test]
+    fn test_runoff_candidacy_freezes_when_tallies_are_final() {
+        let mut election = Election::with_window(ElectionStatus::Active, 0, 100);
+        assert!(election.check_candidacy_changeable(100).is_ok());
+
+        election.method = VotingMethod::InstantRunoff;
+        assert!(election.check_candidacy_changeable(99).is_ok());
+        assert_eq!(
+            election.check_candidacy_changeable(100).unwrap_err(),
+            crate::errors::VoteError::VotingEnded.into()
+        );
+        election.reveal_ends_at = Some(150);
+        assert!(election.check_candidacy_changeable(149).is_ok());
+        assert!(election.check_candidacy_changeable(150).is_err());
+
+        election.status = ElectionStatus::Finalized;
+        assert_eq!(
+            election.check_candidacy_changeable(0).unwrap_err(),
+            crate::errors::VoteError::ElectionAlreadyFinalized.into()
+        );
+    }
+}
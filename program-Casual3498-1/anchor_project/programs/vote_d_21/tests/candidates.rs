@@ -0,0 +1,118 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{find_candidate_pda, find_config_pda};
+
+/// Self-nominate `owner` as candidate `name` and return the candidate account.
+fn nominate(fx: &mut Fixture, owner: &Pubkey, name: &str) -> Pubkey {
+    fx.rt.airdrop(owner, SOL);
+    let candidate = find_candidate_pda(&fx.election, name).0;
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::NominateCandidate {
+                election: fx.election,
+                candidate,
+                config: find_config_pda().0,
+                owner: *owner,
+                payer: *owner,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::NominateCandidate {
+                name: name.to_string(),
+                description: String::new(),
+                uri: String::new(),
+            },
+        ))
+        .unwrap();
+    candidate
+}
+
+fn approve(fx: &mut Fixture, candidate: &Pubkey) -> std::result::Result<(), ProgramError> {
+    fx.rt.process(&instruction(
+        vote_d_21::accounts::ApproveCandidate {
+            election: fx.election,
+            candidate: *candidate,
+            config: find_config_pda().0,
+            admin: fx.admin,
+        },
+        vote_d_21::instruction::ApproveCandidate {},
+    ))
+}
+
+fn reject(fx: &mut Fixture, candidate: &Pubkey, payer: &Pubkey) -> std::result::Result<(), ProgramError> {
+    fx.rt.process(&instruction(
+        vote_d_21::accounts::RejectCandidate {
+            election: fx.election,
+            candidate: *candidate,
+            admin: fx.admin,
+            payer: *payer,
+        },
+        vote_d_21::instruction::RejectCandidate {},
+    ))
+}
+
+fn withdraw(fx: &mut Fixture, candidate: &Pubkey, owner: &Pubkey, reason: &str) -> std::result::Result<(), ProgramError> {
+    fx.rt.process(&instruction(
+        vote_d_21::accounts::WithdrawCandidacy { election: fx.election, candidate: *candidate, owner: *owner },
+        vote_d_21::instruction::WithdrawCandidacy { reason: reason.to_string() },
+    ))
+}
+
+#[test]
+fn test_nominees_need_approval_to_receive_votes() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let dave = fx.rt.signer();
+    let nominee = nominate(&mut fx, &dave, "dave");
+    let voter = fx.register_voter();
+
+    fx.candidates.push(nominee);
+    let err = fx.vote(&voter, fx.ballot(&[1], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::CandidateNotApproved));
+
+    approve(&mut fx, &nominee).unwrap();
+    assert_eq!(approve(&mut fx, &nominee).unwrap_err(), vote_error(VoteError::InvalidCandidateStatus));
+    assert_eq!(reject(&mut fx, &nominee, &dave).unwrap_err(), vote_error(VoteError::InvalidCandidateStatus));
+    assert_eq!(fx.election().candidates, vec![fx.candidates[0], nominee]);
+    fx.vote(&voter, fx.ballot(&[1], &[])).unwrap();
+    assert_eq!(fx.candidate(1).votes, 1);
+}
+
+#[test]
+fn test_rejected_nomination_frees_the_name() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let squatter = fx.rt.signer();
+    let nomination = nominate(&mut fx, &squatter, "bob");
+
+    let admin = fx.admin;
+    assert_eq!(reject(&mut fx, &nomination, &admin).unwrap_err(), vote_error(VoteError::InvalidPayer));
+    reject(&mut fx, &nomination, &squatter).unwrap();
+    assert!(fx.rt.account(&nomination).is_none());
+    assert_eq!(fx.rt.account(&squatter).unwrap().lamports, SOL);
+
+    // The admin registers the name at the same address
+    assert_eq!(fx.add_candidate("bob").unwrap(), nomination);
+    assert_eq!(fx.candidate(1).owner, admin);
+    assert_eq!(fx.candidate(1).status, CandidateStatus::Approved);
+}
+
+#[test]
+fn test_withdrawn_candidates_receive_no_votes() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let (admin, voter) = (fx.admin, fx.register_voter());
+    let (alice, bob) = (fx.candidates[0], fx.candidates[1]);
+
+    let reason = "R".repeat(CandidateAccount::MAX_REASON_LEN + 1);
+    assert_eq!(withdraw(&mut fx, &bob, &admin, &reason).unwrap_err(), vote_error(VoteError::ReasonTooLong));
+    withdraw(&mut fx, &bob, &admin, "Personal reasons").unwrap();
+    let err = withdraw(&mut fx, &bob, &admin, "").unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidateStatus));
+
+    let err = fx.vote(&voter, fx.ballot(&[0, 1], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::CandidateInactive));
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+    assert_eq!(fx.rt.get::<CandidateAccount>(&alice).votes, 1);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{find_config_pda, find_voter_pda};
+
+/// Open commit-reveal election whose reveal window closes `reveal` seconds after voting.
+fn commit_reveal_election(names: &[&str], reveal: i64) -> Fixture {
+    let mut fx = Fixture::pending(2, 0, names);
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetCommitReveal { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetCommitReveal { reveal_ends_at: Some(fx.ends_at + reveal) },
+        ))
+        .unwrap();
+    fx.opened()
+}
+
+fn commit(fx: &Fixture, authority: &Pubkey, commitment: [u8; 32]) -> Instruction {
+    instruction(
+        vote_d_21::accounts::CommitVote {
+            election: fx.election,
+            voter: find_voter_pda(&fx.election, authority).0,
+            config: find_config_pda().0,
+            authority: *authority,
+        },
+        vote_d_21::instruction::CommitVote { commitment },
+    )
+}
+
+fn reveal(fx: &Fixture, authority: &Pubkey, ballot: &Ballot, salt: [u8; 32]) -> Instruction {
+    let mut ix = instruction(
+        vote_d_21::accounts::RevealVote {
+            election: fx.election,
+            voter: find_voter_pda(&fx.election, authority).0,
+            config: find_config_pda().0,
+            authority: *authority,
+        },
+        vote_d_21::instruction::RevealVote { ballot: ballot.clone(), salt },
+    );
+    ix.accounts.extend(ballot.candidates().map(|&key| AccountMeta::new(key, false)));
+    ix
+}
+
+#[test]
+fn test_commit_reveal_ballots_only_in_commit_reveal_elections() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    let ix = commit(&fx, &voter, fx.ballot(&[0], &[]).commitment(&[1; 32]));
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::NotCommitReveal));
+
+    let mut fx = commit_reveal_election(&["alice"], 100);
+    let voter = fx.register_voter();
+    let err = fx.vote(&voter, fx.ballot(&[0], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::CommitRevealRequired));
+}
+
+#[test]
+fn test_reveal_must_match_commitment_in_time() {
+    let mut fx = commit_reveal_election(&["alice", "bob"], 100);
+    let (honest, silent, late) = (fx.register_voter(), fx.register_voter(), fx.register_voter());
+    let ballot = fx.ballot(&[0, 1], &[]);
+    let salt = [7; 32];
+    fx.rt.process(&commit(&fx, &honest, ballot.commitment(&salt))).unwrap();
+    fx.rt.process(&commit(&fx, &late, ballot.commitment(&salt))).unwrap();
+
+    fx.rt.warp(fx.ends_at);
+    let err = fx.rt.process(&reveal(&fx, &silent, &ballot, salt)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::NoCommitment));
+    let err = fx.rt.process(&reveal(&fx, &honest, &ballot, [8; 32])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidReveal));
+    let err = fx.rt.process(&reveal(&fx, &honest, &fx.ballot(&[0], &[]), salt)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidReveal));
+    fx.rt.process(&reveal(&fx, &honest, &ballot, salt)).unwrap();
+    assert_eq!((fx.candidate(0).votes, fx.candidate(1).votes), (1, 1));
+
+    fx.rt.warp(fx.ends_at + 100);
+    let err = fx.rt.process(&reveal(&fx, &late, &ballot, salt)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::RevealEnded));
+    assert_eq!(fx.election().ballots_cast, 1);
+}
@@ -0,0 +1,611 @@
+//! Integration test runtime on a `solana-program-test` bank.
+//!
+//! When `target/deploy/vote_d_21.so` exists (`anchor build`), that compiled
+//! program is deployed through the upgradeable loader and every instruction
+//! runs in the SBF VM. Otherwise the natively compiled `entry` is registered
+//! as a builtin. Either way transactions are signed and pay fees, and the
+//! system, SPL Token and Ed25519 programs are the runtime's own.
+
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Once;
+
+use anchor_lang::__private::base64::prelude::{Engine, BASE64_STANDARD};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::{system_program, AccountDeserialize, Event, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_runtime::stable_log;
+use solana_program_test::{
+    get_runtime_environment_key, invoke_builtin_function, tokio, EbpfError, EbpfVm, InvokeContext, ProgramTest,
+    ProgramTestContext,
+};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{
+    find_candidate_pda, find_config_pda, find_election_pda, find_program_data_pda, find_results_pda, find_voter_pda,
+    BPF_LOADER_UPGRADEABLE_ID,
+};
+
+pub const SOL: u64 = 1_000_000_000;
+
+/// Where `anchor build` leaves the compiled program
+const DEPLOYED_PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/vote_d_21.so");
+
+thread_local! {
+    /// The invoke context of the builtin instruction running on this thread
+    static INVOKE_CONTEXT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Natively, `sol_log_data` has no syscall to reach the transaction logs.
+/// Write it there as the SBF syscall does; everything else goes to the
+/// `solana-program-test` stubs.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+/// Stand-in while the `solana-program-test` stubs are being wrapped.
+struct NoStubs;
+
+impl SyscallStubs for NoStubs {}
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_sysvar(&self, sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64 {
+        self.0.sol_get_sysvar(sysvar_id_addr, var_addr, offset, length)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+
+    fn sol_get_epoch_stake(&self, vote_address: *const u8) -> u64 {
+        self.0.sol_get_epoch_stake(vote_address)
+    }
+
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        match INVOKE_CONTEXT.get() {
+            0 => self.0.sol_log_data(fields),
+            pointer => {
+                let invoke_context = unsafe { &*(pointer as *const InvokeContext) };
+                stable_log::program_data(&invoke_context.get_log_collector(), fields);
+            }
+        }
+    }
+
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// The native program, run the way `solana_program_test::processor!` does,
+/// keeping the invoke context at hand for `sol_log_data`.
+fn native_program(vm: *mut EbpfVm<InvokeContext<'static>>, _: u64, _: u64, _: u64, _: u64, _: u64) {
+    let vm = unsafe {
+        &mut *((vm as *mut u64).offset(-(get_runtime_environment_key() as isize)) as *mut EbpfVm<InvokeContext>)
+    };
+    INVOKE_CONTEXT.set(vm.context_object_pointer as *mut InvokeContext as usize);
+    vm.program_result = invoke_builtin_function(native_entry, vm.context_object_pointer)
+        .map_err(EbpfError::SyscallError)
+        .into();
+    INVOKE_CONTEXT.set(0);
+}
+
+/// Anchor's `entry` ties the accounts to one lifetime. A builtin's own
+/// account is owned by the native loader, so it is shown to the program as
+/// the upgradeable loader account it is once deployed.
+fn native_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts: &mut [AccountInfo] = Box::leak(accounts.to_vec().into_boxed_slice());
+    for account in accounts.iter_mut().filter(|account| account.key == program_id) {
+        account.owner = &BPF_LOADER_UPGRADEABLE_ID;
+        account.data = std::rc::Rc::new(std::cell::RefCell::new(Box::leak(program_account_data().into_boxed_slice())));
+    }
+    vote_d_21::entry(program_id, accounts, data)
+}
+
+/// Upgradeable loader state is bincode: a u32 variant tag, then the
+/// program data address.
+fn program_account_data() -> Vec<u8> {
+    [&2u32.to_le_bytes()[..], find_program_data_pda().0.as_ref()].concat()
+}
+
+/// Program data: the tag, the deploy slot, the upgrade authority as an
+/// option, then the ELF after 45 bytes of metadata.
+fn program_data_account(upgrade_authority: &Pubkey, elf: &[u8]) -> Account {
+    let mut data = [&3u32.to_le_bytes()[..], &0u64.to_le_bytes(), &[1], upgrade_authority.as_ref()].concat();
+    data.extend_from_slice(elf);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// A bank with the program deployed, and the keys that sign for tests.
+pub struct TestRuntime {
+    runtime: tokio::runtime::Runtime,
+    context: ProgramTestContext,
+    keypairs: HashMap<Pubkey, Keypair>,
+    clock: Clock,
+    signatures: HashSet<Signature>,
+    events: Vec<Vec<u8>>,
+    /// Upgrade authority of the deployed program
+    pub upgrade_authority: Pubkey,
+}
+
+impl TestRuntime {
+    pub fn new() -> Self {
+        let upgrade_authority = Keypair::new();
+        let mut program_test = ProgramTest::default();
+        let deployed = std::fs::read(DEPLOYED_PROGRAM).ok();
+        match &deployed {
+            Some(elf) => {
+                let program = Account {
+                    lamports: Rent::default().minimum_balance(36),
+                    data: program_account_data(),
+                    owner: BPF_LOADER_UPGRADEABLE_ID,
+                    executable: true,
+                    rent_epoch: 0,
+                };
+                program_test.add_genesis_account(vote_d_21::ID, program);
+                let program_data = program_data_account(&upgrade_authority.pubkey(), elf);
+                program_test.add_genesis_account(find_program_data_pda().0, program_data);
+            }
+            None => program_test.add_builtin_program("vote_d_21", vote_d_21::ID, native_program),
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let mut context = runtime.block_on(program_test.start_with_context());
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            let stubs = set_syscall_stubs(Box::new(NoStubs));
+            set_syscall_stubs(Box::new(LogDataStubs(stubs)));
+        });
+        if deployed.is_none() {
+            let program_data = program_data_account(&upgrade_authority.pubkey(), &[]);
+            context.set_account(&find_program_data_pda().0, &program_data.into());
+        }
+
+        let clock = Clock { unix_timestamp: 1_000, ..runtime.block_on(context.banks_client.get_sysvar()).unwrap() };
+        context.set_sysvar(&clock);
+        let mut rt = Self {
+            runtime,
+            context,
+            keypairs: HashMap::new(),
+            clock,
+            signatures: HashSet::new(),
+            events: Vec::new(),
+            upgrade_authority: upgrade_authority.pubkey(),
+        };
+        rt.keypairs.insert(upgrade_authority.pubkey(), upgrade_authority);
+        rt
+    }
+
+    /// A new key this runtime signs for.
+    pub fn signer(&mut self) -> Pubkey {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        self.keypairs.insert(pubkey, keypair);
+        pubkey
+    }
+
+    pub fn keypair(&self, pubkey: &Pubkey) -> &Keypair {
+        self.keypairs.get(pubkey).unwrap_or_else(|| panic!("no keypair for {pubkey}"))
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    /// Move the clock to `unix_timestamp`.
+    pub fn warp(&mut self, unix_timestamp: i64) {
+        self.clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&self.clock);
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let mut account = self.account(key).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(key, account);
+    }
+
+    pub fn set_account(&mut self, key: &Pubkey, account: Account) {
+        self.context.set_account(key, &AccountSharedData::from(account));
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        let banks_client = self.context.banks_client.clone();
+        self.runtime.block_on(banks_client.get_account(*key)).unwrap()
+    }
+
+    /// A new SPL Token mint.
+    pub fn create_mint(&mut self, authority: &Pubkey) -> Pubkey {
+        let mint = spl_token::state::Mint {
+            mint_authority: Some(*authority).into(),
+            supply: 0,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        self.create_token_state(mint)
+    }
+
+    /// A new token account of `mint` holding `amount`, owned by `owner`.
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        self.create_token_state(account)
+    }
+
+    fn create_token_state<T: Pack>(&mut self, state: T) -> Pubkey {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        let key = Pubkey::new_unique();
+        let account = Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.set_account(&key, account);
+        key
+    }
+
+    /// Token balance of an SPL Token account.
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = self.account(key).unwrap_or_else(|| panic!("account {key} does not exist"));
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    /// Deserialize a program account, panicking if it is missing or invalid.
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).unwrap_or_else(|| panic!("account {key} does not exist"));
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    /// Events of type `T` emitted by successful transactions, oldest first.
+    pub fn events<T: Event + AnchorDeserialize>(&self) -> Vec<T> {
+        self.events
+            .iter()
+            .filter(|data| data.starts_with(T::DISCRIMINATOR))
+            .map(|data| T::try_from_slice(&data[T::DISCRIMINATOR.len()..]).unwrap())
+            .collect()
+    }
+
+    /// Send `instruction` in its own transaction.
+    pub fn process(&mut self, instruction: &Instruction) -> std::result::Result<(), ProgramError> {
+        self.process_transaction(std::slice::from_ref(instruction))
+    }
+
+    /// Send `instructions` as one transaction, signed by every key their
+    /// metas mark as signer. A failing instruction comes back as the
+    /// program error it returned; other transaction errors panic.
+    pub fn process_transaction(&mut self, instructions: &[Instruction]) -> std::result::Result<(), ProgramError> {
+        let mut transaction = self.sign(instructions);
+        // Resending an identical transaction needs a new blockhash
+        if !self.signatures.insert(transaction.signatures[0]) {
+            let slot = self.clock.slot + 1;
+            self.context.warp_to_slot(slot).unwrap();
+            self.clock.slot = slot;
+            self.context.set_sysvar(&self.clock);
+            transaction = self.sign(instructions);
+            self.signatures.insert(transaction.signatures[0]);
+        }
+
+        let banks_client = self.context.banks_client.clone();
+        let processed = self.runtime.block_on(banks_client.process_transaction_with_metadata(transaction)).unwrap();
+        match processed.result {
+            Ok(()) => {
+                let logs = processed.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+                let events = logs.iter().filter_map(|log| log.strip_prefix("Program data: ")).map(|data| {
+                    data.split(' ')
+                        .flat_map(|field| BASE64_STANDARD.decode(field).unwrap())
+                        .collect()
+                });
+                self.events.extend(events);
+                Ok(())
+            }
+            Err(TransactionError::InstructionError(_, error)) => {
+                Err(ProgramError::try_from(error.clone()).unwrap_or_else(|_| panic!("instruction failed: {error}")))
+            }
+            Err(error) => panic!("transaction failed: {error}"),
+        }
+    }
+
+    fn sign(&self, instructions: &[Instruction]) -> Transaction {
+        let payer = &self.context.payer;
+        let mut signers: Vec<&Keypair> = vec![payer];
+        for meta in instructions.iter().flat_map(|ix| &ix.accounts).filter(|meta| meta.is_signer) {
+            if !signers.iter().any(|signer| signer.pubkey() == meta.pubkey) {
+                signers.push(self.keypair(&meta.pubkey));
+            }
+        }
+        Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &signers, self.context.last_blockhash)
+    }
+}
+
+/// Build a `vote_d_21` instruction from Anchor's generated account and argument types.
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vote_d_21::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn vote_error(error: VoteError) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+pub fn anchor_error(error: anchor_lang::error::ErrorCode) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+/// An open D21 election with registered candidates.
+pub struct Fixture {
+    pub rt: TestRuntime,
+    /// Emergency authority of the program config
+    pub emergency: Pubkey,
+    pub admin: Pubkey,
+    pub election: Pubkey,
+    pub candidates: Vec<Pubkey>,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+impl Fixture {
+    /// Election allowing `positive_votes` and `negative_votes` with the named
+    /// candidates, opened at `starts_at`.
+    pub fn new(positive_votes: u8, negative_votes: u8, names: &[&str]) -> Self {
+        let fixture = Self::pending(positive_votes, negative_votes, names);
+        fixture.opened()
+    }
+
+    /// Same as `new`, but the election is not opened yet.
+    pub fn pending(positive_votes: u8, negative_votes: u8, names: &[&str]) -> Self {
+        let mut rt = TestRuntime::new();
+        let (emergency, admin) = (rt.signer(), rt.signer());
+        let upgrade_authority = rt.upgrade_authority;
+        rt.airdrop(&upgrade_authority, SOL);
+        rt.airdrop(&admin, 100 * SOL);
+        rt.process(&vote_d_21_client::instructions::initialize_config(&upgrade_authority, &emergency))
+            .unwrap();
+
+        let (starts_at, ends_at) = (rt.now() + 100, rt.now() + 1_000);
+        let election = find_election_pda(&admin, 1).0;
+        rt.process(&instruction(
+            vote_d_21::accounts::InitializeElection {
+                election,
+                creator: admin,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::InitializeElection {
+                election_id: 1,
+                title: String::from("Board"),
+                starts_at,
+                ends_at,
+                positive_votes,
+                negative_votes,
+            },
+        ))
+        .unwrap();
+
+        let mut fixture = Self { rt, emergency, admin, election, candidates: Vec::new(), starts_at, ends_at };
+        for name in names {
+            fixture.add_candidate(name).unwrap();
+        }
+        fixture
+    }
+
+    pub fn opened(mut self) -> Self {
+        self.rt.warp(self.starts_at);
+        self.open().unwrap();
+        self
+    }
+
+    pub fn open(&mut self) -> std::result::Result<(), ProgramError> {
+        self.rt.process(&instruction(
+            vote_d_21::accounts::OpenElection { election: self.election },
+            vote_d_21::instruction::OpenElection {},
+        ))
+    }
+
+    pub fn add_candidate(&mut self, name: &str) -> std::result::Result<Pubkey, ProgramError> {
+        let admin = self.admin;
+        self.add_candidate_as(&admin, name)
+    }
+
+    pub fn add_candidate_as(&mut self, authority: &Pubkey, name: &str) -> std::result::Result<Pubkey, ProgramError> {
+        let ix = vote_d_21_client::instructions::initialize_candidate(
+            &self.election,
+            authority,
+            &self.admin,
+            name,
+            "",
+            "",
+        );
+        self.rt.process(&ix)?;
+        let candidate = find_candidate_pda(&self.election, name).0;
+        self.candidates.push(candidate);
+        Ok(candidate)
+    }
+
+    /// Register a new funded voter and return their authority.
+    pub fn register_voter(&mut self) -> Pubkey {
+        let authority = self.rt.signer();
+        self.register_voter_as(&authority);
+        authority
+    }
+
+    /// Register `authority` as a funded voter.
+    pub fn register_voter_as(&mut self, authority: &Pubkey) {
+        self.rt.airdrop(authority, SOL);
+        let ix = vote_d_21_client::instructions::initialize_voter(
+            &self.election,
+            authority,
+            authority,
+            Default::default(),
+        );
+        self.rt.process(&ix).unwrap();
+    }
+
+    pub fn voter(&self, authority: &Pubkey) -> VoterAccount {
+        self.rt.get(&find_voter_pda(&self.election, authority).0)
+    }
+
+    pub fn candidate(&self, index: usize) -> CandidateAccount {
+        self.rt.get(&self.candidates[index])
+    }
+
+    pub fn election(&self) -> Election {
+        self.rt.get(&self.election)
+    }
+
+    /// Positive and negative candidate indices as a ballot.
+    pub fn ballot(&self, positive: &[usize], negative: &[usize]) -> Ballot {
+        Ballot {
+            positive: positive.iter().map(|&i| self.candidates[i]).collect(),
+            negative: negative.iter().map(|&i| self.candidates[i]).collect(),
+            scores: Vec::new(),
+        }
+    }
+
+    pub fn vote(&mut self, authority: &Pubkey, ballot: Ballot) -> std::result::Result<(), ProgramError> {
+        let ix = vote_d_21_client::instructions::vote(&self.election, authority, ballot);
+        self.rt.process(&ix)
+    }
+
+    /// Replace a cast ballot.
+    pub fn change_vote(&mut self, authority: &Pubkey, ballot: Ballot) -> std::result::Result<(), ProgramError> {
+        let keys = self.voter(authority).ballot().union(&ballot);
+        let mut ix = instruction(
+            vote_d_21::accounts::ChangeVote {
+                election: self.election,
+                voter: find_voter_pda(&self.election, authority).0,
+                config: find_config_pda().0,
+                authority: *authority,
+            },
+            vote_d_21::instruction::ChangeVote { ballot },
+        );
+        ix.accounts.extend(keys.iter().map(|key| AccountMeta::new(*key, false)));
+        self.rt.process(&ix)
+    }
+
+    /// Withdraw a cast ballot.
+    pub fn revoke_vote(&mut self, authority: &Pubkey) -> std::result::Result<(), ProgramError> {
+        let ballot = self.voter(authority).ballot();
+        let mut ix = instruction(
+            vote_d_21::accounts::RevokeVote {
+                election: self.election,
+                voter: find_voter_pda(&self.election, authority).0,
+                config: find_config_pda().0,
+                authority: *authority,
+            },
+            vote_d_21::instruction::RevokeVote {},
+        );
+        ix.accounts.extend(ballot.candidates().map(|key| AccountMeta::new(*key, false)));
+        self.rt.process(&ix)
+    }
+
+    /// Finalize after the voting window, passing every candidate.
+    pub fn finalize(&mut self) -> std::result::Result<(), ProgramError> {
+        let mut ix = instruction(
+            vote_d_21::accounts::FinalizeElection {
+                election: self.election,
+                results: find_results_pda(&self.election).0,
+                runoff: None,
+                payer: self.admin,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::FinalizeElection {},
+        );
+        ix.accounts.extend(self.candidates.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+        self.rt.process(&ix)
+    }
+
+    pub fn results(&self) -> Results {
+        self.rt.get(&find_results_pda(&self.election).0)
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{find_config_pda, find_voter_pda};
+
+/// Delegate `authority`'s ballot along `chain`, given as voter authorities.
+fn delegate(fx: &Fixture, authority: &Pubkey, chain: &[Pubkey]) -> Instruction {
+    let mut ix = instruction(
+        vote_d_21::accounts::Delegate {
+            election: fx.election,
+            voter: find_voter_pda(&fx.election, authority).0,
+            config: find_config_pda().0,
+            authority: *authority,
+        },
+        vote_d_21::instruction::Delegate {},
+    );
+    ix.accounts.extend(chain_accounts(fx, chain));
+    ix
+}
+
+fn undelegate(fx: &Fixture, authority: &Pubkey, chain: &[Pubkey]) -> Instruction {
+    let mut ix = instruction(
+        vote_d_21::accounts::Undelegate {
+            election: fx.election,
+            voter: find_voter_pda(&fx.election, authority).0,
+            config: find_config_pda().0,
+            authority: *authority,
+        },
+        vote_d_21::instruction::Undelegate {},
+    );
+    ix.accounts.extend(chain_accounts(fx, chain));
+    ix
+}
+
+fn chain_accounts(fx: &Fixture, chain: &[Pubkey]) -> Vec<AccountMeta> {
+    chain
+        .iter()
+        .map(|authority| AccountMeta::new(find_voter_pda(&fx.election, authority).0, false))
+        .collect()
+}
+
+#[test]
+fn test_delegated_ballot_is_cast_by_representative() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let (a, b, c) = (fx.register_voter(), fx.register_voter(), fx.register_voter());
+
+    let err = fx.rt.process(&delegate(&fx, &a, &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidDelegationChain));
+    let err = fx.rt.process(&delegate(&fx, &a, &[b, c])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidDelegationChain));
+    fx.rt.process(&delegate(&fx, &a, &[b])).unwrap();
+    assert_eq!(fx.rt.process(&delegate(&fx, &a, &[c])).unwrap_err(), vote_error(VoteError::AlreadyDelegated));
+    assert_eq!(fx.rt.process(&delegate(&fx, &b, &[a])).unwrap_err(), vote_error(VoteError::DelegationCycle));
+    assert_eq!(fx.rt.process(&undelegate(&fx, &c, &[b])).unwrap_err(), vote_error(VoteError::NotDelegated));
+    assert_eq!(fx.voter(&b).delegated_weight, 1);
+
+    assert_eq!(fx.vote(&a, fx.ballot(&[0], &[])).unwrap_err(), vote_error(VoteError::VoterDelegated));
+    fx.vote(&b, fx.ballot(&[0], &[])).unwrap();
+    assert_eq!(fx.candidate(0).votes, 2);
+
+    // The weight has been counted, so it can neither move nor come back
+    let err = fx.rt.process(&undelegate(&fx, &a, &[b])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::DelegateAlreadyVoted));
+    let err = fx.rt.process(&delegate(&fx, &c, &[b])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::DelegateAlreadyVoted));
+}
+
+#[test]
+fn test_delegation_chains_are_bounded() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let voters: Vec<Pubkey> = (0..=VoterAccount::MAX_DELEGATION_DEPTH + 1).map(|_| fx.register_voter()).collect();
+
+    // Each voter delegates to the next, from the end of the line
+    for i in (1..=VoterAccount::MAX_DELEGATION_DEPTH).rev() {
+        fx.rt.process(&delegate(&fx, &voters[i], &voters[i + 1..])).unwrap();
+    }
+    let err = fx.rt.process(&delegate(&fx, &voters[0], &voters[1..])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::DelegationTooDeep));
+
+    let representative = voters.last().unwrap();
+    assert_eq!(fx.voter(representative).delegated_weight, VoterAccount::MAX_DELEGATION_DEPTH as u64);
+    fx.rt.process(&undelegate(&fx, &voters[1], &voters[2..])).unwrap();
+    assert_eq!(fx.voter(representative).delegated_weight, VoterAccount::MAX_DELEGATION_DEPTH as u64 - 1);
+}
@@ -0,0 +1,367 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::instructions::{initialize_candidate, initialize_voter, Eligibility};
+use vote_d_21_client::pda::{find_election_pda, find_results_pda, find_voter_pda, find_whitelist_pda};
+
+fn initialize_election(
+    rt: &mut TestRuntime,
+    creator: &Pubkey,
+    title: &str,
+    starts_at: i64,
+    ends_at: i64,
+    positive_votes: u8,
+    negative_votes: u8,
+) -> std::result::Result<(), ProgramError> {
+    rt.process(&instruction(
+        vote_d_21::accounts::InitializeElection {
+            election: find_election_pda(creator, 7).0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        vote_d_21::instruction::InitializeElection {
+            election_id: 7,
+            title: title.to_string(),
+            starts_at,
+            ends_at,
+            positive_votes,
+            negative_votes,
+        },
+    ))
+}
+
+#[test]
+fn test_initialize_election_validates_config() {
+    let mut rt = TestRuntime::new();
+    let creator = rt.signer();
+    rt.airdrop(&creator, 10 * SOL);
+    let now = rt.now();
+
+    let long_title = "x".repeat(Election::MAX_TITLE_LEN + 1);
+    let cases = [
+        (long_title.as_str(), now, now + 10, 2, 1, VoteError::TitleTooLong),
+        ("Board", now + 10, now + 10, 2, 1, VoteError::InvalidVotingWindow),
+        ("Board", now + 10, now + 5, 2, 1, VoteError::InvalidVotingWindow),
+        ("Board", now - 20, now - 10, 2, 1, VoteError::InvalidVotingWindow),
+        ("Board", now, now + 10, 0, 0, VoteError::InvalidVoteConfig),
+        ("Board", now, now + 10, 2, 2, VoteError::InvalidVoteConfig),
+    ];
+    for (title, starts_at, ends_at, positive, negative, error) in cases {
+        let err = initialize_election(&mut rt, &creator, title, starts_at, ends_at, positive, negative).unwrap_err();
+        assert_eq!(err, vote_error(error));
+    }
+
+    let max_title = "x".repeat(Election::MAX_TITLE_LEN);
+    initialize_election(&mut rt, &creator, &max_title, now, now + 10, 2, 1).unwrap();
+    let election: Election = rt.get(&find_election_pda(&creator, 7).0);
+    assert_eq!(election.title, max_title);
+    assert_eq!(election.admin, creator);
+    assert_eq!(election.status, ElectionStatus::Pending);
+    assert_eq!(rt.events::<ElectionCreated>().len(), 1);
+}
+
+#[test]
+fn test_candidate_registration() {
+    let mut fx = Fixture::pending(2, 1, &["alice"]);
+    assert_eq!(fx.election().candidates, fx.candidates);
+    assert_eq!(fx.candidate(0).name, "alice");
+
+    // Candidate accounts are keyed by name, so a name registers once
+    assert!(fx.add_candidate("alice").is_err());
+
+    let stranger = fx.rt.signer();
+    let err = fx.add_candidate_as(&stranger, "bob").unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::AddRegistrar { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::AddRegistrar { registrar: stranger },
+        ))
+        .unwrap();
+    fx.add_candidate_as(&stranger, "bob").unwrap();
+    assert_eq!(fx.election().candidates.len(), 2);
+}
+
+#[test]
+fn test_candidate_names_and_limits() {
+    let mut fx = Fixture::pending(2, 1, &[]);
+    let long_name = "x".repeat(CandidateAccount::MAX_NAME_LEN + 1);
+    for (name, error) in [
+        ("   ", VoteError::NameEmpty),
+        (long_name.as_str(), VoteError::NameTooLong),
+        ("line\nbreak", VoteError::InvalidNameCharacters),
+    ] {
+        assert_eq!(fx.add_candidate(name).unwrap_err(), vote_error(error));
+    }
+
+    let (election, admin) = (fx.election, fx.admin);
+    let description = "D".repeat(CandidateAccount::MAX_DESCRIPTION_LEN + 1);
+    let ix = initialize_candidate(&election, &admin, &admin, "alice", &description, "");
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::DescriptionTooLong));
+    let uri = "U".repeat(CandidateAccount::MAX_URI_LEN + 1);
+    let ix = initialize_candidate(&election, &admin, &admin, "alice", "", &uri);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::UriTooLong));
+
+    for i in 0..Election::MAX_CANDIDATES {
+        fx.add_candidate(&format!("candidate {i}")).unwrap();
+    }
+    assert_eq!(fx.add_candidate("one too many").unwrap_err(), vote_error(VoteError::TooManyCandidates));
+}
+
+#[test]
+fn test_registrar_allow_list() {
+    let mut fx = Fixture::pending(2, 1, &["alice"]);
+    let add = |fx: &mut Fixture, admin, registrar| {
+        fx.rt.process(&instruction(
+            vote_d_21::accounts::AddRegistrar { election: fx.election, admin },
+            vote_d_21::instruction::AddRegistrar { registrar },
+        ))
+    };
+    let remove = |fx: &mut Fixture, admin, registrar| {
+        fx.rt.process(&instruction(
+            vote_d_21::accounts::RemoveRegistrar { election: fx.election, admin },
+            vote_d_21::instruction::RemoveRegistrar { registrar },
+        ))
+    };
+
+    // Only the admin manages registrars
+    let (admin, stranger) = (fx.admin, fx.rt.signer());
+    assert_eq!(add(&mut fx, stranger, stranger).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+    add(&mut fx, admin, stranger).unwrap();
+    assert_eq!(remove(&mut fx, stranger, stranger).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+
+    // The admin is a registrar already
+    assert_eq!(add(&mut fx, admin, admin).unwrap_err(), vote_error(VoteError::RegistrarAlreadyExists));
+
+    let registrars: Vec<Pubkey> = (1..Election::MAX_REGISTRARS).map(|_| Pubkey::new_unique()).collect();
+    for registrar in &registrars {
+        add(&mut fx, admin, *registrar).unwrap();
+    }
+    let err = add(&mut fx, admin, registrars[0]).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::RegistrarAlreadyExists));
+    let err = add(&mut fx, admin, Pubkey::new_unique()).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::TooManyRegistrars));
+
+    let err = remove(&mut fx, admin, Pubkey::new_unique()).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::RegistrarNotFound));
+    fx.add_candidate_as(&stranger, "bob").unwrap();
+    remove(&mut fx, admin, stranger).unwrap();
+    let err = remove(&mut fx, admin, stranger).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::RegistrarNotFound));
+    assert_eq!(fx.election().registrars.len(), Election::MAX_REGISTRARS - 1);
+
+    // A removed registrar can no longer add candidates
+    let err = fx.add_candidate_as(&stranger, "carol").unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+}
+
+#[test]
+fn test_whitelist_eligibility() {
+    let mut fx = Fixture::pending(2, 1, &["alice"]);
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetEligibility { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetEligibility { mode: EligibilityMode::Whitelist },
+        ))
+        .unwrap();
+    let mut fx = fx.opened();
+    let voter = fx.rt.signer();
+    fx.rt.airdrop(&voter, SOL);
+
+    let ix = initialize_voter(&fx.election, &voter, &voter, Eligibility::default());
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::IneligibleVoter));
+
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::AddToWhitelist {
+                election: fx.election,
+                whitelist_entry: find_whitelist_pda(&fx.election, &voter).0,
+                admin: fx.admin,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::AddToWhitelist { voter },
+        ))
+        .unwrap();
+    let eligibility = Eligibility { whitelisted: true, ..Default::default() };
+    fx.rt.process(&initialize_voter(&fx.election, &voter, &voter, eligibility)).unwrap();
+    assert_eq!(fx.election().registered_voters, 1);
+}
+
+#[test]
+fn test_eligibility_fixed_once_voters_register() {
+    let mut fx = Fixture::pending(2, 1, &["alice"]);
+    let set_whitelist = instruction(
+        vote_d_21::accounts::SetEligibility { election: fx.election, admin: fx.admin },
+        vote_d_21::instruction::SetEligibility { mode: EligibilityMode::Whitelist },
+    );
+    let voter = fx.register_voter();
+
+    // The open-registration voter would otherwise keep a place on a whitelist election
+    assert_eq!(fx.rt.process(&set_whitelist).unwrap_err(), vote_error(VoteError::VotersAlreadyRegistered));
+    assert_eq!(fx.election().eligibility, EligibilityMode::Open);
+    assert_eq!(fx.voter(&voter).authority, voter);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let mut fx = Fixture::pending(2, 1, &["alice"]);
+    let (successor, stranger) = (fx.rt.signer(), fx.rt.signer());
+    let transfer = |admin: Pubkey, new_admin: Pubkey| {
+        instruction(
+            vote_d_21::accounts::TransferAdmin { election: fx.election, admin },
+            vote_d_21::instruction::TransferAdmin { new_admin },
+        )
+    };
+    let accept = |new_admin: Pubkey| {
+        instruction(
+            vote_d_21::accounts::AcceptAdmin { election: fx.election, new_admin },
+            vote_d_21::instruction::AcceptAdmin {},
+        )
+    };
+    let (nominate, accept_successor) = (transfer(fx.admin, successor), accept(successor));
+    let (hijack, accept_stranger) = (transfer(stranger, stranger), accept(stranger));
+
+    assert_eq!(fx.rt.process(&accept_successor).unwrap_err(), vote_error(VoteError::NoPendingAdmin));
+    assert_eq!(fx.rt.process(&hijack).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+
+    fx.rt.process(&nominate).unwrap();
+    assert_eq!(fx.election().pending_admin, Some(successor));
+    assert_eq!(fx.rt.events::<AdminTransferStarted>()[0].pending_admin, successor);
+    // Only the nominee can complete the transfer
+    assert_eq!(fx.rt.process(&accept_stranger).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+    assert_eq!(fx.election().admin, fx.admin);
+
+    fx.rt.process(&accept_successor).unwrap();
+    let election = fx.election();
+    assert_eq!((election.admin, election.pending_admin), (successor, None));
+    assert_eq!(election.creator, fx.admin);
+    let events = fx.rt.events::<AdminTransferred>();
+    assert_eq!((events[0].previous_admin, events[0].new_admin), (fx.admin, successor));
+
+    // The previous admin lost the role, and the nomination is spent
+    assert_eq!(fx.rt.process(&nominate).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+    assert_eq!(fx.rt.process(&accept_successor).unwrap_err(), vote_error(VoteError::NoPendingAdmin));
+}
+
+#[test]
+fn test_finalize_ranks_candidates() {
+    let mut fx = Fixture::new(2, 1, &["alice", "bob", "carol"]);
+    for (positive, negative) in [(&[0, 1][..], &[2][..]), (&[0, 2], &[]), (&[0], &[])] {
+        let voter = fx.register_voter();
+        fx.vote(&voter, fx.ballot(positive, negative)).unwrap();
+    }
+
+    assert_eq!(fx.finalize().unwrap_err(), vote_error(VoteError::VotingNotEnded));
+    fx.rt.warp(fx.ends_at);
+    fx.finalize().unwrap();
+
+    let results = fx.results();
+    assert_eq!(fx.election().status, ElectionStatus::Finalized);
+    assert_eq!(results.winners, vec![fx.candidates[0]]);
+    assert!(!results.is_tie);
+    let order: Vec<Pubkey> = results.entries.iter().map(|entry| entry.candidate).collect();
+    assert_eq!(order, vec![fx.candidates[0], fx.candidates[1], fx.candidates[2]]);
+
+    let events = fx.rt.events::<ElectionFinalized>();
+    assert_eq!(events[0].ballots_cast, 3);
+    assert_eq!(events[0].registered_voters, 3);
+    assert!(events[0].quorum_reached);
+
+    // The results account can only be created once
+    assert!(fx.finalize().is_err());
+}
+
+#[test]
+fn test_finalize_requires_every_candidate() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    fx.rt.warp(fx.ends_at);
+
+    let missing = fx.candidates.pop().unwrap();
+    assert_eq!(fx.finalize().unwrap_err(), vote_error(VoteError::InvalidCandidate));
+    fx.candidates.insert(0, missing);
+    assert_eq!(fx.finalize().unwrap_err(), vote_error(VoteError::InvalidCandidate));
+}
+
+#[test]
+fn test_quorum_not_reached_invalidates_election() {
+    let mut fx = Fixture::pending(1, 0, &["alice", "bob"]);
+    let set_quorum = |fx: &mut Fixture, quorum_bps| {
+        fx.rt.process(&instruction(
+            vote_d_21::accounts::SetQuorum { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetQuorum { quorum_bps },
+        ))
+    };
+    assert_eq!(set_quorum(&mut fx, Some(10_001)).unwrap_err(), vote_error(VoteError::InvalidQuorum));
+    set_quorum(&mut fx, Some(5_000)).unwrap();
+
+    let mut fx = fx.opened();
+    assert_eq!(set_quorum(&mut fx, None).unwrap_err(), vote_error(VoteError::ConfigurationLocked));
+    let voter = fx.register_voter();
+    fx.register_voter();
+    fx.register_voter();
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+
+    fx.rt.warp(fx.ends_at);
+    fx.finalize().unwrap();
+    assert_eq!(fx.election().status, ElectionStatus::Invalid);
+    assert!(fx.results().winners.is_empty());
+    assert_eq!(fx.results().entries[0].votes, 1);
+    assert!(!fx.rt.events::<ElectionFinalized>()[0].quorum_reached);
+}
+
+#[test]
+fn test_finalized_election_only_closes_accounts() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+    fx.rt.warp(fx.ends_at);
+    fx.finalize().unwrap();
+
+    let disqualify = instruction(
+        vote_d_21::accounts::DisqualifyCandidate { election: fx.election, candidate: fx.candidates[0], admin: fx.admin },
+        vote_d_21::instruction::DisqualifyCandidate { reason: String::new() },
+    );
+    assert_eq!(fx.rt.process(&disqualify).unwrap_err(), vote_error(VoteError::ElectionAlreadyFinalized));
+
+    // Rent only goes back to whoever paid it
+    let (results, stranger) = (find_results_pda(&fx.election).0, fx.rt.signer());
+    let close_candidate = |payer| {
+        instruction(
+            vote_d_21::accounts::CloseCandidate {
+                election: fx.election,
+                results,
+                candidate: fx.candidates[0],
+                admin: fx.admin,
+                payer,
+            },
+            vote_d_21::instruction::CloseCandidate {},
+        )
+    };
+    let close_voter = |payer| {
+        instruction(
+            vote_d_21::accounts::CloseVoter {
+                election: fx.election,
+                results,
+                voter: find_voter_pda(&fx.election, &voter).0,
+                authority: voter,
+                payer,
+            },
+            vote_d_21::instruction::CloseVoter {},
+        )
+    };
+    let (candidate_by_stranger, candidate_by_admin) = (close_candidate(stranger), close_candidate(fx.admin));
+    let (voter_by_stranger, voter_by_voter) = (close_voter(stranger), close_voter(voter));
+
+    assert_eq!(fx.rt.process(&candidate_by_stranger).unwrap_err(), vote_error(VoteError::InvalidPayer));
+    assert_eq!(fx.rt.process(&voter_by_stranger).unwrap_err(), vote_error(VoteError::InvalidPayer));
+    fx.rt.process(&candidate_by_admin).unwrap();
+    fx.rt.process(&voter_by_voter).unwrap();
+    assert!(fx.rt.account(&fx.candidates[0]).is_none());
+    assert!(fx.rt.account(&find_voter_pda(&fx.election, &voter).0).is_none());
+}
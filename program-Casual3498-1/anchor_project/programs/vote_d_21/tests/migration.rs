@@ -0,0 +1,152 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use solana_sdk::account::Account;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::instructions::{migrate_candidate, migrate_voter};
+use vote_d_21_client::pda::{find_candidate_pda, find_legacy_candidate_pda, find_legacy_voter_pda, find_voter_pda};
+
+/// Store `data` at `key` the way the first deployment allocated it.
+fn set_deployed(rt: &mut TestRuntime, key: &Pubkey, mut data: Vec<u8>, space: usize) {
+    data.resize(space, 0);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: vote_d_21::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    rt.set_account(key, account);
+}
+
+/// A candidate account byte for byte as the first deployment wrote it.
+fn deploy_candidate(rt: &mut TestRuntime, name: &str, votes: u64) -> Pubkey {
+    let (key, bump) = find_legacy_candidate_pda(name);
+    let mut data = CandidateAccountV0::DISCRIMINATOR.to_vec();
+    data.extend((name.len() as u32).to_le_bytes());
+    data.extend(name.as_bytes());
+    data.extend(votes.to_le_bytes());
+    data.push(bump);
+    set_deployed(rt, &key, data, 53);
+    key
+}
+
+/// A voter account byte for byte as the first deployment wrote it.
+fn deploy_voter(rt: &mut TestRuntime, authority: &Pubkey, votes: Option<[Pubkey; 2]>) -> Pubkey {
+    let (key, bump) = find_legacy_voter_pda(authority);
+    let mut data = VoterAccountV0::DISCRIMINATOR.to_vec();
+    data.extend(authority.to_bytes());
+    data.push(votes.is_some() as u8);
+    for vote in votes.unwrap_or_default() {
+        data.extend(vote.to_bytes());
+    }
+    data.push(bump);
+    set_deployed(rt, &key, data, 106);
+    key
+}
+
+#[test]
+fn test_deployed_accounts_move_into_election() {
+    let mut fx = Fixture::new(2, 0, &[]);
+    let (admin, election) = (fx.admin, fx.election);
+    let (alice, bob) = (deploy_candidate(&mut fx.rt, "Alice", 1), deploy_candidate(&mut fx.rt, "Bob", 1));
+    let (voted, silent) = (fx.rt.signer(), fx.rt.signer());
+    let legacy_voted = deploy_voter(&mut fx.rt, &voted, Some([alice, bob]));
+    let legacy_silent = deploy_voter(&mut fx.rt, &silent, None);
+
+    let admin_lamports = fx.rt.account(&admin).unwrap().lamports;
+    for name in ["Alice", "Bob"] {
+        fx.rt.process(&migrate_candidate(&election, &admin, name)).unwrap();
+        fx.candidates.push(find_candidate_pda(&election, name).0);
+    }
+    let candidates = fx.candidates.clone();
+    fx.rt.process(&migrate_voter(&election, &admin, &voted, &candidates)).unwrap();
+    fx.rt.process(&migrate_voter(&election, &admin, &silent, &[])).unwrap();
+
+    // The legacy accounts are closed, their rent going to the admin
+    for legacy in [alice, bob, legacy_voted, legacy_silent] {
+        assert!(fx.rt.account(&legacy).is_none());
+    }
+    let refunded = 2 * Rent::default().minimum_balance(53) + 2 * Rent::default().minimum_balance(106);
+    let created = 2 * Rent::default().minimum_balance(CandidateAccount::SPACE)
+        + 2 * Rent::default().minimum_balance(VoterAccount::SPACE);
+    assert_eq!(fx.rt.account(&admin).unwrap().lamports, admin_lamports + refunded - created);
+
+    let election_account = fx.election();
+    assert_eq!(election_account.candidates, candidates);
+    assert_eq!((election_account.registered_voters, election_account.ballots_cast), (2, 1));
+    let candidate = fx.candidate(0);
+    assert_eq!((candidate.name.as_str(), candidate.votes, candidate.owner), ("Alice", 1, admin));
+    let voter = fx.voter(&voted);
+    assert_eq!((voter.voted, voter.votes, voter.weight), (true, candidates.clone(), 1));
+    assert!(!fx.voter(&silent).voted);
+    let migrated = fx.rt.events::<CandidateMigrated>();
+    assert_eq!((migrated[0].legacy, migrated[0].pubkey), (alice, candidates[0]));
+    let migrated = fx.rt.events::<VoterMigrated>();
+    assert_eq!((migrated[0].legacy, migrated[0].voter), (legacy_voted, find_voter_pda(&election, &voted).0));
+
+    // Migrated ballots can be changed, and migrated voters can vote
+    fx.rt.airdrop(&voted, SOL);
+    fx.change_vote(&voted, fx.ballot(&[0], &[])).unwrap();
+    fx.vote(&silent, fx.ballot(&[0], &[])).unwrap();
+    assert_eq!((fx.candidate(0).votes, fx.candidate(1).votes), (2, 0));
+}
+
+#[test]
+fn test_migration_rules() {
+    let mut fx = Fixture::new(2, 0, &[]);
+    let (admin, election) = (fx.admin, fx.election);
+    let alice = deploy_candidate(&mut fx.rt, "Alice", 1);
+    let bob = deploy_candidate(&mut fx.rt, "Bob", 1);
+    let authority = fx.rt.signer();
+    deploy_voter(&mut fx.rt, &authority, Some([alice, bob]));
+
+    let stranger = fx.rt.signer();
+    fx.rt.airdrop(&stranger, SOL);
+    let err = fx.rt.process(&migrate_candidate(&election, &stranger, "Alice")).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+
+    // The ballot's candidates move first, and must be the ones it voted for
+    let err = fx.rt.process(&migrate_voter(&election, &admin, &authority, &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+    fx.rt.process(&migrate_candidate(&election, &admin, "Alice")).unwrap();
+    fx.rt.process(&migrate_candidate(&election, &admin, "Bob")).unwrap();
+    let (alice, bob) = (find_candidate_pda(&election, "Alice").0, find_candidate_pda(&election, "Bob").0);
+    let err = fx.rt.process(&migrate_voter(&election, &admin, &authority, &[bob, alice])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+    fx.rt.process(&migrate_voter(&election, &admin, &authority, &[alice, bob])).unwrap();
+
+    // Each account moves once: the migrated account exists and the legacy one is gone
+    assert!(fx.rt.process(&migrate_candidate(&election, &admin, "Alice")).is_err());
+    assert!(fx.rt.process(&migrate_voter(&election, &admin, &authority, &[alice, bob])).is_err());
+    assert_eq!((fx.election().candidates.len(), fx.election().registered_voters), (2, 1));
+}
+
+#[test]
+fn test_legacy_ballots_only_join_d21_elections() {
+    let mut fx = Fixture::pending(1, 0, &[]);
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetVotingMethod { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetVotingMethod { method: VotingMethod::Approval },
+        ))
+        .unwrap();
+    deploy_candidate(&mut fx.rt, "Alice", 0);
+    let err = fx.rt.process(&migrate_candidate(&fx.election, &fx.admin, "Alice")).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::WrongVotingMethod));
+
+    // A D21 election allowing one positive vote cannot hold a two-vote ballot
+    let mut fx = Fixture::new(1, 0, &[]);
+    let (alice, bob) = (deploy_candidate(&mut fx.rt, "Alice", 1), deploy_candidate(&mut fx.rt, "Bob", 1));
+    let authority = fx.rt.signer();
+    deploy_voter(&mut fx.rt, &authority, Some([alice, bob]));
+    for name in ["Alice", "Bob"] {
+        fx.rt.process(&migrate_candidate(&fx.election, &fx.admin, name)).unwrap();
+        fx.candidates.push(find_candidate_pda(&fx.election, name).0);
+    }
+    let err = fx.rt.process(&migrate_voter(&fx.election, &fx.admin, &authority, &fx.candidates)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::TooManyVotes));
+}
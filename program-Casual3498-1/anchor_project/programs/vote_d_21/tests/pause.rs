@@ -0,0 +1,155 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::system_program;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::instructions::{initialize_config, pause, unpause};
+use vote_d_21_client::pda::{find_ballot_pda, find_candidate_pda, find_config_pda, find_vault_pda, find_voter_pda};
+
+#[test]
+fn test_only_upgrade_authority_initializes_config() {
+    let mut rt = TestRuntime::new();
+    let (intruder, emergency) = (rt.signer(), rt.signer());
+    rt.airdrop(&intruder, SOL);
+
+    let err = rt.process(&initialize_config(&intruder, &intruder)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+
+    let upgrade_authority = rt.upgrade_authority;
+    rt.airdrop(&upgrade_authority, SOL);
+    rt.process(&initialize_config(&upgrade_authority, &emergency)).unwrap();
+    let config: Config = rt.get(&find_config_pda().0);
+    assert_eq!(config.emergency_authority, emergency);
+    assert!(!config.paused);
+    assert_eq!(rt.events::<ConfigInitialized>()[0].emergency_authority, emergency);
+
+    // The config exists once per deployment
+    assert!(rt.process(&initialize_config(&upgrade_authority, &intruder)).is_err());
+}
+
+#[test]
+fn test_only_emergency_authority_pauses() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let intruder = fx.rt.signer();
+
+    let err = fx.rt.process(&pause(&intruder)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+
+    let mut ix = pause(&fx.emergency);
+    ix.accounts[1].is_signer = false;
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), anchor_error(ErrorCode::AccountNotSigner));
+    assert!(!fx.rt.get::<Config>(&find_config_pda().0).paused);
+}
+
+#[test]
+fn test_pause_halts_registration_and_voting() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let voter = fx.register_voter();
+
+    fx.rt.process(&pause(&fx.emergency)).unwrap();
+    assert!(fx.rt.get::<Config>(&find_config_pda().0).paused);
+    assert_eq!(fx.rt.events::<Paused>()[0].authority, fx.emergency);
+
+    let err = fx.vote(&voter, fx.ballot(&[0], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::ProgramPaused));
+    assert_eq!(fx.add_candidate("carol").unwrap_err(), vote_error(VoteError::ProgramPaused));
+    let latecomer = fx.rt.signer();
+    fx.rt.airdrop(&latecomer, SOL);
+    let ix = vote_d_21_client::instructions::initialize_voter(&fx.election, &latecomer, &latecomer, Default::default());
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::ProgramPaused));
+
+    fx.rt.process(&unpause(&fx.emergency)).unwrap();
+    assert_eq!(fx.rt.events::<Unpaused>().len(), 1);
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+    assert_eq!(fx.candidate(0).votes, 1);
+}
+
+#[test]
+fn test_pause_halts_every_tally_and_registration_change() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let (authority, representative) = (fx.register_voter(), fx.register_voter());
+    fx.vote(&authority, fx.ballot(&[0], &[])).unwrap();
+    fx.rt.process(&pause(&fx.emergency)).unwrap();
+
+    let paused = vote_error(VoteError::ProgramPaused);
+    assert_eq!(fx.change_vote(&authority, fx.ballot(&[1], &[])).unwrap_err(), paused);
+    assert_eq!(fx.revoke_vote(&authority).unwrap_err(), paused);
+
+    let (election, config) = (fx.election, find_config_pda().0);
+    let voter = find_voter_pda(&election, &representative).0;
+    let mint = fx.rt.create_mint(&fx.admin);
+    let voter_token_account = fx.rt.create_token_account(&mint, &representative, 10);
+    let instructions = [
+        instruction(
+            vote_d_21::accounts::CommitVote { election, voter, config, authority: representative },
+            vote_d_21::instruction::CommitVote { commitment: [0; 32] },
+        ),
+        instruction(
+            vote_d_21::accounts::RevealVote { election, voter, config, authority: representative },
+            vote_d_21::instruction::RevealVote { ballot: fx.ballot(&[0], &[]), salt: [0; 32] },
+        ),
+        instruction(
+            vote_d_21::accounts::VoteRanked {
+                election,
+                voter,
+                config,
+                ballot: find_ballot_pda(&voter).0,
+                authority: representative,
+                payer: representative,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::VoteRanked { rankings: vec![fx.candidates[0]] },
+        ),
+        instruction(
+            vote_d_21::accounts::Delegate { election, voter, config, authority: representative },
+            vote_d_21::instruction::Delegate {},
+        ),
+        instruction(
+            vote_d_21::accounts::Undelegate { election, voter, config, authority: representative },
+            vote_d_21::instruction::Undelegate {},
+        ),
+        instruction(
+            vote_d_21::accounts::NominateCandidate {
+                election,
+                candidate: find_candidate_pda(&election, "carol").0,
+                config,
+                owner: representative,
+                payer: representative,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::NominateCandidate {
+                name: String::from("carol"),
+                description: String::new(),
+                uri: String::new(),
+            },
+        ),
+        instruction(
+            vote_d_21::accounts::LockTokens {
+                election,
+                voter,
+                config,
+                mint,
+                voter_token_account,
+                vault: find_vault_pda(&voter).0,
+                authority: representative,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::LockTokens { amount: 10 },
+        ),
+        instruction(
+            vote_d_21::accounts::ApproveCandidate { election, candidate: fx.candidates[1], config, admin: fx.admin },
+            vote_d_21::instruction::ApproveCandidate {},
+        ),
+    ];
+    for ix in &instructions {
+        assert_eq!(fx.rt.process(ix).unwrap_err(), paused);
+    }
+
+    fx.rt.process(&unpause(&fx.emergency)).unwrap();
+    fx.change_vote(&authority, fx.ballot(&[1], &[])).unwrap();
+    assert_eq!([fx.candidate(0).votes, fx.candidate(1).votes], [0, 1]);
+}
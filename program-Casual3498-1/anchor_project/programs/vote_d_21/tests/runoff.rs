@@ -0,0 +1,172 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::system_program;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{find_ballot_pda, find_config_pda, find_runoff_pda, find_voter_pda};
+
+/// Open instant-runoff election with the named candidates.
+fn runoff_election(names: &[&str]) -> Fixture {
+    let mut fx = Fixture::pending(1, 0, names);
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetVotingMethod { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetVotingMethod { method: VotingMethod::InstantRunoff },
+        ))
+        .unwrap();
+    fx.opened()
+}
+
+fn rank(fx: &Fixture, authority: &Pubkey, rankings: &[Pubkey]) -> Instruction {
+    let voter = find_voter_pda(&fx.election, authority).0;
+    instruction(
+        vote_d_21::accounts::VoteRanked {
+            election: fx.election,
+            voter,
+            config: find_config_pda().0,
+            ballot: find_ballot_pda(&voter).0,
+            authority: *authority,
+            payer: *authority,
+            system_program: system_program::ID,
+        },
+        vote_d_21::instruction::VoteRanked { rankings: rankings.to_vec() },
+    )
+}
+
+/// Register a voter ranking the candidates at `rankings`, and return their ballot.
+fn vote_ranked(fx: &mut Fixture, rankings: &[usize]) -> Pubkey {
+    let authority = fx.register_voter();
+    let rankings: Vec<Pubkey> = rankings.iter().map(|&i| fx.candidates[i]).collect();
+    fx.rt.process(&rank(fx, &authority, &rankings)).unwrap();
+    find_ballot_pda(&find_voter_pda(&fx.election, &authority).0).0
+}
+
+fn withdraw(fx: &mut Fixture, index: usize) -> std::result::Result<(), ProgramError> {
+    fx.rt.process(&instruction(
+        vote_d_21::accounts::WithdrawCandidacy {
+            election: fx.election,
+            candidate: fx.candidates[index],
+            owner: fx.admin,
+        },
+        vote_d_21::instruction::WithdrawCandidacy { reason: String::new() },
+    ))
+}
+
+fn disqualify(fx: &mut Fixture, index: usize) -> std::result::Result<(), ProgramError> {
+    fx.rt.process(&instruction(
+        vote_d_21::accounts::DisqualifyCandidate {
+            election: fx.election,
+            candidate: fx.candidates[index],
+            admin: fx.admin,
+        },
+        vote_d_21::instruction::DisqualifyCandidate { reason: String::new() },
+    ))
+}
+
+/// Start the count and return the runoff tally account.
+fn start(fx: &mut Fixture) -> Pubkey {
+    let runoff = find_runoff_pda(&fx.election).0;
+    let mut start = instruction(
+        vote_d_21::accounts::StartRunoff {
+            election: fx.election,
+            runoff,
+            payer: fx.admin,
+            system_program: system_program::ID,
+        },
+        vote_d_21::instruction::StartRunoff {},
+    );
+    start.accounts.extend(fx.candidates.iter().map(|&key| AccountMeta::new_readonly(key, false)));
+    fx.rt.process(&start).unwrap();
+    runoff
+}
+
+fn tabulate(fx: &mut Fixture, runoff: &Pubkey, ballots: &[AccountMeta]) -> std::result::Result<(), ProgramError> {
+    let mut round = instruction(
+        vote_d_21::accounts::TabulateRound { election: fx.election, runoff: *runoff },
+        vote_d_21::instruction::TabulateRound {},
+    );
+    round.accounts.extend_from_slice(ballots);
+    fx.rt.process(&round)
+}
+
+/// Start the count and tabulate rounds over `ballots` until it completes.
+fn count(fx: &mut Fixture, ballots: &[Pubkey]) -> RunoffTally {
+    let runoff = start(fx);
+    let ballots: Vec<AccountMeta> = ballots.iter().map(|&key| AccountMeta::new(key, false)).collect();
+    while !fx.rt.get::<RunoffTally>(&runoff).complete {
+        tabulate(fx, &runoff, &ballots).unwrap();
+    }
+    fx.rt.get(&runoff)
+}
+
+#[test]
+fn test_candidates_removed_while_voting_are_eliminated_at_start() {
+    let mut fx = runoff_election(&["alice", "bob", "carol"]);
+    let ballots = [vote_ranked(&mut fx, &[2, 0]), vote_ranked(&mut fx, &[1]), vote_ranked(&mut fx, &[0])];
+    withdraw(&mut fx, 2).unwrap();
+
+    fx.rt.warp(fx.ends_at);
+    let runoff = count(&mut fx, &ballots);
+    assert_eq!(runoff.eliminated, vec![fx.candidates[2]]);
+    assert_eq!(runoff.winner, Some(fx.candidates[0]));
+}
+
+#[test]
+fn test_candidacy_frozen_once_runoff_can_start() {
+    let mut fx = runoff_election(&["alice", "bob", "carol"]);
+    let ballots = [vote_ranked(&mut fx, &[2, 0]), vote_ranked(&mut fx, &[2]), vote_ranked(&mut fx, &[1])];
+
+    // Once the count may start, its field can no longer change under it
+    fx.rt.warp(fx.ends_at);
+    assert_eq!(withdraw(&mut fx, 2).unwrap_err(), vote_error(VoteError::VotingEnded));
+    assert_eq!(disqualify(&mut fx, 2).unwrap_err(), vote_error(VoteError::VotingEnded));
+    let runoff = count(&mut fx, &ballots);
+    assert_eq!(runoff.winner, Some(fx.candidates[2]));
+
+    assert_eq!(withdraw(&mut fx, 2).unwrap_err(), vote_error(VoteError::VotingEnded));
+    assert_eq!(disqualify(&mut fx, 2).unwrap_err(), vote_error(VoteError::VotingEnded));
+    assert_eq!(fx.candidate(2).status, CandidateStatus::Approved);
+}
+
+#[test]
+fn test_ranked_ballots_only_in_runoff_elections() {
+    let mut fx = Fixture::new(1, 0, &["alice"]);
+    let voter = fx.register_voter();
+    let ix = rank(&fx, &voter, &[fx.candidates[0]]);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::WrongVotingMethod));
+
+    let mut fx = runoff_election(&["alice", "bob"]);
+    let voter = fx.register_voter();
+    assert_eq!(fx.vote(&voter, fx.ballot(&[0], &[])).unwrap_err(), vote_error(VoteError::WrongVotingMethod));
+    let (alice, bob) = (fx.candidates[0], fx.candidates[1]);
+    for rankings in [vec![], vec![alice, bob, alice], vec![Pubkey::new_unique()]] {
+        let ix = rank(&fx, &voter, &rankings);
+        assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidRanking));
+    }
+    fx.rt.process(&rank(&fx, &voter, &[bob, alice])).unwrap();
+}
+
+#[test]
+fn test_rounds_count_each_ballot_once() {
+    let mut fx = runoff_election(&["alice", "bob"]);
+    let ballots = [vote_ranked(&mut fx, &[0]), vote_ranked(&mut fx, &[1, 0]), vote_ranked(&mut fx, &[0])];
+    fx.rt.warp(fx.ends_at);
+    assert_eq!(fx.finalize().unwrap_err(), vote_error(VoteError::RunoffNotComplete));
+
+    let runoff = start(&mut fx);
+    let err = tabulate(&mut fx, &runoff, &[AccountMeta::new_readonly(ballots[0], false)]).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBallot));
+    tabulate(&mut fx, &runoff, &[AccountMeta::new(ballots[0], false)]).unwrap();
+    let err = tabulate(&mut fx, &runoff, &[AccountMeta::new(ballots[0], false)]).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::BallotAlreadyCounted));
+
+    let rest = [AccountMeta::new(ballots[1], false), AccountMeta::new(ballots[2], false)];
+    tabulate(&mut fx, &runoff, &rest).unwrap();
+    let tally: RunoffTally = fx.rt.get(&runoff);
+    assert!(tally.complete);
+    assert_eq!(tally.winner, Some(fx.candidates[0]));
+    assert_eq!(tabulate(&mut fx, &runoff, &rest).unwrap_err(), vote_error(VoteError::RunoffComplete));
+}
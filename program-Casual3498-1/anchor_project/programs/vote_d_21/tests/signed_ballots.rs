@@ -0,0 +1,160 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use solana_precompile_error::PrecompileError;
+use solana_sdk::signature::Signer;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::instructions::{pause, verify_signed_ballots, vote_signed_batch};
+
+/// `message` signed off-chain by `authority`.
+fn sign_message(fx: &Fixture, authority: &Pubkey, message: &[u8]) -> [u8; 64] {
+    fx.rt.keypair(authority).sign_message(message).into()
+}
+
+/// `ballot` signed by `authority` for `fx`'s election with `nonce`.
+fn sign(fx: &Fixture, authority: &Pubkey, nonce: u64, ballot: Ballot) -> (SignedBallot, [u8; 64]) {
+    let signed = SignedBallot { authority: *authority, nonce, ballot };
+    let signature = sign_message(fx, authority, &signed.message(&fx.election));
+    (signed, signature)
+}
+
+#[test]
+fn test_relayer_submits_signed_batch() {
+    let mut fx = Fixture::new(2, 1, &["alice", "bob", "carol"]);
+    let voters: Vec<Pubkey> = (0..3).map(|_| fx.register_voter()).collect();
+    let ballots = vec![
+        sign(&fx, &voters[0], 0, fx.ballot(&[0, 1], &[2])),
+        sign(&fx, &voters[1], 0, fx.ballot(&[0], &[])),
+        sign(&fx, &voters[2], 0, fx.ballot(&[1, 0], &[])),
+    ];
+
+    fx.rt.process_transaction(&vote_signed_batch(&fx.election, &ballots)).unwrap();
+
+    // Ballots sharing candidates all count
+    assert_eq!(fx.candidate(0).votes, 3);
+    assert_eq!(fx.candidate(1).votes, 2);
+    assert_eq!(fx.candidate(2).negative_votes, 1);
+    assert_eq!(fx.election().ballots_cast, 3);
+    for authority in &voters {
+        let voter = fx.voter(authority);
+        assert!(voter.voted);
+        assert_eq!(voter.nonce, 1);
+    }
+    let cast = fx.rt.events::<VoteCast>();
+    assert_eq!(cast.iter().map(|e| e.voter).collect::<Vec<_>>(), voters);
+    assert_eq!(cast[2].tallies[1].votes, 3);
+}
+
+#[test]
+fn test_signed_ballot_cannot_be_replayed() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let authority = fx.register_voter();
+    let batch = vote_signed_batch(&fx.election, &[sign(&fx, &authority, 0, fx.ballot(&[0], &[]))]);
+    fx.rt.process_transaction(&batch).unwrap();
+
+    let err = fx.rt.process_transaction(&batch).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::AlreadyVoted));
+
+    // Even once the ballot is revoked, its signature stays spent
+    fx.revoke_vote(&authority).unwrap();
+    let err = fx.rt.process_transaction(&batch).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidNonce));
+    assert_eq!(fx.candidate(0).votes, 0);
+
+    let batch = vote_signed_batch(&fx.election, &[sign(&fx, &authority, 1, fx.ballot(&[1], &[]))]);
+    fx.rt.process_transaction(&batch).unwrap();
+    assert_eq!((fx.candidate(1).votes, fx.voter(&authority).nonce), (1, 2));
+}
+
+#[test]
+fn test_ballots_must_match_verified_signatures() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let authority = fx.register_voter();
+    let ballots = [sign(&fx, &authority, 0, fx.ballot(&[0], &[]))];
+
+    // Without the Ed25519 instruction in front
+    let [_, batch] = vote_signed_batch(&fx.election, &ballots).try_into().unwrap();
+    let err = fx.rt.process_transaction(std::slice::from_ref(&batch)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBallotSignature));
+
+    // A ballot altered after signing fails the Ed25519 program
+    let (mut altered, signature) = ballots[0].clone();
+    altered.ballot = fx.ballot(&[1], &[]);
+    let err = fx.rt.process_transaction(&vote_signed_batch(&fx.election, &[(altered, signature)])).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(PrecompileError::InvalidSignature as u32));
+
+    // A valid signature over a different message does not authorize the ballot
+    let elsewhere = Pubkey::new_unique();
+    let signed = ballots[0].0.clone();
+    let signature = sign_message(&fx, &authority, &signed.message(&elsewhere));
+    let transaction = [verify_signed_ballots(&elsewhere, &[(signed, signature)]), batch.clone()];
+    let err = fx.rt.process_transaction(&transaction).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBallotSignature));
+
+    // Nor does one by another key
+    let intruder = fx.rt.signer();
+    let forged = SignedBallot { authority: intruder, ..ballots[0].0.clone() };
+    let signature = sign_message(&fx, &intruder, &forged.message(&fx.election));
+    let transaction = [verify_signed_ballots(&fx.election, &[(forged, signature)]), batch];
+    let err = fx.rt.process_transaction(&transaction).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBallotSignature));
+
+    assert!(!fx.voter(&authority).voted);
+    assert!(fx.rt.events::<VoteCast>().is_empty());
+}
+
+#[test]
+fn test_signature_data_must_sit_in_ed25519_instruction() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let authority = fx.register_voter();
+    let ballots = [sign(&fx, &authority, 0, fx.ballot(&[0], &[]))];
+
+    // Naming the Ed25519 instruction by its index rather than u16::MAX
+    // passes the Ed25519 program, which then reads the same bytes
+    let mut transaction = vote_signed_batch(&fx.election, &ballots);
+    for field in [1, 3, 6] {
+        let offset = 2 + 2 * field;
+        transaction[0].data[offset..offset + 2].copy_from_slice(&0u16.to_le_bytes());
+    }
+    let err = fx.rt.process_transaction(&transaction).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBallotSignature));
+
+    // The Ed25519 program itself rejects offsets past the instruction
+    let mut transaction = vote_signed_batch(&fx.election, &ballots);
+    transaction[0].data[6..8].copy_from_slice(&u16::MAX.to_le_bytes());
+    let err = fx.rt.process_transaction(&transaction).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(PrecompileError::InvalidDataOffsets as u32));
+    assert!(!fx.voter(&authority).voted);
+}
+
+#[test]
+fn test_batch_is_atomic_and_bounded() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let (first, second) = (fx.register_voter(), fx.register_voter());
+
+    // A bad nonce on the second ballot undoes the first
+    let ballots = [
+        sign(&fx, &first, 0, fx.ballot(&[0], &[])),
+        sign(&fx, &second, 5, fx.ballot(&[0], &[])),
+    ];
+    let err = fx.rt.process_transaction(&vote_signed_batch(&fx.election, &ballots)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidNonce));
+    assert_eq!(fx.candidate(0).votes, 0);
+    assert_eq!(fx.voter(&first).nonce, 0);
+
+    let err = fx.rt.process_transaction(&vote_signed_batch(&fx.election, &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBatchSize));
+    let ballots: Vec<_> = (0..=SignedBallot::MAX_BATCH as u64)
+        .map(|nonce| sign(&fx, &first, nonce, fx.ballot(&[0], &[])))
+        .collect();
+    let err = fx.rt.process_transaction(&vote_signed_batch(&fx.election, &ballots)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidBatchSize));
+
+    fx.rt.process(&pause(&fx.emergency)).unwrap();
+    let ballots = [sign(&fx, &first, 0, fx.ballot(&[0], &[]))];
+    let err = fx.rt.process_transaction(&vote_signed_batch(&fx.election, &ballots)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::ProgramPaused));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::instructions::{
+    fund_sponsor_budget, initialize_sponsor_budget, sponsored_initialize_voter, sponsored_vote,
+    withdraw_sponsor_budget,
+};
+use vote_d_21_client::pda::{find_sponsor_budget_pda, find_voter_pda};
+
+const FEE: u64 = 5_000;
+
+/// A fixture whose election has a sponsor budget funded with `deposit`
+/// lamports, reimbursing the returned relayer.
+fn sponsored(deposit: u64) -> (Fixture, Pubkey) {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let relayer = fx.rt.signer();
+    fx.rt.airdrop(&relayer, SOL);
+    fx.rt.process(&initialize_sponsor_budget(&fx.election, &fx.admin, &relayer, FEE)).unwrap();
+    fx.rt.process(&fund_sponsor_budget(&fx.election, &fx.admin, deposit)).unwrap();
+    (fx, relayer)
+}
+
+fn budget(fx: &Fixture) -> SponsorBudget {
+    fx.rt.get(&find_sponsor_budget_pda(&fx.election).0)
+}
+
+fn lamports(fx: &Fixture, key: &Pubkey) -> u64 {
+    fx.rt.account(key).map_or(0, |account| account.lamports)
+}
+
+#[test]
+fn test_voter_without_sol_registers_and_votes() {
+    let (mut fx, relayer) = sponsored(SOL);
+    let authority = fx.rt.signer();
+    let before = lamports(&fx, &relayer);
+
+    let ix = sponsored_initialize_voter(&fx.election, &authority, &relayer, Default::default());
+    fx.rt.process(&ix).unwrap();
+    // The relayer fronted the rent and got it back with the fee
+    assert_eq!(lamports(&fx, &relayer), before + FEE);
+    // Closing the voter account refunds the sponsor, who paid the rent
+    assert_eq!(fx.voter(&authority).payer, fx.admin);
+
+    fx.rt.process(&sponsored_vote(&fx.election, &authority, &relayer, fx.ballot(&[0], &[]))).unwrap();
+    assert_eq!(lamports(&fx, &relayer), before + 2 * FEE);
+    assert_eq!(fx.candidate(0).votes, 1);
+    assert_eq!(lamports(&fx, &authority), 0);
+
+    let rent = Rent::default().minimum_balance(VoterAccount::SPACE);
+    let budget = budget(&fx);
+    assert_eq!((budget.spent, budget.sponsored_transactions), (rent + 2 * FEE, 2));
+    let reimbursed = fx.rt.events::<RelayerReimbursed>();
+    assert_eq!(reimbursed.iter().map(|e| e.amount).collect::<Vec<_>>(), vec![rent + FEE, FEE]);
+    assert_eq!(reimbursed[1].available, SOL - rent - 2 * FEE);
+}
+
+#[test]
+fn test_only_budget_relayer_is_reimbursed() {
+    let (mut fx, relayer) = sponsored(SOL);
+    let authority = fx.register_voter();
+
+    let intruder = fx.rt.signer();
+    fx.rt.airdrop(&intruder, SOL);
+    let ix = sponsored_vote(&fx.election, &authority, &intruder, fx.ballot(&[0], &[]));
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidRelayer));
+
+    // A budget without its relayer, or the other way round, is rejected
+    let mut ix = sponsored_vote(&fx.election, &authority, &relayer, fx.ballot(&[0], &[]));
+    ix.accounts[5] = AccountMeta::new_readonly(vote_d_21::ID, false);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidRelayer));
+    let mut ix = sponsored_vote(&fx.election, &authority, &relayer, fx.ballot(&[0], &[]));
+    ix.accounts[4] = AccountMeta::new_readonly(vote_d_21::ID, false);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidRelayer));
+
+    let latecomer = fx.rt.signer();
+    let ix = sponsored_initialize_voter(&fx.election, &latecomer, &intruder, Default::default());
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidRelayer));
+    assert!(fx.rt.account(&find_voter_pda(&fx.election, &latecomer).0).is_none());
+    assert_eq!(budget(&fx).sponsored_transactions, 0);
+}
+
+#[test]
+fn test_budget_limits_and_withdrawal() {
+    let (mut fx, relayer) = sponsored(FEE);
+    let authority = fx.rt.signer();
+
+    // Not enough for the voter's rent
+    let ix = sponsored_initialize_voter(&fx.election, &authority, &relayer, Default::default());
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::SponsorBudgetExhausted));
+
+    let voter = fx.register_voter();
+    fx.rt.process(&sponsored_vote(&fx.election, &voter, &relayer, fx.ballot(&[1], &[]))).unwrap();
+    let voter = fx.register_voter();
+    let ix = sponsored_vote(&fx.election, &voter, &relayer, fx.ballot(&[1], &[]));
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::SponsorBudgetExhausted));
+
+    fx.rt.process(&fund_sponsor_budget(&fx.election, &relayer, 3 * FEE)).unwrap();
+    let err = fx.rt.process(&withdraw_sponsor_budget(&fx.election, &relayer, FEE)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::UnauthorizedAccess));
+    let err = fx.rt.process(&withdraw_sponsor_budget(&fx.election, &fx.admin, 3 * FEE + 1)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::SponsorBudgetExhausted));
+
+    let before = lamports(&fx, &fx.admin);
+    fx.rt.process(&withdraw_sponsor_budget(&fx.election, &fx.admin, 3 * FEE)).unwrap();
+    assert_eq!(lamports(&fx, &fx.admin), before + 3 * FEE);
+    let key = find_sponsor_budget_pda(&fx.election).0;
+    assert_eq!(lamports(&fx, &key), Rent::default().minimum_balance(SponsorBudget::SPACE));
+    assert_eq!(fx.rt.events::<SponsorBudgetWithdrawn>()[0].available, 0);
+}
+
+#[test]
+fn test_budget_setup_is_admin_only() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let intruder = fx.rt.signer();
+    fx.rt.airdrop(&intruder, SOL);
+
+    let ix = initialize_sponsor_budget(&fx.election, &intruder, &intruder, FEE);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::UnauthorizedAccess));
+    let ix = initialize_sponsor_budget(&fx.election, &fx.admin, &intruder, SponsorBudget::MAX_FEE_PER_TRANSACTION + 1);
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::InvalidSponsorFee));
+
+    fx.rt.process(&initialize_sponsor_budget(&fx.election, &fx.admin, &intruder, FEE)).unwrap();
+    let created = &fx.rt.events::<SponsorBudgetCreated>()[0];
+    assert_eq!((created.sponsor, created.relayer, created.fee_per_transaction), (fx.admin, intruder, FEE));
+}
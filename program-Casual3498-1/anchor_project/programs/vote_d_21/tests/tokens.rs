@@ -0,0 +1,228 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::{find_config_pda, find_results_pda, find_vault_pda, find_voter_pda};
+
+fn set_weighting(fx: &Fixture, weighting: VoteWeighting) -> Instruction {
+    instruction(
+        vote_d_21::accounts::SetVoteWeighting { election: fx.election, admin: fx.admin },
+        vote_d_21::instruction::SetVoteWeighting { weighting },
+    )
+}
+
+/// Open election weighted by `weighting` of a new governance mint.
+fn weighted(weighting: impl FnOnce(Pubkey) -> VoteWeighting) -> (Fixture, Pubkey) {
+    let mut fx = Fixture::pending(2, 0, &["alice", "bob"]);
+    let mint = fx.rt.create_mint(&fx.admin);
+    fx.rt.process(&set_weighting(&fx, weighting(mint))).unwrap();
+    (fx.opened(), mint)
+}
+
+fn lock(fx: &Fixture, authority: &Pubkey, mint: &Pubkey, token_account: &Pubkey, amount: u64) -> Instruction {
+    let voter = find_voter_pda(&fx.election, authority).0;
+    instruction(
+        vote_d_21::accounts::LockTokens {
+            election: fx.election,
+            voter,
+            config: find_config_pda().0,
+            mint: *mint,
+            voter_token_account: *token_account,
+            vault: find_vault_pda(&voter).0,
+            authority: *authority,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        vote_d_21::instruction::LockTokens { amount },
+    )
+}
+
+fn unlock(fx: &Fixture, authority: &Pubkey, token_account: &Pubkey) -> Instruction {
+    let voter = find_voter_pda(&fx.election, authority).0;
+    instruction(
+        vote_d_21::accounts::UnlockTokens {
+            election: fx.election,
+            voter,
+            vault: find_vault_pda(&voter).0,
+            voter_token_account: *token_account,
+            authority: *authority,
+            token_program: anchor_spl::token::ID,
+        },
+        vote_d_21::instruction::UnlockTokens {},
+    )
+}
+
+#[test]
+fn test_tokens_moved_between_voters_count_once() {
+    let (mut fx, mint) = weighted(|mint| VoteWeighting::TokenWeighted { mint });
+    let (first, second) = (fx.register_voter(), fx.register_voter());
+    let first_tokens = fx.rt.create_token_account(&mint, &first, 100);
+    let second_tokens = fx.rt.create_token_account(&mint, &second, 0);
+
+    // Holding tokens at registration grants nothing
+    assert_eq!(fx.voter(&first).weight, 0);
+    assert_eq!(fx.vote(&first, fx.ballot(&[0], &[])).unwrap_err(), vote_error(VoteError::NoVotingWeight));
+
+    // The first voter hands their tokens over; only the holder can lock them
+    let transfer = spl_token::instruction::transfer(&spl_token::ID, &first_tokens, &second_tokens, &first, &[], 100)
+        .unwrap();
+    fx.rt.process(&transfer).unwrap();
+    fx.rt.process(&lock(&fx, &second, &mint, &second_tokens, 100)).unwrap();
+    let err = fx.rt.process(&lock(&fx, &first, &mint, &first_tokens, 100)).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(spl_token::error::TokenError::InsufficientFunds as u32));
+    assert_eq!(fx.rt.token_balance(&second_tokens), 0);
+    assert_eq!(fx.voter(&second).weight, 100);
+
+    fx.vote(&second, fx.ballot(&[0], &[])).unwrap();
+    assert_eq!(fx.vote(&first, fx.ballot(&[0], &[])).unwrap_err(), vote_error(VoteError::NoVotingWeight));
+    assert_eq!(fx.candidate(0).votes, 100);
+}
+
+#[test]
+fn test_token_gated_ballot_requires_locked_minimum() {
+    let (mut fx, mint) = weighted(|mint| VoteWeighting::TokenGated { mint, min_balance: 50 });
+    let voter = fx.register_voter();
+    let tokens = fx.rt.create_token_account(&mint, &voter, 80);
+    assert_eq!(fx.voter(&voter).weight, 0);
+
+    let err = fx.rt.process(&lock(&fx, &voter, &mint, &tokens, 49)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InsufficientTokenBalance));
+    fx.rt.process(&lock(&fx, &voter, &mint, &tokens, 80)).unwrap();
+    assert_eq!(fx.voter(&voter).weight, 1);
+
+    fx.vote(&voter, fx.ballot(&[1], &[])).unwrap();
+    assert_eq!(fx.candidate(1).votes, 1);
+}
+
+#[test]
+fn test_weighting_fixed_once_voters_register() {
+    let mut fx = Fixture::pending(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    assert_eq!(fx.voter(&voter).weight, 1);
+
+    // The equal weight granted at registration would otherwise skip the token lock
+    let mint = fx.rt.create_mint(&fx.admin);
+    let ix = set_weighting(&fx, VoteWeighting::TokenGated { mint, min_balance: 10 });
+    assert_eq!(fx.rt.process(&ix).unwrap_err(), vote_error(VoteError::VotersAlreadyRegistered));
+    assert_eq!(fx.election().weighting, VoteWeighting::Equal);
+}
+
+#[test]
+fn test_equal_weighting_locks_no_tokens() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    let mint = fx.rt.create_mint(&fx.admin);
+    let tokens = fx.rt.create_token_account(&mint, &voter, 10);
+    let err = fx.rt.process(&lock(&fx, &voter, &mint, &tokens, 10)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::TokensNotLockable));
+    assert_eq!(fx.rt.token_balance(&tokens), 10);
+}
+
+#[test]
+fn test_locked_tokens_block_closing_the_voter() {
+    let (mut fx, mint) = weighted(|mint| VoteWeighting::TokenWeighted { mint });
+    let voter = fx.register_voter();
+    let tokens = fx.rt.create_token_account(&mint, &voter, 30);
+    let other_mint = fx.rt.create_mint(&fx.admin);
+    let other_tokens = fx.rt.create_token_account(&other_mint, &voter, 30);
+    let err = fx.rt.process(&lock(&fx, &voter, &other_mint, &other_tokens, 30)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidTokenAccount));
+    fx.rt.process(&lock(&fx, &voter, &mint, &tokens, 30)).unwrap();
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+    fx.rt.warp(fx.ends_at);
+    fx.finalize().unwrap();
+
+    let voter_account = find_voter_pda(&fx.election, &voter).0;
+    let close = instruction(
+        vote_d_21::accounts::CloseVoter {
+            election: fx.election,
+            results: find_results_pda(&fx.election).0,
+            voter: voter_account,
+            authority: voter,
+            payer: voter,
+        },
+        vote_d_21::instruction::CloseVoter {},
+    );
+    assert_eq!(fx.rt.process(&close).unwrap_err(), vote_error(VoteError::TokensStillLocked));
+
+    fx.rt.process(&unlock(&fx, &voter, &tokens)).unwrap();
+    assert_eq!(fx.rt.token_balance(&tokens), 30);
+    fx.rt.process(&close).unwrap();
+    assert!(fx.rt.account(&voter_account).is_none());
+}
+
+#[test]
+fn test_weighted_tally_overflow_rejected() {
+    let (mut fx, mint) = weighted(|mint| VoteWeighting::TokenWeighted { mint });
+    for amount in [u64::MAX, 1] {
+        let voter = fx.register_voter();
+        let tokens = fx.rt.create_token_account(&mint, &voter, amount);
+        fx.rt.process(&lock(&fx, &voter, &mint, &tokens, amount)).unwrap();
+        let result = fx.vote(&voter, fx.ballot(&[0], &[]));
+        if amount == 1 {
+            assert_eq!(result.unwrap_err(), vote_error(VoteError::VoteOverflow));
+        } else {
+            result.unwrap();
+        }
+    }
+    assert_eq!(fx.candidate(0).votes, u64::MAX);
+    assert_eq!(fx.election().ballots_cast, 1);
+}
+
+#[test]
+fn test_tokens_stay_locked_until_reveals_close() {
+    let mut fx = Fixture::pending(2, 0, &["alice"]);
+    let mint = fx.rt.create_mint(&fx.admin);
+    fx.rt.process(&set_weighting(&fx, VoteWeighting::TokenWeighted { mint })).unwrap();
+    let reveal_ends_at = fx.ends_at + 100;
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetCommitReveal { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetCommitReveal { reveal_ends_at: Some(reveal_ends_at) },
+        ))
+        .unwrap();
+    let mut fx = fx.opened();
+    let voter = fx.register_voter();
+    let tokens = fx.rt.create_token_account(&mint, &voter, 40);
+    fx.rt.process(&lock(&fx, &voter, &mint, &tokens, 40)).unwrap();
+
+    let (ballot, salt) = (fx.ballot(&[0], &[]), [3; 32]);
+    let voter_account = find_voter_pda(&fx.election, &voter).0;
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::CommitVote {
+                election: fx.election,
+                voter: voter_account,
+                config: find_config_pda().0,
+                authority: voter,
+            },
+            vote_d_21::instruction::CommitVote { commitment: ballot.commitment(&salt) },
+        ))
+        .unwrap();
+
+    // The commitment is still unrevealed, so the escrow backs its weight
+    fx.rt.warp(fx.ends_at);
+    let err = fx.rt.process(&unlock(&fx, &voter, &tokens)).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::VotingNotEnded));
+    let mut reveal = instruction(
+        vote_d_21::accounts::RevealVote {
+            election: fx.election,
+            voter: voter_account,
+            config: find_config_pda().0,
+            authority: voter,
+        },
+        vote_d_21::instruction::RevealVote { ballot, salt },
+    );
+    reveal.accounts.push(AccountMeta::new(fx.candidates[0], false));
+    fx.rt.process(&reveal).unwrap();
+    assert_eq!(fx.candidate(0).votes, 40);
+
+    fx.rt.warp(reveal_ends_at);
+    fx.rt.process(&unlock(&fx, &voter, &tokens)).unwrap();
+    assert_eq!(fx.rt.token_balance(&tokens), 40);
+}
@@ -0,0 +1,253 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use common::*;
+use vote_d_21::errors::VoteError;
+use vote_d_21::events::*;
+use vote_d_21::state::*;
+use vote_d_21_client::pda::find_voter_pda;
+
+#[test]
+fn test_vote_records_ballot_and_tallies() {
+    let mut fx = Fixture::new(2, 1, &["alice", "bob", "carol"]);
+    let voter = fx.register_voter();
+
+    let ballot = fx.ballot(&[0, 1], &[2]);
+    fx.vote(&voter, ballot.clone()).unwrap();
+
+    let account = fx.voter(&voter);
+    assert!(account.voted);
+    assert_eq!(account.votes, ballot.positive);
+    assert_eq!(account.negative_votes, ballot.negative);
+    assert_eq!(fx.candidate(0).votes, 1);
+    assert_eq!(fx.candidate(1).votes, 1);
+    assert_eq!(fx.candidate(2).negative_votes, 1);
+    assert_eq!(fx.election().ballots_cast, 1);
+
+    let events = fx.rt.events::<VoteCast>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].voter, voter);
+    assert_eq!(events[0].positive, ballot.positive);
+    assert_eq!(events[0].ballots_cast, 1);
+    assert_eq!(events[0].tallies.len(), 3);
+    assert_eq!(events[0].header.version, EVENT_VERSION);
+}
+
+#[test]
+fn test_double_vote_rejected() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let voter = fx.register_voter();
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+
+    let err = fx.vote(&voter, fx.ballot(&[1], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::AlreadyVoted));
+    assert_eq!(fx.candidate(1).votes, 0);
+}
+
+#[test]
+fn test_duplicate_candidates_rejected() {
+    let mut fx = Fixture::new(2, 1, &["alice", "bob", "carol"]);
+    let voter = fx.register_voter();
+
+    let err = fx.vote(&voter, fx.ballot(&[0, 0], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::DuplicateCandidates));
+    let err = fx.vote(&voter, fx.ballot(&[0, 1], &[1])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::DuplicateCandidates));
+    assert!(!fx.voter(&voter).voted);
+}
+
+#[test]
+fn test_wrong_authority_rejected() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let voter = fx.register_voter();
+    let intruder = fx.rt.signer();
+
+    // Signing for someone else's voter account breaks its seeds
+    let mut ix = vote_d_21_client::instructions::vote(&fx.election, &intruder, fx.ballot(&[0], &[]));
+    ix.accounts[1].pubkey = find_voter_pda(&fx.election, &voter).0;
+    let err = fx.rt.process(&ix).unwrap_err();
+    assert_eq!(err, anchor_error(ErrorCode::ConstraintSeeds));
+
+    // The real authority must sign
+    let mut ix = vote_d_21_client::instructions::vote(&fx.election, &voter, fx.ballot(&[0], &[]));
+    ix.accounts[3].is_signer = false;
+    let err = fx.rt.process(&ix).unwrap_err();
+    assert_eq!(err, anchor_error(ErrorCode::AccountNotSigner));
+    assert!(!fx.voter(&voter).voted);
+}
+
+#[test]
+fn test_forged_candidate_rejected() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let voter = fx.register_voter();
+
+    // A copy of a real candidate account owned by another program
+    let forged = Pubkey::new_unique();
+    let mut state = fx.rt.account(&fx.candidates[0]).unwrap();
+    state.owner = system_program::ID;
+    fx.rt.set_account(&forged, state);
+    let ballot = Ballot { positive: vec![forged], negative: vec![], scores: vec![] };
+    let err = fx.vote(&voter, ballot).unwrap_err();
+    assert_eq!(err, anchor_error(ErrorCode::AccountOwnedByWrongProgram));
+
+    // A genuine candidate registered in another election
+    let other = vote_d_21_client::pda::find_election_pda(&fx.admin, 2).0;
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::InitializeElection {
+                election: other,
+                creator: fx.admin,
+                system_program: system_program::ID,
+            },
+            vote_d_21::instruction::InitializeElection {
+                election_id: 2,
+                title: String::from("Other"),
+                starts_at: fx.starts_at,
+                ends_at: fx.ends_at,
+                positive_votes: 2,
+                negative_votes: 0,
+            },
+        ))
+        .unwrap();
+    let ix = vote_d_21_client::instructions::initialize_candidate(&other, &fx.admin, &fx.admin, "mallory", "", "");
+    fx.rt.process(&ix).unwrap();
+    let foreign = vote_d_21_client::pda::find_candidate_pda(&other, "mallory").0;
+    let ballot = Ballot { positive: vec![foreign], negative: vec![], scores: vec![] };
+    let err = fx.vote(&voter, ballot).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+    assert!(!fx.voter(&voter).voted);
+}
+
+#[test]
+fn test_candidate_accounts_must_match_ballot() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob"]);
+    let voter = fx.register_voter();
+
+    // Accounts in a different order than the ballot
+    let mut ix = vote_d_21_client::instructions::vote(&fx.election, &voter, fx.ballot(&[0, 1], &[]));
+    ix.accounts.swap(6, 7);
+    let err = fx.rt.process(&ix).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+
+    // A missing account
+    let mut ix = vote_d_21_client::instructions::vote(&fx.election, &voter, fx.ballot(&[0, 1], &[]));
+    ix.accounts.pop();
+    let err = fx.rt.process(&ix).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+
+    // A read-only account
+    let mut ix = vote_d_21_client::instructions::vote(&fx.election, &voter, fx.ballot(&[0], &[]));
+    ix.accounts[6].is_writable = false;
+    let err = fx.rt.process(&ix).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::InvalidCandidate));
+}
+
+#[test]
+fn test_ballot_rules() {
+    let mut fx = Fixture::new(2, 1, &["alice", "bob", "carol", "dave"]);
+    let voter = fx.register_voter();
+
+    let cases = [
+        (fx.ballot(&[], &[]), VoteError::EmptyBallot),
+        (fx.ballot(&[0, 1, 2], &[]), VoteError::TooManyVotes),
+        (fx.ballot(&[0, 1], &[2, 3]), VoteError::TooManyNegativeVotes),
+        (fx.ballot(&[0], &[1]), VoteError::NegativeVoteNotAllowed),
+    ];
+    for (ballot, error) in cases {
+        assert_eq!(fx.vote(&voter, ballot).unwrap_err(), vote_error(error));
+    }
+    let scored = Ballot { scores: vec![1], ..fx.ballot(&[0], &[]) };
+    assert_eq!(fx.vote(&voter, scored).unwrap_err(), vote_error(VoteError::InvalidScores));
+    assert!(!fx.voter(&voter).voted);
+}
+
+#[test]
+fn test_voting_window() {
+    let mut fx = Fixture::pending(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    let err = fx.vote(&voter, fx.ballot(&[0], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::ElectionNotActive));
+
+    assert_eq!(fx.open().unwrap_err(), vote_error(VoteError::VotingNotStarted));
+    let mut fx = fx.opened();
+    fx.rt.warp(fx.ends_at);
+    let err = fx.vote(&voter, fx.ballot(&[0], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::VotingEnded));
+}
+
+#[test]
+fn test_change_and_revoke_vote() {
+    let mut fx = Fixture::new(2, 0, &["alice", "bob", "carol"]);
+    let voter = fx.register_voter();
+
+    let err = fx.change_vote(&voter, fx.ballot(&[1], &[])).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::NotVoted));
+    let err = fx.revoke_vote(&voter).unwrap_err();
+    assert_eq!(err, vote_error(VoteError::NotVoted));
+
+    fx.vote(&voter, fx.ballot(&[0, 1], &[])).unwrap();
+    fx.change_vote(&voter, fx.ballot(&[2], &[])).unwrap();
+    assert_eq!([fx.candidate(0).votes, fx.candidate(1).votes, fx.candidate(2).votes], [0, 0, 1]);
+    assert_eq!(fx.rt.events::<VoteChanged>().len(), 1);
+
+    fx.revoke_vote(&voter).unwrap();
+    assert_eq!(fx.candidate(2).votes, 0);
+    assert!(!fx.voter(&voter).voted);
+    assert_eq!(fx.election().ballots_cast, 0);
+
+    // A revoked voter may vote again
+    fx.vote(&voter, fx.ballot(&[1], &[])).unwrap();
+    assert_eq!(fx.candidate(1).votes, 1);
+}
+
+#[test]
+fn test_approval_and_score_voting() {
+    let mut fx = Fixture::pending(1, 0, &["alice", "bob", "carol"]);
+    set_voting_method(&mut fx, VotingMethod::Approval);
+    let mut fx = fx.opened();
+    let voter = fx.register_voter();
+    fx.vote(&voter, fx.ballot(&[0, 1, 2], &[])).unwrap();
+    assert_eq!(fx.candidate(2).votes, 1);
+
+    let mut fx = Fixture::pending(1, 0, &["alice", "bob"]);
+    set_voting_method(&mut fx, VotingMethod::Score { max_score: 5 });
+    let mut fx = fx.opened();
+    let voter = fx.register_voter();
+    let too_high = Ballot { scores: vec![6], ..fx.ballot(&[0], &[]) };
+    assert_eq!(fx.vote(&voter, too_high).unwrap_err(), vote_error(VoteError::ScoreTooHigh));
+    let unscored = fx.ballot(&[0], &[]);
+    assert_eq!(fx.vote(&voter, unscored).unwrap_err(), vote_error(VoteError::InvalidScores));
+
+    fx.vote(&voter, Ballot { scores: vec![5, 2], ..fx.ballot(&[0, 1], &[]) }).unwrap();
+    assert_eq!(fx.candidate(0).score_sum, 5);
+    assert_eq!(fx.candidate(1).score_sum, 2);
+}
+
+fn set_voting_method(fx: &mut Fixture, method: VotingMethod) {
+    fx.rt
+        .process(&instruction(
+            vote_d_21::accounts::SetVotingMethod { election: fx.election, admin: fx.admin },
+            vote_d_21::instruction::SetVotingMethod { method },
+        ))
+        .unwrap();
+}
+
+#[test]
+fn test_revoking_more_than_tallied_underflows() {
+    let mut fx = Fixture::new(2, 0, &["alice"]);
+    let voter = fx.register_voter();
+    fx.vote(&voter, fx.ballot(&[0], &[])).unwrap();
+
+    // A tally that lost the ballot it is asked to retract
+    let key = fx.candidates[0];
+    let mut candidate = fx.candidate(0);
+    candidate.votes = 0;
+    let mut account = fx.rt.account(&key).unwrap();
+    candidate.try_serialize(&mut &mut account.data[..]).unwrap();
+    fx.rt.set_account(&key, account);
+
+    assert_eq!(fx.revoke_vote(&voter).unwrap_err(), vote_error(VoteError::VoteUnderflow));
+    assert!(fx.voter(&voter).voted);
+}
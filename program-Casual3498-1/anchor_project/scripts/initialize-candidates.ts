@@ -2,6 +2,12 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { VoteD21 } from "../target/types/vote_d_21";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { createHash } from "crypto";
+
+// Candidate PDAs are seeded with sha256 of the canonical name. toLowerCase
+// matches the program's Unicode case folding for the ASCII names used here.
+const nameSeed = (name: string) =>
+  createHash("sha256").update(name.trim().normalize("NFC").toLowerCase().normalize("NFC")).digest();
 
 async function main() {
   // Configure the client to use the local cluster
@@ -11,6 +17,30 @@ async function main() {
   const program = anchor.workspace.VoteD21 as Program<VoteD21>;
   
   const candidates = ["Alice", "Bob", "Charlie", "Diana"];
+
+  // Election to register candidates in: ELECTION_ID env var, defaults to 1
+  const electionId = new anchor.BN(process.env.ELECTION_ID ?? "1");
+  const [electionPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("election"), provider.wallet.publicKey.toBuffer(), electionId.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+
+  if (!(await provider.connection.getAccountInfo(electionPDA))) {
+    console.log(`Creating election ${electionId.toString()}: ${electionPDA.toBase58()}\n`);
+    // Voting window: VOTING_HOURS env var (default 24h) starting now
+    // D21 parameters: two positive votes, one negative vote
+    const now = Math.floor(Date.now() / 1000);
+    const hours = Number(process.env.VOTING_HOURS ?? "24");
+    await program.methods
+      .initializeElection(electionId, "Vote D-21", new anchor.BN(now), new anchor.BN(now + hours * 3600), 2, 1)
+      .accounts({
+        election: electionPDA,
+        creator: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods.openElection().accounts({ election: electionPDA }).rpc();
+  }
   
   console.log("Initializing candidates...\n");
   
@@ -18,7 +48,7 @@ async function main() {
     try {
       // Derive PDA for candidate
       const [candidatePDA, bump] = PublicKey.findProgramAddressSync(
-        [Buffer.from("candidate"), Buffer.from(candidateName)],
+        [Buffer.from("candidate"), electionPDA.toBuffer(), nameSeed(candidateName)],
         program.programId
       );
       
@@ -27,9 +57,11 @@ async function main() {
       
       // Initialize candidate
       const tx = await program.methods
-        .initializeCandidate(candidateName)
+        .initializeCandidate(candidateName, "", "")
         .accounts({
+          election: electionPDA,
           candidate: candidatePDA,
+          authority: provider.wallet.publicKey,
           payer: provider.wallet.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -2,6 +2,12 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { VoteD21 } from "../target/types/vote_d_21";
 import { expect } from "chai";
+import { createHash } from "crypto";
+
+// Candidate PDAs are seeded with sha256 of the canonical name. toLowerCase
+// matches the program's Unicode case folding for the ASCII names used here.
+const nameSeed = (name: string) =>
+  createHash("sha256").update(name.trim().normalize("NFC").toLowerCase().normalize("NFC")).digest();
 
 describe("vote_d_21", () => {
   // Configure the client to use the local cluster
@@ -11,6 +17,13 @@ describe("vote_d_21", () => {
   const program = anchor.workspace.VoteD21 as Program<VoteD21>;
   const wallet = provider.wallet as anchor.Wallet;
 
+  // Every run uses a fresh election so PDAs never collide with earlier runs
+  const electionId = new anchor.BN(Date.now());
+  const [electionPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("election"), wallet.publicKey.toBuffer(), electionId.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+
   // Candidate names
   const candidates = ["Alice", "Bob", "Charlie", "Diana"];
   const candidatePDAs: { name: string; pda: anchor.web3.PublicKey; bump: number }[] = [];
@@ -34,6 +47,34 @@ describe("vote_d_21", () => {
       2 * anchor.web3.LAMPORTS_PER_SOL
     );
     await provider.connection.confirmTransaction(airdropSig);
+
+    // Registration and voting read the program config. The provider wallet
+    // deployed the program, so it is the upgrade authority that creates it
+    const [configPDA] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+    if (!(await provider.connection.getAccountInfo(configPDA))) {
+      const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+      await program.methods
+        .initializeConfig(wallet.publicKey)
+        .accountsPartial({ config: configPDA, authority: wallet.publicKey, programData })
+        .rpc();
+    }
+
+    // Voting window: opened immediately, closes in one hour
+    // D21 parameters: two positive votes, one negative vote
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .initializeElection(electionId, "Vote D-21", new anchor.BN(now - 60), new anchor.BN(now + 3600), 2, 1)
+      .accounts({
+        election: electionPDA,
+        creator: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods.openElection().accounts({ election: electionPDA }).rpc();
+    console.log("Election:", electionPDA.toBase58());
   });
 
   describe("1. Initialize Candidates", () => {
@@ -42,7 +83,7 @@ describe("vote_d_21", () => {
       
       for (const name of candidates) {
         const [candidatePDA, bump] = anchor.web3.PublicKey.findProgramAddressSync(
-          [Buffer.from("candidate"), Buffer.from(name)],
+          [Buffer.from("candidate"), electionPDA.toBuffer(), nameSeed(name)],
           program.programId
         );
 
@@ -51,9 +92,11 @@ describe("vote_d_21", () => {
         console.log(`  Bump: ${bump}`);
 
         const tx = await program.methods
-          .initializeCandidate(name)
+          .initializeCandidate(name, "", "")
           .accounts({
+            election: electionPDA,
             candidate: candidatePDA,
+            authority: wallet.publicKey,
             payer: wallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
@@ -71,20 +114,25 @@ describe("vote_d_21", () => {
       }
 
       expect(candidatePDAs.length).to.equal(4);
+
+      const electionAccount = await program.account.election.fetch(electionPDA);
+      expect(electionAccount.candidates.length).to.equal(4);
     });
 
     it("Should fail to reinitialize an existing candidate", async () => {
       const name = "Alice";
       const [candidatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("candidate"), Buffer.from(name)],
+        [Buffer.from("candidate"), electionPDA.toBuffer(), nameSeed(name)],
         program.programId
       );
 
       try {
         await program.methods
-          .initializeCandidate(name)
+          .initializeCandidate(name, "", "")
           .accounts({
+            election: electionPDA,
             candidate: candidatePDA,
+            authority: wallet.publicKey,
             payer: wallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
@@ -96,6 +144,32 @@ describe("vote_d_21", () => {
         expect(err.toString()).to.include("already in use");
       }
     });
+
+    it("Should fail when a non-registrar registers a candidate", async () => {
+      const name = "Mallory";
+      const [candidatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("candidate"), electionPDA.toBuffer(), nameSeed(name)],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .initializeCandidate(name, "", "")
+          .accounts({
+            election: electionPDA,
+            candidate: candidatePDA,
+            authority: unauthorizedWallet.publicKey,
+            payer: unauthorizedWallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([unauthorizedWallet])
+          .rpc();
+
+        expect.fail("Should have thrown UnauthorizedAccess error");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAccess");
+      }
+    });
   });
 
   describe("2. Initialize Voter", () => {
@@ -103,7 +177,7 @@ describe("vote_d_21", () => {
       console.log("\n--- Initializing Voter ---");
 
       [voterPDA, voterBump] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("voter"), wallet.publicKey.toBuffer()],
+        [Buffer.from("voter"), electionPDA.toBuffer(), wallet.publicKey.toBuffer()],
         program.programId
       );
 
@@ -111,9 +185,12 @@ describe("vote_d_21", () => {
       console.log(`Voter Bump: ${voterBump}`);
 
       const tx = await program.methods
-        .initializeVoter()
+        .initializeVoter([])
         .accounts({
+          election: electionPDA,
           voter: voterPDA,
+          whitelistEntry: null,
+          sponsorBudget: null,
           authority: wallet.publicKey,
           payer: wallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -132,9 +209,12 @@ describe("vote_d_21", () => {
     it("Should fail to reinitialize an existing voter", async () => {
       try {
         await program.methods
-          .initializeVoter()
+          .initializeVoter([])
           .accounts({
+            election: electionPDA,
             voter: voterPDA,
+            whitelistEntry: null,
+            sponsorBudget: null,
             authority: wallet.publicKey,
             payer: wallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
@@ -166,13 +246,18 @@ describe("vote_d_21", () => {
       console.log(`Charlie votes before: ${charlieAccountBefore.votes.toNumber()}`);
 
       const tx = await program.methods
-        .vote([alice.pda, charlie.pda])
+        .vote({ positive: [alice.pda, charlie.pda], negative: [], scores: [] })
         .accounts({
+          election: electionPDA,
           voter: voterPDA,
-          candidate1: alice.pda,
-          candidate2: charlie.pda,
           authority: wallet.publicKey,
+          sponsorBudget: null,
+          relayer: null,
         })
+        .remainingAccounts([
+          { pubkey: alice.pda, isWritable: true, isSigner: false },
+          { pubkey: charlie.pda, isWritable: true, isSigner: false },
+        ])
         .rpc();
 
       console.log(`TX Signature: ${tx}`);
@@ -215,14 +300,17 @@ describe("vote_d_21", () => {
 
       // Initialize new voter
       const [newVoterPDA, newVoterBump] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("voter"), newVoter.publicKey.toBuffer()],
+        [Buffer.from("voter"), electionPDA.toBuffer(), newVoter.publicKey.toBuffer()],
         program.programId
       );
 
       await program.methods
-        .initializeVoter()
+        .initializeVoter([])
         .accounts({
+          election: electionPDA,
           voter: newVoterPDA,
+          whitelistEntry: null,
+          sponsorBudget: null,
           authority: newVoter.publicKey,
           payer: newVoter.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -235,13 +323,18 @@ describe("vote_d_21", () => {
 
       try {
         await program.methods
-          .vote([bob.pda, bob.pda])
+          .vote({ positive: [bob.pda, bob.pda], negative: [], scores: [] })
           .accounts({
+            election: electionPDA,
             voter: newVoterPDA,
-            candidate1: bob.pda,
-            candidate2: bob.pda,
             authority: newVoter.publicKey,
+            sponsorBudget: null,
+            relayer: null,
           })
+          .remainingAccounts([
+            { pubkey: bob.pda, isWritable: true, isSigner: false },
+            { pubkey: bob.pda, isWritable: true, isSigner: false },
+          ])
           .signers([newVoter])
           .rpc();
         
@@ -260,13 +353,18 @@ describe("vote_d_21", () => {
 
       try {
         await program.methods
-          .vote([bob.pda, diana.pda])
+          .vote({ positive: [bob.pda, diana.pda], negative: [], scores: [] })
           .accounts({
+            election: electionPDA,
             voter: voterPDA,
-            candidate1: bob.pda,
-            candidate2: diana.pda,
             authority: wallet.publicKey,
+            sponsorBudget: null,
+            relayer: null,
           })
+          .remainingAccounts([
+            { pubkey: bob.pda, isWritable: true, isSigner: false },
+            { pubkey: diana.pda, isWritable: true, isSigner: false },
+          ])
           .rpc();
         
         expect.fail("Should have thrown AlreadyVoted error");
@@ -281,14 +379,17 @@ describe("vote_d_21", () => {
 
       // Initialize a voter for another authority but try to vote with unauthorized wallet
       const [unauthorizedVoterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("voter"), unauthorizedWallet.publicKey.toBuffer()],
+        [Buffer.from("voter"), electionPDA.toBuffer(), unauthorizedWallet.publicKey.toBuffer()],
         program.programId
       );
 
       await program.methods
-        .initializeVoter()
+        .initializeVoter([])
         .accounts({
+          election: electionPDA,
           voter: unauthorizedVoterPDA,
+          whitelistEntry: null,
+          sponsorBudget: null,
           authority: unauthorizedWallet.publicKey,
           payer: unauthorizedWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -302,13 +403,18 @@ describe("vote_d_21", () => {
       try {
         // Try to vote with wallet.publicKey instead of unauthorizedWallet
         await program.methods
-          .vote([bob.pda, diana.pda])
+          .vote({ positive: [bob.pda, diana.pda], negative: [], scores: [] })
           .accounts({
+            election: electionPDA,
             voter: unauthorizedVoterPDA,
-            candidate1: bob.pda,
-            candidate2: diana.pda,
             authority: wallet.publicKey, // Wrong authority!
+            sponsorBudget: null,
+            relayer: null,
           })
+          .remainingAccounts([
+            { pubkey: bob.pda, isWritable: true, isSigner: false },
+            { pubkey: diana.pda, isWritable: true, isSigner: false },
+          ])
           .rpc();
         
         expect.fail("Should have thrown UnauthorizedAccess error");
@@ -337,14 +443,17 @@ describe("vote_d_21", () => {
       await provider.connection.confirmTransaction(airdropSig);
 
       const [testVoterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("voter"), testVoter.publicKey.toBuffer()],
+        [Buffer.from("voter"), electionPDA.toBuffer(), testVoter.publicKey.toBuffer()],
         program.programId
       );
 
       await program.methods
-        .initializeVoter()
+        .initializeVoter([])
         .accounts({
+          election: electionPDA,
           voter: testVoterPDA,
+          whitelistEntry: null,
+          sponsorBudget: null,
           authority: testVoter.publicKey,
           payer: testVoter.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -359,13 +468,18 @@ describe("vote_d_21", () => {
       try {
         // Pass Alice's PDA in keys but Bob's PDA in accounts
         await program.methods
-          .vote([alice.pda, charlie.pda])
+          .vote({ positive: [alice.pda, charlie.pda], negative: [], scores: [] })
           .accounts({
+            election: electionPDA,
             voter: testVoterPDA,
-            candidate1: bob.pda,  // Mismatch!
-            candidate2: charlie.pda,
             authority: testVoter.publicKey,
+            sponsorBudget: null,
+            relayer: null,
           })
+          .remainingAccounts([
+            { pubkey: bob.pda, isWritable: true, isSigner: false }, // Mismatch!
+            { pubkey: charlie.pda, isWritable: true, isSigner: false },
+          ])
           .signers([testVoter])
           .rpc();
         
@@ -377,7 +491,70 @@ describe("vote_d_21", () => {
     });
   });
 
-  describe("5. Final State Verification", () => {
+  describe("5. Change and Revoke Vote", () => {
+    const writable = (pubkey: anchor.web3.PublicKey) => ({ pubkey, isWritable: true, isSigner: false });
+
+    it("Should move a vote from Charlie to Bob", async () => {
+      const alice = candidatePDAs.find(c => c.name === "Alice");
+      const bob = candidatePDAs.find(c => c.name === "Bob");
+      const charlie = candidatePDAs.find(c => c.name === "Charlie");
+
+      const bobBefore = await program.account.candidateAccount.fetch(bob.pda);
+      const charlieBefore = await program.account.candidateAccount.fetch(charlie.pda);
+
+      // Previous ballot's candidates first, then the ones only on the new ballot
+      await program.methods
+        .changeVote({ positive: [alice.pda, bob.pda], negative: [], scores: [] })
+        .accounts({ election: electionPDA, voter: voterPDA, authority: wallet.publicKey })
+        .remainingAccounts([writable(alice.pda), writable(charlie.pda), writable(bob.pda)])
+        .rpc();
+
+      const bobAfter = await program.account.candidateAccount.fetch(bob.pda);
+      const charlieAfter = await program.account.candidateAccount.fetch(charlie.pda);
+      expect(bobAfter.votes.toNumber()).to.equal(bobBefore.votes.toNumber() + 1);
+      expect(charlieAfter.votes.toNumber()).to.equal(charlieBefore.votes.toNumber() - 1);
+
+      const voterAccount = await program.account.voterAccount.fetch(voterPDA);
+      expect(voterAccount.votes[1].toBase58()).to.equal(bob.pda.toBase58());
+    });
+
+    it("Should revoke the ballot and allow voting again", async () => {
+      const alice = candidatePDAs.find(c => c.name === "Alice");
+      const bob = candidatePDAs.find(c => c.name === "Bob");
+      const charlie = candidatePDAs.find(c => c.name === "Charlie");
+
+      await program.methods
+        .revokeVote()
+        .accounts({ election: electionPDA, voter: voterPDA, authority: wallet.publicKey })
+        .remainingAccounts([writable(alice.pda), writable(bob.pda)])
+        .rpc();
+
+      let voterAccount = await program.account.voterAccount.fetch(voterPDA);
+      expect(voterAccount.voted).to.be.false;
+      expect(voterAccount.votes).to.be.empty;
+
+      try {
+        await program.methods
+          .revokeVote()
+          .accounts({ election: electionPDA, voter: voterPDA, authority: wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown NotVoted error");
+      } catch (err) {
+        expect(err.toString()).to.include("NotVoted");
+      }
+
+      await program.methods
+        .vote({ positive: [alice.pda, charlie.pda], negative: [], scores: [] })
+        .accounts({ election: electionPDA, voter: voterPDA, authority: wallet.publicKey, sponsorBudget: null, relayer: null })
+        .remainingAccounts([writable(alice.pda), writable(charlie.pda)])
+        .rpc();
+
+      voterAccount = await program.account.voterAccount.fetch(voterPDA);
+      expect(voterAccount.voted).to.be.true;
+    });
+  });
+
+  describe("6. Final State Verification", () => {
     it("Should display final vote counts for all candidates", async () => {
       console.log("\n========== FINAL RESULTS ==========");
       